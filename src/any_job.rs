@@ -0,0 +1,277 @@
+use crate::job::*;
+use std::fmt;
+
+/// Type-erased job
+///
+/// A job whose state is only known at runtime, one variant per typestate. Transitions go through the `From` implementations of `Job<S>`, so only the edges of the automaton are allowed.
+///
+/// # Example :
+/// ```rust, ignore
+///# use filasse::any_job::*;
+///# use filasse::job::*;
+/// let foo = AnyJob::from(Job::new(5, 2, 2, 1));
+/// let foo = foo.transition(JobState::Ready).unwrap();
+/// let bar: Job<Running> = foo.try_into_running().unwrap();
+///```
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum AnyJob {
+    New(Job<New>),
+    Ready(Job<Ready>),
+    Running(Job<Running>),
+    Blocked(Job<Blocked>),
+    Stopped(Job<Stopped>),
+    SuspendedReady(Job<SuspendedReady>),
+    SuspendedBlocked(Job<SuspendedBlocked>),
+    Zombie(Job<Zombie>),
+    Terminated(Job<Terminated>),
+}
+
+/// Illegal transition
+///
+/// The job is given back unchanged with the state it was asked to go to.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct TransitionError {
+    pub job: AnyJob,
+    pub to: JobState,
+}
+
+impl fmt::Display for TransitionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "illegal transition of job {} from {} to {}",
+            self.job.pid(),
+            self.job.state(),
+            self.to
+        )
+    }
+}
+
+impl std::error::Error for TransitionError {}
+
+impl AnyJob {
+    /// Getter pid
+    pub fn pid(&self) -> u64 {
+        match self {
+            AnyJob::New(job) => job.pid,
+            AnyJob::Ready(job) => job.pid,
+            AnyJob::Running(job) => job.pid,
+            AnyJob::Blocked(job) => job.pid,
+            AnyJob::Stopped(job) => job.pid,
+            AnyJob::SuspendedReady(job) => job.pid,
+            AnyJob::SuspendedBlocked(job) => job.pid,
+            AnyJob::Zombie(job) => job.pid,
+            AnyJob::Terminated(job) => job.pid,
+        }
+    }
+
+    /// Getter parent
+    pub fn parent(&self) -> u64 {
+        match self {
+            AnyJob::New(job) => job.parent,
+            AnyJob::Ready(job) => job.parent,
+            AnyJob::Running(job) => job.parent,
+            AnyJob::Blocked(job) => job.parent,
+            AnyJob::Stopped(job) => job.parent,
+            AnyJob::SuspendedReady(job) => job.parent,
+            AnyJob::SuspendedBlocked(job) => job.parent,
+            AnyJob::Zombie(job) => job.parent,
+            AnyJob::Terminated(job) => job.parent,
+        }
+    }
+
+    /// Getter state
+    pub fn state(&self) -> JobState {
+        match self {
+            AnyJob::New(_) => JobState::New,
+            AnyJob::Ready(_) => JobState::Ready,
+            AnyJob::Running(_) => JobState::Running,
+            AnyJob::Blocked(_) => JobState::Blocked,
+            AnyJob::Stopped(_) => JobState::Stopped,
+            AnyJob::SuspendedReady(_) => JobState::SuspendedReady,
+            AnyJob::SuspendedBlocked(_) => JobState::SuspendedBlocked,
+            AnyJob::Zombie(_) => JobState::Zombie,
+            AnyJob::Terminated(_) => JobState::Terminated,
+        }
+    }
+
+    /// Allowed transition
+    ///
+    /// Returns true if the automaton has an edge from the current state to `to`.
+    pub fn can_become(&self, to: JobState) -> bool {
+        self.transition(to).is_ok()
+    }
+
+    /// Runtime transition
+    ///
+    /// # Example :
+    /// ```rust, ignore
+    ///# use filasse::any_job::*;
+    ///# use filasse::job::*;
+    /// let foo = AnyJob::from(Job::new(5, 2, 2, 1));
+    /// assert!(foo.transition(JobState::Running).is_err());
+    ///```
+    pub fn transition(self, to: JobState) -> Result<AnyJob, TransitionError> {
+        match to {
+            JobState::New => Err(TransitionError { job: self, to }),
+            JobState::Ready => self.try_into_ready().map(AnyJob::from),
+            JobState::Running => self.try_into_running().map(AnyJob::from),
+            JobState::Blocked => self.try_into_blocked().map(AnyJob::from),
+            JobState::Stopped => self.try_into_stopped().map(AnyJob::from),
+            JobState::SuspendedReady => self.try_into_suspended_ready().map(AnyJob::from),
+            JobState::SuspendedBlocked => self.try_into_suspended_blocked().map(AnyJob::from),
+            JobState::Zombie => self.try_into_zombie().map(AnyJob::from),
+            JobState::Terminated => self.try_into_terminated().map(AnyJob::from),
+        }
+    }
+
+    pub fn try_into_ready(self) -> Result<Job<Ready>, TransitionError> {
+        match self {
+            AnyJob::New(job) => Ok(Job::from(job)),
+            AnyJob::Running(job) => Ok(Job::from(job)),
+            AnyJob::Blocked(job) => Ok(Job::from(job)),
+            AnyJob::Stopped(job) => Ok(Job::from(job)),
+            AnyJob::SuspendedReady(job) => Ok(Job::from(job)),
+            job => Err(TransitionError {
+                job,
+                to: JobState::Ready,
+            }),
+        }
+    }
+
+    pub fn try_into_running(self) -> Result<Job<Running>, TransitionError> {
+        match self {
+            AnyJob::Ready(job) => Ok(Job::from(job)),
+            job => Err(TransitionError {
+                job,
+                to: JobState::Running,
+            }),
+        }
+    }
+
+    pub fn try_into_blocked(self) -> Result<Job<Blocked>, TransitionError> {
+        match self {
+            AnyJob::Running(job) => Ok(Job::from(job)),
+            AnyJob::SuspendedBlocked(job) => Ok(Job::from(job)),
+            job => Err(TransitionError {
+                job,
+                to: JobState::Blocked,
+            }),
+        }
+    }
+
+    pub fn try_into_stopped(self) -> Result<Job<Stopped>, TransitionError> {
+        match self {
+            AnyJob::Ready(job) => Ok(Job::from(job)),
+            AnyJob::Running(job) => Ok(Job::from(job)),
+            AnyJob::Blocked(job) => Ok(Job::from(job)),
+            job => Err(TransitionError {
+                job,
+                to: JobState::Stopped,
+            }),
+        }
+    }
+
+    pub fn try_into_suspended_ready(self) -> Result<Job<SuspendedReady>, TransitionError> {
+        match self {
+            AnyJob::New(job) => Ok(Job::from(job)),
+            AnyJob::Ready(job) => Ok(Job::from(job)),
+            AnyJob::Running(job) => Ok(Job::from(job)),
+            AnyJob::SuspendedBlocked(job) => Ok(Job::from(job)),
+            job => Err(TransitionError {
+                job,
+                to: JobState::SuspendedReady,
+            }),
+        }
+    }
+
+    pub fn try_into_suspended_blocked(self) -> Result<Job<SuspendedBlocked>, TransitionError> {
+        match self {
+            AnyJob::Blocked(job) => Ok(Job::from(job)),
+            job => Err(TransitionError {
+                job,
+                to: JobState::SuspendedBlocked,
+            }),
+        }
+    }
+
+    pub fn try_into_zombie(self) -> Result<Job<Zombie>, TransitionError> {
+        match self {
+            AnyJob::New(job) => Ok(Job::from(job)),
+            AnyJob::Ready(job) => Ok(Job::from(job)),
+            AnyJob::Running(job) => Ok(Job::from(job)),
+            AnyJob::Blocked(job) => Ok(Job::from(job)),
+            AnyJob::Stopped(job) => Ok(Job::from(job)),
+            AnyJob::SuspendedReady(job) => Ok(Job::from(job)),
+            AnyJob::SuspendedBlocked(job) => Ok(Job::from(job)),
+            job => Err(TransitionError {
+                job,
+                to: JobState::Zombie,
+            }),
+        }
+    }
+
+    pub fn try_into_terminated(self) -> Result<Job<Terminated>, TransitionError> {
+        match self {
+            AnyJob::Zombie(job) => Ok(Job::from(job)),
+            job => Err(TransitionError {
+                job,
+                to: JobState::Terminated,
+            }),
+        }
+    }
+}
+
+impl From<Job<New>> for AnyJob {
+    fn from(job: Job<New>) -> AnyJob {
+        AnyJob::New(job)
+    }
+}
+
+impl From<Job<Ready>> for AnyJob {
+    fn from(job: Job<Ready>) -> AnyJob {
+        AnyJob::Ready(job)
+    }
+}
+
+impl From<Job<Running>> for AnyJob {
+    fn from(job: Job<Running>) -> AnyJob {
+        AnyJob::Running(job)
+    }
+}
+
+impl From<Job<Blocked>> for AnyJob {
+    fn from(job: Job<Blocked>) -> AnyJob {
+        AnyJob::Blocked(job)
+    }
+}
+
+impl From<Job<Stopped>> for AnyJob {
+    fn from(job: Job<Stopped>) -> AnyJob {
+        AnyJob::Stopped(job)
+    }
+}
+
+impl From<Job<SuspendedReady>> for AnyJob {
+    fn from(job: Job<SuspendedReady>) -> AnyJob {
+        AnyJob::SuspendedReady(job)
+    }
+}
+
+impl From<Job<SuspendedBlocked>> for AnyJob {
+    fn from(job: Job<SuspendedBlocked>) -> AnyJob {
+        AnyJob::SuspendedBlocked(job)
+    }
+}
+
+impl From<Job<Zombie>> for AnyJob {
+    fn from(job: Job<Zombie>) -> AnyJob {
+        AnyJob::Zombie(job)
+    }
+}
+
+impl From<Job<Terminated>> for AnyJob {
+    fn from(job: Job<Terminated>) -> AnyJob {
+        AnyJob::Terminated(job)
+    }
+}
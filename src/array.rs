@@ -0,0 +1,203 @@
+use std::fmt;
+
+/// Maximum number of indices of a specification
+///
+/// A guard against expanding huge ranges, the largest MaxArraySize of Slurm. The size of the arrays is limited by the scheduler.
+pub const MAX_ARRAY_INDICES: usize = 4_000_001;
+
+/// Errors of an array specification
+///
+/// * `InvalidSpec` : the specification can not be parsed, or its throttle is zero.
+/// * `Empty` : the specification has no index.
+/// * `TooLarge` : the specification has more than `MAX_ARRAY_INDICES` distinct indices.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArrayError {
+    InvalidSpec(String),
+    Empty,
+    TooLarge(usize),
+}
+
+impl fmt::Display for ArrayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArrayError::InvalidSpec(spec) => write!(f, "invalid array specification {}", spec),
+            ArrayError::Empty => write!(f, "empty array"),
+            ArrayError::TooLarge(max) => write!(f, "array larger than {} indices", max),
+        }
+    }
+}
+
+impl std::error::Error for ArrayError {}
+
+/// Specification of a job array
+///
+/// The indices of the tasks, the maximum number of tasks active at once and the command line of a task, where `%a` is replaced by the index of the task, `%A` by the id of the array and `%%` by `%`.
+///
+/// # Example :
+/// ```rust, ignore
+///# use filasse::array::*;
+/// let spec = ArraySpec::parse("0-15:4%2").unwrap().command("./sweep --seed %a");
+/// assert!(spec.indices == vec![0, 4, 8, 12]);
+/// assert!(spec.throttle == Some(2));
+///```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArraySpec {
+    pub indices: Vec<u32>,
+    pub throttle: Option<usize>,
+    pub command: Option<String>,
+}
+
+impl ArraySpec {
+    /// Initialisation method
+    pub fn new(indices: Vec<u32>) -> Self {
+        ArraySpec {
+            indices,
+            throttle: None,
+            command: None,
+        }
+    }
+
+    /// Parse a specification
+    ///
+    /// The syntax of Slurm : comma separated indices or ranges `first-last`, a range may have a step `first-last:step`, and the whole may end with a throttle `%max` of at least 1. At most `MAX_ARRAY_INDICES` distinct indices are expanded.
+    pub fn parse(spec: &str) -> Result<Self, ArrayError> {
+        let invalid = || ArrayError::InvalidSpec(spec.to_string());
+        let (list, throttle) = match spec.split_once('%') {
+            Some((list, max)) => {
+                let max = max.trim().parse::<usize>().map_err(|_| invalid())?;
+                if max == 0 {
+                    return Err(invalid());
+                }
+                (list, Some(max))
+            }
+            None => (spec, None),
+        };
+        let mut indices = Vec::new();
+        for part in list.split(',').map(str::trim) {
+            let (range, step) = match part.split_once(':') {
+                Some((range, step)) => (range, step.parse::<u32>().map_err(|_| invalid())?),
+                None => (part, 1),
+            };
+            let (first, last) = match range.split_once('-') {
+                Some((first, last)) => (
+                    first.parse::<u32>().map_err(|_| invalid())?,
+                    last.parse::<u32>().map_err(|_| invalid())?,
+                ),
+                None => {
+                    let index = range.parse::<u32>().map_err(|_| invalid())?;
+                    (index, index)
+                }
+            };
+            if step == 0 || last < first {
+                return Err(invalid());
+            }
+            if ((last - first) / step) as usize + 1 > MAX_ARRAY_INDICES {
+                return Err(ArrayError::TooLarge(MAX_ARRAY_INDICES));
+            }
+            indices.extend((first..=last).step_by(step as usize));
+            indices.sort_unstable();
+            indices.dedup();
+            if indices.len() > MAX_ARRAY_INDICES {
+                return Err(ArrayError::TooLarge(MAX_ARRAY_INDICES));
+            }
+        }
+        if indices.is_empty() {
+            return Err(ArrayError::Empty);
+        }
+        Ok(ArraySpec {
+            indices,
+            throttle,
+            command: None,
+        })
+    }
+
+    pub fn throttle(mut self, max: usize) -> Self {
+        self.throttle = Some(max);
+        self
+    }
+
+    pub fn command(mut self, template: &str) -> Self {
+        self.command = Some(template.to_string());
+        self
+    }
+}
+
+/// Job array
+///
+/// One logical job made of indexed tasks with consecutive pids, the id of the array being the pid of its first task.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct JobArray {
+    pub id: u64,
+    pub indices: Vec<u32>,
+    pub throttle: Option<usize>,
+    pub command: Option<String>,
+}
+
+impl JobArray {
+    /// Number of tasks
+    pub fn len(&self) -> usize {
+        self.indices.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.indices.is_empty()
+    }
+
+    /// Pids of the tasks
+    pub fn pids(&self) -> std::ops::Range<u64> {
+        self.id..self.id + self.indices.len() as u64
+    }
+
+    /// Pid of a task
+    pub fn pid(&self, index: u32) -> Option<u64> {
+        let position = self.indices.iter().position(|&i| i == index)?;
+        Some(self.id + position as u64)
+    }
+
+    /// Index of a task
+    pub fn index(&self, pid: u64) -> Option<u32> {
+        let position = pid.checked_sub(self.id)?;
+        self.indices.get(position as usize).copied()
+    }
+
+    /// Command line of a task
+    ///
+    /// The command template with the index of the task substituted. Returns `None` without template or for an unknown index.
+    pub fn command_line(&self, index: u32) -> Option<String> {
+        self.pid(index)?;
+        let template = self.command.as_ref()?;
+        Some(substitute(template, self.id, index))
+    }
+}
+
+/// Index substitution
+///
+/// Replace `%a` by `index`, `%A` by `id` and `%%` by `%` in `template`.
+///
+/// # Example :
+/// ```rust, ignore
+///# use filasse::array::*;
+/// assert!(substitute("out_%A_%a.log", 7, 3) == "out_7_3.log");
+///```
+pub fn substitute(template: &str, id: u64, index: u32) -> String {
+    let mut result = String::with_capacity(template.len());
+    let mut chars = template.chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('a') => result.push_str(&index.to_string()),
+            Some('A') => result.push_str(&id.to_string()),
+            Some('%') => result.push('%'),
+            Some(other) => {
+                result.push('%');
+                result.push(other);
+            }
+            None => result.push('%'),
+        }
+    }
+    result
+}
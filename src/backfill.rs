@@ -0,0 +1,202 @@
+use crate::batch::*;
+use crate::cluster::*;
+
+/// Backfilling policies
+///
+/// * `None` : plain first come, first served, the first job which does not fit blocks the queue.
+/// * `Easy` : only the first blocked job holds a reservation, later jobs may start now if they do not delay it.
+/// * `Conservative` : every blocked job holds a reservation, a job may start now only if it delays none of them.
+///
+/// The requested walltime is the runtime estimate of every job. A job started or reserved by backfilling holds its resources until its KILL, the grace period after its walltime.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum Backfill {
+    #[default]
+    None,
+    Easy,
+    Conservative,
+}
+
+/// Slot of a profile
+///
+/// An allocation held from `start` to `end`, either by a running job or as a reservation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Slot {
+    pub start: u64,
+    pub end: u64,
+    pub allocation: Allocation,
+}
+
+/// Availability profile
+///
+/// The resources held on each node of a cluster over time.
+///
+/// # Example :
+/// ```rust, ignore
+///# use filasse::backfill::*;
+///# use filasse::cluster::*;
+/// let cluster = Cluster::homogeneous(2, 4, 8);
+/// let mut profile = Profile::new(&cluster);
+/// let nodes = cluster.partition_nodes(None);
+/// let (start, allocation) = profile.earliest(&Resources::new(2, 4, 8), &nodes, 0, 10).unwrap();
+/// profile.reserve(Slot { start, end: start + 10, allocation });
+///```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Profile {
+    capacity: Vec<(u32, u64)>,
+    slots: Vec<Slot>,
+}
+
+impl Profile {
+    /// Initialisation method
+    ///
+    /// An empty profile with the capacity of the nodes of `cluster`.
+    pub fn new(cluster: &Cluster) -> Self {
+        Profile {
+            capacity: cluster
+                .nodes()
+                .iter()
+                .map(|node| (node.cores, node.memory))
+                .collect(),
+            slots: Vec::new(),
+        }
+    }
+
+    /// Getter slots
+    pub fn slots(&self) -> &[Slot] {
+        &self.slots
+    }
+
+    /// Hold resources
+    pub fn reserve(&mut self, slot: Slot) {
+        self.slots.push(slot);
+    }
+
+    /// Free resources of a node
+    ///
+    /// The cores and memory free on `node` during the whole interval from `start` to `end`.
+    pub fn free(&self, node: usize, start: u64, end: u64) -> (u32, u64) {
+        let (cores, memory) = self.capacity[node];
+        let held = self
+            .slots
+            .iter()
+            .filter(|slot| slot.allocation.nodes.contains(&node));
+        // Usage only grows when a slot starts, so its maximum is at `start` or at the start of a slot.
+        let points = std::iter::once(start).chain(
+            held.clone()
+                .map(|slot| slot.start)
+                .filter(|&t| start < t && t < end),
+        );
+        let (used_cores, used_memory) = points
+            .map(|t| {
+                held.clone()
+                    .filter(|slot| slot.start <= t && t < slot.end)
+                    .fold((0u32, 0u64), |(c, m), slot| {
+                        (
+                            c.saturating_add(slot.allocation.cores),
+                            m.saturating_add(slot.allocation.memory),
+                        )
+                    })
+            })
+            .fold((0, 0), |(c, m), (uc, um)| (c.max(uc), m.max(um)));
+        (
+            cores.saturating_sub(used_cores),
+            memory.saturating_sub(used_memory),
+        )
+    }
+
+    /// Earliest start
+    ///
+    /// The earliest time from `now` at which `resources` are free on `nodes` during `duration`, and the nodes to take. Returns `None` if the request never fits.
+    pub fn earliest(
+        &self,
+        resources: &Resources,
+        nodes: &[usize],
+        now: u64,
+        duration: u64,
+    ) -> Option<(u64, Allocation)> {
+        let duration = duration.max(1);
+        let mut times: Vec<u64> = std::iter::once(now)
+            .chain(self.slots.iter().map(|slot| slot.end).filter(|&t| t > now))
+            .collect();
+        times.sort_unstable();
+        times.dedup();
+        times.into_iter().find_map(|start| {
+            let chosen: Vec<usize> = nodes
+                .iter()
+                .copied()
+                .filter(|&node| {
+                    let (cores, memory) = self.free(node, start, start + duration);
+                    cores >= resources.cores && memory >= resources.memory
+                })
+                .take(resources.nodes as usize)
+                .collect();
+            (chosen.len() == resources.nodes as usize).then_some((
+                start,
+                Allocation {
+                    nodes: chosen,
+                    cores: resources.cores,
+                    memory: resources.memory,
+                },
+            ))
+        })
+    }
+}
+
+/// Report of a backfilling run
+///
+/// The same workload run with a backfilling policy and with plain FCFS.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct BackfillReport {
+    pub backfilled: usize,
+    pub makespan: u64,
+    pub utilization: f64,
+    pub fcfs_makespan: u64,
+    pub fcfs_utilization: f64,
+}
+
+impl BackfillReport {
+    /// Utilization gain
+    ///
+    /// The utilization of the policy minus the utilization of plain FCFS.
+    pub fn gain(&self) -> f64 {
+        self.utilization - self.fcfs_utilization
+    }
+}
+
+/// Compare with FCFS
+///
+/// Run the jobs on two copies of the cluster, with `policy` and without backfilling.
+///
+/// # Example :
+/// ```rust, ignore
+///# use filasse::backfill::*;
+///# use filasse::batch::*;
+///# use filasse::cluster::*;
+/// let jobs = vec![BatchJob::new(2, 4, 8, 10), BatchJob::new(1, 4, 8, 5)];
+/// let report = compare(&Cluster::homogeneous(2, 4, 8), &jobs, Backfill::Easy).unwrap();
+/// println!("{} backfilled, gain {}", report.backfilled, report.gain());
+///```
+pub fn compare(
+    cluster: &Cluster,
+    jobs: &[BatchJob],
+    policy: Backfill,
+) -> Result<BackfillReport, BatchError> {
+    let run = |policy: Backfill| -> Result<BatchScheduler, BatchError> {
+        let mut batch = BatchScheduler::new(cluster.clone());
+        batch.set_backfill(policy);
+        for job in jobs.iter() {
+            batch.submit(job.clone())?;
+        }
+        batch.run();
+        Ok(batch)
+    };
+    let backfill = run(policy)?;
+    let fcfs = run(Backfill::None)?;
+    Ok(BackfillReport {
+        backfilled: backfill.backfilled(),
+        makespan: backfill.makespan(),
+        utilization: backfill.utilization(),
+        fcfs_makespan: fcfs.makespan(),
+        fcfs_utilization: fcfs.utilization(),
+    })
+}
@@ -0,0 +1,1109 @@
+use crate::backfill::*;
+use crate::cluster::*;
+use crate::fairshare::*;
+use crate::job::*;
+use crate::metrics::*;
+use crate::preemption::*;
+use crate::priority::*;
+use crate::reservation::*;
+use crate::scheduler::DEFAULT_GRACE;
+use std::collections::{HashSet, VecDeque};
+use std::fmt;
+
+/// Errors of the batch scheduler
+///
+/// * `UnknownJob` : no job with this id is pending in the batch scheduler.
+/// * `UnknownNode` : no node with this id is in the cluster.
+/// * `UnknownPartition` : no partition with this name is in the cluster.
+/// * `Unsatisfiable` : the job asks for more than its partition has, even empty.
+/// * `UnknownReservation` : no reservation with this id is in the calendar.
+/// * `EmptyWindow` : a reservation must end after it starts.
+/// * `ReservationConflict` : the resources are already held by this reservation during the window.
+/// * `InvalidDependency` : a job can not depend on this id, 0 or its own.
+/// * `CyclicDependency` : the dependencies of the submitted job form a cycle through this job.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BatchError {
+    UnknownJob(u64),
+    UnknownNode(usize),
+    UnknownPartition(String),
+    Unsatisfiable(Resources),
+    UnknownReservation(u64),
+    EmptyWindow { start: u64, end: u64 },
+    ReservationConflict(u64),
+    InvalidDependency(u64),
+    CyclicDependency(u64),
+}
+
+impl fmt::Display for BatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BatchError::UnknownJob(id) => write!(f, "unknown batch job {}", id),
+            BatchError::UnknownNode(id) => write!(f, "unknown node {}", id),
+            BatchError::UnknownPartition(name) => write!(f, "unknown partition {}", name),
+            BatchError::Unsatisfiable(resources) => write!(
+                f,
+                "{} nodes with {} cores and {} memory can never be allocated",
+                resources.nodes, resources.cores, resources.memory
+            ),
+            BatchError::UnknownReservation(id) => write!(f, "unknown reservation {}", id),
+            BatchError::EmptyWindow { start, end } => {
+                write!(f, "empty reservation window from {} to {}", start, end)
+            }
+            BatchError::ReservationConflict(id) => {
+                write!(f, "resources already held by reservation {}", id)
+            }
+            BatchError::InvalidDependency(id) => write!(f, "invalid dependency on job {}", id),
+            BatchError::CyclicDependency(id) => write!(f, "cyclic dependency through job {}", id),
+        }
+    }
+}
+
+impl std::error::Error for BatchError {}
+
+/// Rigid parallel job
+///
+/// A job which needs all its resources at once, from its start to its end. `walltime` is the limit given by the user, `runtime` the time the job would really run, the walltime by default. A job whose runtime exceeds its walltime is killed. `dependencies` are the ids of the jobs which must complete before it is queued.
+///
+/// # Example :
+/// ```rust, ignore
+///# use filasse::batch::*;
+/// let job = BatchJob::new(2, 16, 64, 3600).runtime(1800).partition("compute").user("alice").qos("high").after(1);
+///```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchJob {
+    pub id: u64,
+    pub resources: Resources,
+    pub walltime: u64,
+    pub runtime: u64,
+    pub submit: u64,
+    pub partition: Option<String>,
+    pub user: Option<String>,
+    pub account: Option<String>,
+    pub qos: Option<String>,
+    pub dependencies: Vec<u64>,
+}
+
+impl BatchJob {
+    /// Initialisation method
+    ///
+    /// `nodes` nodes with `cores` cores and `memory` memory on each, for at most `walltime`. The id is given at submission.
+    pub fn new(nodes: u32, cores: u32, memory: u64, walltime: u64) -> Self {
+        BatchJob {
+            id: 0,
+            resources: Resources::new(nodes, cores, memory),
+            walltime,
+            runtime: walltime,
+            submit: 0,
+            partition: None,
+            user: None,
+            account: None,
+            qos: None,
+            dependencies: Vec::new(),
+        }
+    }
+
+    pub fn runtime(mut self, runtime: u64) -> Self {
+        self.runtime = runtime;
+        self
+    }
+
+    pub fn submit(mut self, submit: u64) -> Self {
+        self.submit = submit;
+        self
+    }
+
+    pub fn partition(mut self, name: &str) -> Self {
+        self.partition = Some(name.to_string());
+        self
+    }
+
+    pub fn user(mut self, name: &str) -> Self {
+        self.user = Some(name.to_string());
+        self
+    }
+
+    /// Account
+    ///
+    /// Only grants access to the reservations of the account, the usage being always charged to the user in the fair-share tree. Without it, the account of the user in the tree is used.
+    pub fn account(mut self, name: &str) -> Self {
+        self.account = Some(name.to_string());
+        self
+    }
+
+    pub fn qos(mut self, name: &str) -> Self {
+        self.qos = Some(name.to_string());
+        self
+    }
+
+    /// Dependency
+    ///
+    /// The job waits for the job `id` to complete, and is cancelled if it ends otherwise.
+    pub fn after(mut self, id: u64) -> Self {
+        self.dependencies.push(id);
+        self
+    }
+}
+
+/// Job running on the cluster
+///
+/// `kill` is the date of the KILL signal, the grace period after the walltime. `suspended` is the time the job spent suspended since its start.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Execution {
+    pub job: BatchJob,
+    pub allocation: Allocation,
+    pub start: u64,
+    pub kill: u64,
+    pub suspended: u64,
+}
+
+impl Execution {
+    /// End time
+    ///
+    /// A job exceeding its walltime receives TERM and is assumed to use the grace period to exit, so it ends at the earliest of its runtime and its KILL.
+    pub fn end(&self) -> u64 {
+        (self.start + self.suspended + self.job.runtime).min(self.kill)
+    }
+
+    /// Time the job has run at `now`, suspensions excluded
+    pub fn run_time(&self, now: u64) -> u64 {
+        now.saturating_sub(self.start + self.suspended)
+    }
+
+    /// Timeout
+    ///
+    /// Returns true if the job runs past its walltime.
+    pub fn timed_out(&self) -> bool {
+        self.job.runtime > self.job.walltime
+    }
+}
+
+/// Accounting record of a finished job
+///
+/// `suspended` is the time the job spent suspended between its start and its end.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record {
+    pub id: u64,
+    pub resources: Resources,
+    pub submit: u64,
+    pub start: u64,
+    pub end: u64,
+    pub exit: ExitReason,
+    pub suspended: u64,
+}
+
+impl Record {
+    /// Time spent running, suspensions excluded
+    pub fn run_time(&self) -> u64 {
+        self.end - self.start - self.suspended
+    }
+
+    /// Time spent pending
+    pub fn wait(&self) -> u64 {
+        self.start - self.submit
+    }
+
+    /// Time between submission and end
+    pub fn turnaround(&self) -> u64 {
+        self.end - self.submit
+    }
+}
+
+/// Batch scheduler
+///
+/// Space-sharing scheduler of rigid jobs on a cluster, driven by a simulated clock jumping from event to event. Pending jobs start in first come, first served order, or by decreasing multifactor priority : without backfilling, a job which does not fit blocks the jobs behind it.
+///
+/// ```rust, ignore
+///# use filasse::batch::*;
+///# use filasse::cluster::*;
+///# use std::collections::VecDeque;
+/// pub struct BatchScheduler {
+///     cluster: Cluster,
+///     time: u64,
+///     next_id: u64,
+///     arrivals: Vec<BatchJob>,
+///     waiting: Vec<BatchJob>,
+///     pending: VecDeque<BatchJob>,
+///     running: Vec<Execution>,
+///     finished: Vec<Record>,
+///     backfill: Backfill,
+///     backfilled: usize,
+///     grace: u64,
+///     priority: Option<Multifactor>,
+///     fairshare: Option<FairShare>,
+///     reservations: Vec<Reservation>,
+///     next_reservation: u64,
+///     preemption: Option<Preemption>,
+///     preempted: Vec<Preempted>,
+///     suspended: Vec<Suspension>,
+/// }
+///```
+#[derive(Debug, Clone)]
+pub struct BatchScheduler {
+    cluster: Cluster,
+    time: u64,
+    next_id: u64,
+    arrivals: Vec<BatchJob>,
+    waiting: Vec<BatchJob>,
+    pending: VecDeque<BatchJob>,
+    running: Vec<Execution>,
+    finished: Vec<Record>,
+    backfill: Backfill,
+    backfilled: usize,
+    grace: u64,
+    priority: Option<Multifactor>,
+    fairshare: Option<FairShare>,
+    reservations: Vec<Reservation>,
+    next_reservation: u64,
+    preemption: Option<Preemption>,
+    preempted: Vec<Preempted>,
+    suspended: Vec<Suspension>,
+}
+
+impl BatchScheduler {
+    /// Initialisation method
+    ///
+    /// # Example :
+    /// ```rust, ignore
+    ///# use filasse::batch::*;
+    ///# use filasse::cluster::*;
+    /// let mut batch = BatchScheduler::new(Cluster::homogeneous(4, 32, 128));
+    /// batch.submit(BatchJob::new(2, 32, 64, 100)).unwrap();
+    /// batch.run();
+    ///```
+    pub fn new(cluster: Cluster) -> Self {
+        BatchScheduler {
+            cluster,
+            time: 0,
+            next_id: 1,
+            arrivals: Vec::<BatchJob>::new(),
+            waiting: Vec::<BatchJob>::new(),
+            pending: VecDeque::<BatchJob>::new(),
+            running: Vec::<Execution>::new(),
+            finished: Vec::<Record>::new(),
+            backfill: Backfill::None,
+            backfilled: 0,
+            grace: DEFAULT_GRACE,
+            priority: None,
+            fairshare: None,
+            reservations: Vec::<Reservation>::new(),
+            next_reservation: 1,
+            preemption: None,
+            preempted: Vec::<Preempted>::new(),
+            suspended: Vec::<Suspension>::new(),
+        }
+    }
+
+    /// Getter cluster
+    pub fn cluster(&self) -> &Cluster {
+        &self.cluster
+    }
+
+    /// Getter time
+    pub fn time(&self) -> u64 {
+        self.time
+    }
+
+    /// Getter backfilling policy
+    pub fn backfill(&self) -> Backfill {
+        self.backfill
+    }
+
+    /// Setter backfilling policy
+    pub fn set_backfill(&mut self, policy: Backfill) {
+        self.backfill = policy;
+    }
+
+    /// Number of backfilled jobs
+    ///
+    /// The jobs started ahead of a blocked job since the creation of the scheduler.
+    pub fn backfilled(&self) -> usize {
+        self.backfilled
+    }
+
+    /// Getter grace period
+    pub fn grace(&self) -> u64 {
+        self.grace
+    }
+
+    /// Setter grace period
+    ///
+    /// The time between the TERM and the KILL sent to a job reaching its walltime. It applies to the jobs started afterwards.
+    pub fn set_grace(&mut self, grace: u64) {
+        self.grace = grace;
+    }
+
+    /// Getter priority policy
+    pub fn priority(&self) -> Option<&Multifactor> {
+        self.priority.as_ref()
+    }
+
+    /// Setter priority policy
+    ///
+    /// `None` keeps the pending jobs in submission order.
+    pub fn set_priority(&mut self, policy: Option<Multifactor>) {
+        self.priority = policy;
+    }
+
+    /// Getter fair-share tree
+    pub fn fairshare(&self) -> Option<&FairShare> {
+        self.fairshare.as_ref()
+    }
+
+    /// Setter fair-share tree
+    ///
+    /// The users are charged the core-seconds of their finished jobs, and their factors feed the fair-share factor of the priority policy.
+    pub fn set_fairshare(&mut self, tree: Option<FairShare>) {
+        self.fairshare = tree;
+    }
+
+    /// Getter preemption policy
+    pub fn preemption(&self) -> Option<&Preemption> {
+        self.preemption.as_ref()
+    }
+
+    /// Setter preemption policy
+    ///
+    /// `None` never preempts running jobs. A suspended job keeps its memory, so a job preempting it must fit in the memory left. A pending job which fits without preempting is left to the backfilling policy, but a job preempting jobs of lower tiers may delay the jobs ahead of it in the queue.
+    ///
+    /// # Example :
+    /// ```rust, ignore
+    ///# use filasse::batch::*;
+    ///# use filasse::cluster::*;
+    ///# use filasse::preemption::*;
+    /// let mut batch = BatchScheduler::new(Cluster::homogeneous(1, 4, 8));
+    /// let mut policy = Preemption::new();
+    /// policy.set_qos("high", 1, PreemptMode::Off);
+    /// policy.set_qos("low", 0, PreemptMode::Suspend);
+    /// batch.set_preemption(Some(policy));
+    /// batch.submit(BatchJob::new(1, 4, 4, 100).qos("low")).unwrap();
+    /// batch.submit(BatchJob::new(1, 4, 4, 10).submit(50).qos("high")).unwrap();
+    /// batch.run();
+    /// assert!(batch.metrics().preemptions == 1);
+    ///```
+    pub fn set_preemption(&mut self, policy: Option<Preemption>) {
+        self.preemption = policy;
+    }
+
+    /// Preemptions
+    ///
+    /// The preemptions since the creation of the scheduler, in order.
+    pub fn preempted(&self) -> &[Preempted] {
+        &self.preempted
+    }
+
+    /// Getter suspended jobs
+    pub fn suspended(&self) -> &[Suspension] {
+        &self.suspended
+    }
+
+    /// Reserve nodes
+    ///
+    /// The whole nodes, with all their cores and memory, are held from `start` to `end` for the jobs allowed by `access`. Returns the id of the reservation.
+    ///
+    /// # Example :
+    /// ```rust, ignore
+    ///# use filasse::batch::*;
+    ///# use filasse::cluster::*;
+    ///# use filasse::reservation::*;
+    /// let mut batch = BatchScheduler::new(Cluster::homogeneous(4, 32, 128));
+    /// batch.reserve_nodes("maintenance", 3600, 7200, vec![0, 1, 2, 3], Access::Maintenance).unwrap();
+    ///```
+    pub fn reserve_nodes(
+        &mut self,
+        name: &str,
+        start: u64,
+        end: u64,
+        nodes: Vec<usize>,
+        access: Access,
+    ) -> Result<u64, BatchError> {
+        let mut allocations: Vec<Allocation> = Vec::new();
+        for id in nodes {
+            let node = self.cluster.node(id).ok_or(BatchError::UnknownNode(id))?;
+            match allocations
+                .iter_mut()
+                .find(|a| a.cores == node.cores && a.memory == node.memory)
+            {
+                Some(allocation) => allocation.nodes.push(id),
+                None => allocations.push(Allocation {
+                    nodes: vec![id],
+                    cores: node.cores,
+                    memory: node.memory,
+                }),
+            }
+        }
+        self.reserve(name, start, end, allocations, access)
+    }
+
+    /// Reserve cores
+    ///
+    /// `cores` cores and `memory` memory are held on each of the nodes from `start` to `end` for the jobs allowed by `access`, within the size of every node. Returns the id of the reservation.
+    pub fn reserve_cores(
+        &mut self,
+        name: &str,
+        start: u64,
+        end: u64,
+        allocation: Allocation,
+        access: Access,
+    ) -> Result<u64, BatchError> {
+        self.reserve(name, start, end, vec![allocation], access)
+    }
+
+    /// Cancel a reservation
+    pub fn cancel_reservation(&mut self, id: u64) -> Result<Reservation, BatchError> {
+        let index = self
+            .reservations
+            .iter()
+            .position(|r| r.id == id)
+            .ok_or(BatchError::UnknownReservation(id))?;
+        Ok(self.reservations.remove(index))
+    }
+
+    /// Getter reservations
+    ///
+    /// The reservations by increasing start.
+    pub fn reservations(&self) -> &[Reservation] {
+        &self.reservations
+    }
+
+    /// Reservation calendar
+    ///
+    /// The reservations active at some time between `from` and `to`, by increasing start.
+    pub fn calendar(&self, from: u64, to: u64) -> Vec<&Reservation> {
+        self.reservations
+            .iter()
+            .filter(|r| r.overlaps(from, to))
+            .collect()
+    }
+
+    /// Explain the priority of a job
+    ///
+    /// The factors of a pending job under the priority policy, or the default multifactor policy if none is set.
+    ///
+    /// # Example :
+    /// ```rust, ignore
+    ///# use filasse::batch::*;
+    ///# use filasse::cluster::*;
+    /// let mut batch = BatchScheduler::new(Cluster::homogeneous(1, 4, 8));
+    /// batch.submit(BatchJob::new(1, 4, 8, 10)).unwrap();
+    /// let waiting = batch.submit(BatchJob::new(1, 4, 8, 10)).unwrap();
+    /// batch.schedule();
+    /// println!("{}", batch.explain(waiting).unwrap());
+    ///```
+    pub fn explain(&self, id: u64) -> Result<Explanation, BatchError> {
+        let job = self
+            .pending
+            .iter()
+            .find(|job| job.id == id)
+            .ok_or(BatchError::UnknownJob(id))?;
+        let mut policy = self.priority.clone().unwrap_or_default();
+        if let Some(tree) = self.fairshare.as_ref() {
+            for (user, factor) in tree.factors() {
+                policy.set_fairshare(&user, factor);
+            }
+        }
+        Ok(policy.explain(job, self.time, self.cluster.total_cores()))
+    }
+
+    /// Getter pending jobs
+    ///
+    /// The jobs already submitted and waiting for resources, in order.
+    pub fn pending(&self) -> &VecDeque<BatchJob> {
+        &self.pending
+    }
+
+    /// Getter running jobs
+    pub fn running(&self) -> &[Execution] {
+        &self.running
+    }
+
+    /// Getter finished jobs
+    ///
+    /// The records in order of end.
+    pub fn finished(&self) -> &[Record] {
+        &self.finished
+    }
+
+    /// State of a job
+    ///
+    /// `New` before its submit time and while it waits for its dependencies, `Ready` while pending, `Running`, `Stopped` while suspended by a preemption, then `Terminated`.
+    pub fn state(&self, id: u64) -> Option<JobState> {
+        if self
+            .arrivals
+            .iter()
+            .chain(self.waiting.iter())
+            .any(|job| job.id == id)
+        {
+            Some(JobState::New)
+        } else if self.pending.iter().any(|job| job.id == id) {
+            Some(JobState::Ready)
+        } else if self.running.iter().any(|e| e.job.id == id) {
+            Some(JobState::Running)
+        } else if self.suspended.iter().any(|s| s.execution.job.id == id) {
+            Some(JobState::Stopped)
+        } else if self.finished.iter().any(|r| r.id == id) {
+            Some(JobState::Terminated)
+        } else {
+            None
+        }
+    }
+
+    /// Submit a job
+    ///
+    /// The job enters the pending queue at its submit time, or now if it is in the past, once its dependencies completed. It is cancelled if one of them ends otherwise, or is still not submitted at the end of the run. A job closing a cycle of dependencies is rejected. Returns the id of the job.
+    pub fn submit(&mut self, mut job: BatchJob) -> Result<u64, BatchError> {
+        let partition = job.partition.as_deref();
+        if let Some(name) = partition {
+            if self.cluster.partition(name).is_none() {
+                return Err(BatchError::UnknownPartition(name.to_string()));
+            }
+        }
+        if !self.cluster.can_ever_fit(&job.resources, partition) {
+            return Err(BatchError::Unsatisfiable(job.resources));
+        }
+        if let Some(&id) = job
+            .dependencies
+            .iter()
+            .find(|&&id| id == 0 || id == self.next_id)
+        {
+            return Err(BatchError::InvalidDependency(id));
+        }
+        if self.reaches(&job.dependencies, self.next_id) {
+            return Err(BatchError::CyclicDependency(self.next_id));
+        }
+        job.id = self.next_id;
+        self.next_id += 1;
+        if job.submit <= self.time {
+            job.submit = self.time;
+            self.waiting.push(job);
+            self.resolve_dependencies();
+        } else {
+            let index = self.arrivals.partition_point(|j| j.submit <= job.submit);
+            self.arrivals.insert(index, job);
+        }
+        Ok(self.next_id - 1)
+    }
+
+    /// Start jobs
+    ///
+    /// Resume the suspended jobs whose cores are free, start pending jobs in order until one does not fit, let the pending jobs preempt running jobs according to the preemption policy, then backfill according to the backfilling policy. Returns the ids of the started jobs.
+    pub fn schedule(&mut self) -> Vec<u64> {
+        self.prioritize();
+        self.resume();
+        let mut started = Vec::new();
+        while !self.pending.is_empty() {
+            match self.place(0) {
+                Some(allocation) => {
+                    let job = self.pending.pop_front().unwrap();
+                    started.push(job.id);
+                    self.launch(job, allocation);
+                }
+                None => break,
+            }
+        }
+        if self.preemption.is_some() {
+            let mut requeued = Vec::new();
+            let mut index = 0;
+            while index < self.pending.len() {
+                match self.preempt(index, &mut requeued) {
+                    Some(allocation) => {
+                        let job = self.pending.remove(index).unwrap();
+                        started.push(job.id);
+                        self.launch(job, allocation);
+                    }
+                    None => index += 1,
+                }
+            }
+            for job in requeued {
+                let index = self.pending.partition_point(|j| j.submit <= job.submit);
+                self.pending.insert(index, job);
+            }
+        }
+        if self.backfill != Backfill::None && !self.pending.is_empty() {
+            started.extend(self.backfill_pending());
+        }
+        started
+    }
+
+    /// Next event
+    ///
+    /// The date of the next end or arrival of a job, or of the next end of a reservation while jobs are pending.
+    pub fn next_event(&self) -> Option<u64> {
+        let end = self.running.iter().map(|e| e.end()).min();
+        let arrival = self.arrivals.first().map(|job| job.submit);
+        let reservation = self
+            .reservations
+            .iter()
+            .map(|r| r.end)
+            .filter(|&end| end > self.time && !self.pending.is_empty())
+            .min();
+        [end, arrival, reservation].into_iter().flatten().min()
+    }
+
+    /// Advance to the next event
+    ///
+    /// Jumps to the next event, releases the jobs ending at that date, queues the jobs arriving, then starts what fits. Returns false if there is no event left.
+    pub fn step(&mut self) -> bool {
+        let time = match self.next_event() {
+            Some(time) => time,
+            None => return false,
+        };
+        self.time = time;
+        if let Some(tree) = self.fairshare.as_mut() {
+            tree.decay(time);
+        }
+        let mut index = 0;
+        while index < self.running.len() {
+            if self.running[index].end() <= time {
+                let execution = self.running.remove(index);
+                let exit = if execution.timed_out() {
+                    ExitReason::Timeout
+                } else {
+                    ExitReason::Completed
+                };
+                self.finish(execution, exit);
+            } else {
+                index += 1;
+            }
+        }
+        while self.arrivals.first().is_some_and(|job| job.submit <= time) {
+            let job = self.arrivals.remove(0);
+            self.waiting.push(job);
+        }
+        self.resolve_dependencies();
+        self.schedule();
+        true
+    }
+
+    /// Run to completion
+    ///
+    /// The jobs still waiting for dependencies which will never complete are cancelled. Returns the makespan.
+    pub fn run(&mut self) -> u64 {
+        self.schedule();
+        while self.step() {}
+        for job in std::mem::take(&mut self.waiting) {
+            self.cancel(job);
+        }
+        self.makespan()
+    }
+
+    /// Makespan
+    ///
+    /// The end of the last finished job.
+    pub fn makespan(&self) -> u64 {
+        self.finished.iter().map(|r| r.end).max().unwrap_or(0)
+    }
+
+    /// Utilization
+    ///
+    /// The fraction of the cores of the cluster busy between time 0 and the makespan.
+    pub fn utilization(&self) -> f64 {
+        self.metrics().utilization
+    }
+
+    /// Number of timeouts
+    pub fn timeouts(&self) -> usize {
+        self.finished
+            .iter()
+            .filter(|r| r.exit == ExitReason::Timeout)
+            .count()
+    }
+
+    /// Metrics
+    ///
+    /// The metrics of the finished jobs and of the preemptions.
+    pub fn metrics(&self) -> Metrics {
+        Metrics {
+            preemptions: self.preempted.len(),
+            lost_work: self.preempted.iter().map(|p| p.lost).sum(),
+            ..Metrics::from_records(&self.finished, self.cluster.total_cores())
+        }
+    }
+
+    /// Mean wait
+    ///
+    /// The mean time spent pending by the finished jobs.
+    pub fn mean_wait(&self) -> f64 {
+        self.metrics().mean_wait
+    }
+
+    // Sort the pending jobs by decreasing priority, keeping the submission order between equals.
+    fn prioritize(&mut self) {
+        if let (Some(policy), Some(tree)) = (self.priority.as_mut(), self.fairshare.as_ref()) {
+            for (user, factor) in tree.factors() {
+                policy.set_fairshare(&user, factor);
+            }
+        }
+        if let Some(policy) = self.priority.as_ref() {
+            let (now, cores) = (self.time, self.cluster.total_cores());
+            self.pending.make_contiguous().sort_by(|a, b| {
+                let (pa, pb) = (
+                    policy.priority(a, now, cores),
+                    policy.priority(b, now, cores),
+                );
+                pb.partial_cmp(&pa).unwrap()
+            });
+        }
+    }
+
+    // Walltime estimate of the end of a running job, its KILL once it is past its walltime, never in the past.
+    fn estimated_end(&self, execution: &Execution) -> u64 {
+        let end = execution.start + execution.suspended + execution.job.walltime;
+        if end > self.time {
+            end
+        } else {
+            execution.kill.max(self.time + 1)
+        }
+    }
+
+    // Profile of the running jobs, the suspended ones holding their memory as if they resumed now.
+    fn profile(&self) -> Profile {
+        let mut profile = Profile::new(&self.cluster);
+        for execution in self.running.iter() {
+            profile.reserve(Slot {
+                start: execution.start,
+                end: self.estimated_end(execution),
+                allocation: execution.allocation.clone(),
+            });
+        }
+        for suspension in self.suspended.iter() {
+            let execution = &suspension.execution;
+            let remaining = execution
+                .job
+                .walltime
+                .saturating_sub(execution.run_time(suspension.since));
+            profile.reserve(Slot {
+                start: suspension.since,
+                end: self.time + remaining.max(1),
+                allocation: Allocation {
+                    cores: 0,
+                    ..execution.allocation.clone()
+                },
+            });
+        }
+        profile
+    }
+
+    // Resume the suspended jobs whose cores are free, in order of suspension.
+    fn resume(&mut self) {
+        let mut index = 0;
+        while index < self.suspended.len() {
+            if self.cluster.claim(&self.suspended[index].cores()) {
+                let suspension = self.suspended.remove(index);
+                let mut execution = suspension.execution;
+                execution.suspended += self.time - suspension.since;
+                execution.kill += self.time - suspension.since;
+                self.running.push(execution);
+            } else {
+                index += 1;
+            }
+        }
+    }
+
+    // Allocation for the pending job at `index` after preempting running jobs of lower tier by increasing cost, none if it still does not fit or if it fits without preempting. The preempted jobs to requeue are added to `requeued`.
+    fn preempt(&mut self, index: usize, requeued: &mut Vec<BatchJob>) -> Option<Allocation> {
+        let policy = self.preemption.clone()?;
+        // Starting a job which fits now could delay the head of the queue : it is left to backfilling.
+        if let Some(allocation) = self.place(index) {
+            self.cluster.release(&allocation);
+            return None;
+        }
+        let job = &self.pending[index];
+        let nodes = self.cluster.partition_nodes(job.partition.as_deref());
+        let mut candidates: Vec<(f64, u64)> = self
+            .running
+            .iter()
+            .filter(|e| {
+                policy.can_preempt(job, &e.job)
+                    && e.allocation.nodes.iter().any(|n| nodes.contains(n))
+            })
+            .map(|e| (policy.cost(e, self.time), e.job.id))
+            .collect();
+        if candidates.is_empty() {
+            return None;
+        }
+        candidates.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+        let mut victims = Vec::new();
+        let mut allocation = None;
+        for (_, id) in candidates {
+            if allocation.is_some() {
+                break;
+            }
+            let position = self.running.iter().position(|e| e.job.id == id).unwrap();
+            let execution = self.running.remove(position);
+            let freed = match policy.mode(&execution.job) {
+                PreemptMode::Suspend => Allocation {
+                    memory: 0,
+                    ..execution.allocation.clone()
+                },
+                _ => execution.allocation.clone(),
+            };
+            self.cluster.release(&freed);
+            victims.push((position, execution, freed));
+            allocation = self.place(index);
+        }
+        let allocation = match allocation {
+            Some(allocation) => allocation,
+            None => {
+                for (position, execution, freed) in victims.into_iter().rev() {
+                    self.cluster.claim(&freed);
+                    self.running.insert(position, execution);
+                }
+                return None;
+            }
+        };
+        let by = self.pending[index].id;
+        for (_, execution, _) in victims {
+            let mode = policy.mode(&execution.job);
+            self.preempted.push(Preempted {
+                id: execution.job.id,
+                by,
+                time: self.time,
+                mode,
+                lost: policy.lost(&execution, self.time),
+            });
+            match mode {
+                PreemptMode::Suspend => self.suspended.push(Suspension {
+                    execution,
+                    since: self.time,
+                }),
+                PreemptMode::Requeue => {
+                    self.charge(&execution);
+                    let saved = policy.saved(&execution, self.time);
+                    let mut job = execution.job;
+                    job.runtime = job.runtime.saturating_sub(saved);
+                    requeued.push(job);
+                }
+                PreemptMode::Off | PreemptMode::Cancel => {
+                    self.record(execution, ExitReason::Cancelled)
+                }
+            }
+        }
+        Some(allocation)
+    }
+
+    // Copy of `profile` with the reservations `job` may not enter.
+    fn exclude(&self, profile: &Profile, job: &BatchJob) -> Profile {
+        let mut profile = profile.clone();
+        let account = job.account.as_deref().or_else(|| {
+            let user = job.user.as_deref()?;
+            self.fairshare.as_ref()?.account(user)
+        });
+        for reservation in self.reservations.iter() {
+            if reservation.end > self.time && !reservation.allows(job.user.as_deref(), account) {
+                for slot in reservation.slots() {
+                    profile.reserve(slot);
+                }
+            }
+        }
+        profile
+    }
+
+    // Allocation for the pending job at `index` if it can start now, outside the reservations it may not enter until its KILL, the grace period after its walltime.
+    fn place(&mut self, index: usize) -> Option<Allocation> {
+        let job = &self.pending[index];
+        if self.reservations.is_empty() {
+            return self
+                .cluster
+                .allocate(&job.resources, job.partition.as_deref());
+        }
+        let profile = self.exclude(&self.profile(), job);
+        let nodes = self.cluster.partition_nodes(job.partition.as_deref());
+        let duration = job.walltime + self.grace;
+        match profile.earliest(&job.resources, &nodes, self.time, duration) {
+            Some((start, allocation)) if start == self.time && self.cluster.claim(&allocation) => {
+                Some(allocation)
+            }
+            _ => None,
+        }
+    }
+
+    fn reserve(
+        &mut self,
+        name: &str,
+        start: u64,
+        end: u64,
+        allocations: Vec<Allocation>,
+        access: Access,
+    ) -> Result<u64, BatchError> {
+        if end <= start {
+            return Err(BatchError::EmptyWindow { start, end });
+        }
+        for allocation in allocations.iter() {
+            for &node in allocation.nodes.iter() {
+                let capacity = self
+                    .cluster
+                    .node(node)
+                    .ok_or(BatchError::UnknownNode(node))?;
+                if allocation.cores > capacity.cores || allocation.memory > capacity.memory {
+                    return Err(BatchError::Unsatisfiable(Resources::new(
+                        1,
+                        allocation.cores,
+                        allocation.memory,
+                    )));
+                }
+                let mut held = (0, 0);
+                for other in self.reservations.iter().filter(|r| r.overlaps(start, end)) {
+                    for slot in other.allocations.iter().filter(|a| a.nodes.contains(&node)) {
+                        held.0 += slot.cores;
+                        held.1 += slot.memory;
+                        if held.0 + allocation.cores > capacity.cores
+                            || held.1 + allocation.memory > capacity.memory
+                        {
+                            return Err(BatchError::ReservationConflict(other.id));
+                        }
+                    }
+                }
+            }
+        }
+        let id = self.next_reservation;
+        self.next_reservation += 1;
+        let reservation = Reservation {
+            id,
+            name: name.to_string(),
+            start,
+            end,
+            allocations,
+            access,
+        };
+        let index = self.reservations.partition_point(|r| r.start <= start);
+        self.reservations.insert(index, reservation);
+        Ok(id)
+    }
+
+    fn backfill_pending(&mut self) -> Vec<u64> {
+        let mut profile = self.profile();
+        let mut started = Vec::new();
+        let mut reserved = false;
+        let mut index = 0;
+        while index < self.pending.len() {
+            let job = &self.pending[index];
+            // A job may use the grace period after its walltime before its KILL.
+            let duration = job.walltime.max(1) + self.grace;
+            let nodes = self.cluster.partition_nodes(job.partition.as_deref());
+            let allowed = self.exclude(&profile, job);
+            match allowed.earliest(&job.resources, &nodes, self.time, duration) {
+                Some((start, allocation))
+                    if start == self.time && self.cluster.claim(&allocation) =>
+                {
+                    let job = self.pending.remove(index).unwrap();
+                    profile.reserve(Slot {
+                        start,
+                        end: start + duration,
+                        allocation: allocation.clone(),
+                    });
+                    self.backfilled += 1;
+                    started.push(job.id);
+                    self.launch(job, allocation);
+                }
+                Some((start, allocation)) => {
+                    if self.backfill == Backfill::Conservative || !reserved {
+                        profile.reserve(Slot {
+                            start,
+                            end: start + duration,
+                            allocation,
+                        });
+                        reserved = true;
+                    }
+                    index += 1;
+                }
+                None => index += 1,
+            }
+        }
+        started
+    }
+
+    // Queue the waiting jobs whose dependencies completed, cancel those with a dependency which ended otherwise.
+    fn resolve_dependencies(&mut self) {
+        let mut index = 0;
+        while index < self.waiting.len() {
+            let exits: Vec<Option<ExitReason>> = self.waiting[index]
+                .dependencies
+                .iter()
+                .map(|&id| self.finished.iter().find(|r| r.id == id).map(|r| r.exit))
+                .collect();
+            if exits.iter().flatten().any(|exit| !exit.is_success()) {
+                let job = self.waiting.remove(index);
+                self.cancel(job);
+                // The cancelled job may be the dependency of a job already passed.
+                index = 0;
+            } else if exits.iter().all(Option::is_some) {
+                let job = self.waiting.remove(index);
+                self.pending.push_back(job);
+            } else {
+                index += 1;
+            }
+        }
+    }
+
+    // True if the job `target` is among `dependencies` or the dependencies of the jobs not queued yet they lead to.
+    fn reaches(&self, dependencies: &[u64], target: u64) -> bool {
+        let mut stack = dependencies.to_vec();
+        let mut seen = HashSet::new();
+        while let Some(id) = stack.pop() {
+            if id == target {
+                return true;
+            }
+            if seen.insert(id) {
+                if let Some(job) = self
+                    .arrivals
+                    .iter()
+                    .chain(self.waiting.iter())
+                    .find(|job| job.id == id)
+                {
+                    stack.extend(job.dependencies.iter().copied());
+                }
+            }
+        }
+        false
+    }
+
+    // Record a job which never started, its dependencies being never satisfied.
+    fn cancel(&mut self, job: BatchJob) {
+        self.finished.push(Record {
+            id: job.id,
+            resources: job.resources,
+            submit: job.submit,
+            start: self.time,
+            end: self.time,
+            exit: ExitReason::DependencyNeverSatisfied,
+            suspended: 0,
+        });
+    }
+
+    fn launch(&mut self, job: BatchJob, allocation: Allocation) {
+        let kill = self.time + job.walltime + self.grace;
+        self.running.push(Execution {
+            job,
+            allocation,
+            start: self.time,
+            kill,
+            suspended: 0,
+        });
+    }
+
+    fn finish(&mut self, execution: Execution, exit: ExitReason) {
+        self.cluster.release(&execution.allocation);
+        self.record(execution, exit);
+    }
+
+    // Charge the user of a job for the core-seconds it ran.
+    fn charge(&mut self, execution: &Execution) {
+        if let (Some(tree), Some(user)) = (self.fairshare.as_mut(), execution.job.user.as_ref()) {
+            let used = execution.job.resources.total_cores() * execution.run_time(self.time);
+            tree.charge(user, used as f64);
+        }
+    }
+
+    fn record(&mut self, execution: Execution, exit: ExitReason) {
+        self.charge(&execution);
+        self.finished.push(Record {
+            id: execution.job.id,
+            resources: execution.job.resources,
+            submit: execution.job.submit,
+            start: execution.start,
+            end: self.time,
+            exit,
+            suspended: execution.suspended,
+        });
+    }
+}
@@ -0,0 +1,551 @@
+use crate::batch::*;
+use crate::eventlog::{self, EventLogError};
+use crate::harness::*;
+use crate::policy::*;
+use crate::replay::*;
+use crate::report::*;
+use crate::swf::{self, SwfError};
+use crate::workload::*;
+use std::fmt;
+
+/// Usage of the command-line simulator
+pub const USAGE: &str = "usage:
+  filasse simulate <workload> [--policy NAME] [--quantum Q] [--cpus N] [--gantt FILE] [--swf FILE] [--trace FILE]
+  filasse compare <workload> [--policies NAME,NAME,..] [--quantum Q] [--cpus N]
+  filasse validate <workload> [--cpus N]
+  filasse sweep <model> [--policies NAME,NAME,..] [--quanta Q,Q,..] [--cpus N,N,..] [--seeds FIRST-LAST|S,S,..] [--jobs N] [--threads N] [--csv FILE] [--runs FILE]
+  filasse replay <recording> [--workload FILE] [--policy NAME] [--quantum Q] [--cpus N]
+
+workloads: .toml, .json, .yaml or .yml files, SWF otherwise
+recordings: .jsonl event logs, traces of simulate --trace with the --workload they ran
+models: lublin[:LOAD], poisson:RATE:MEAN
+policies: fcfs, easy, conservative, priority, rr (round robin, one cpu)";
+
+/// Errors of the command line
+///
+/// * `Usage` : the arguments are not valid.
+/// * `Io` : the file can not be read or written.
+/// * `Swf` : the SWF workload file is not valid.
+/// * `Workload` : the TOML, JSON or YAML workload file is not valid.
+/// * `Policy` : the policy is unknown or can not run the workload.
+/// * `Unsatisfiable` : the job at this position of the workload asks for more processors than the CPUs.
+/// * `Harness` : the workload model is invalid or a run of the sweep failed.
+/// * `EventLog` : the event log is not valid.
+/// * `Replay` : the trace is not valid or the policy can not replay it.
+/// * `Divergence` : the replayed schedule differs from the recorded one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CliError {
+    Usage(String),
+    Io { path: String, message: String },
+    Swf { path: String, error: SwfError },
+    Workload { path: String, error: WorkloadError },
+    Policy(PolicyError),
+    Unsatisfiable { job: usize, cpus: u32 },
+    Harness(HarnessError),
+    EventLog { path: String, error: EventLogError },
+    Replay { path: String, error: ReplayError },
+    Divergence(Divergence),
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CliError::Usage(message) => write!(f, "{}", message),
+            CliError::Io { path, message } => write!(f, "{}: {}", path, message),
+            CliError::Swf { path, error } => write!(f, "{}: {}", path, error),
+            CliError::Workload { path, error } => write!(f, "{}: {}", path, error),
+            CliError::Policy(error) => write!(f, "{}", error),
+            CliError::Unsatisfiable { job, cpus } => {
+                write!(f, "job {} asks for more than {} cpus", job, cpus)
+            }
+            CliError::Harness(error) => write!(f, "{}", error),
+            CliError::EventLog { path, error } => write!(f, "{}: {}", path, error),
+            CliError::Replay { path, error } => write!(f, "{}: {}", path, error),
+            CliError::Divergence(divergence) => write!(f, "{}", divergence),
+        }
+    }
+}
+
+impl std::error::Error for CliError {}
+
+impl From<PolicyError> for CliError {
+    fn from(error: PolicyError) -> Self {
+        CliError::Policy(error)
+    }
+}
+
+impl From<HarnessError> for CliError {
+    fn from(error: HarnessError) -> Self {
+        CliError::Harness(error)
+    }
+}
+
+/// Subcommands
+///
+/// The options given override the scheduler configuration of the workload file.
+///
+/// * `Simulate` : run a policy on a workload, print the statistics and write the optional outputs.
+/// * `Compare` : run several policies on a workload and print one line of statistics for each.
+/// * `Validate` : check a workload file, and that its jobs fit on `cpus` if given.
+/// * `Sweep` : run the matrix of the policies, quanta, CPUs and seeds on workloads of a model, print the means with their confidence intervals and write the tidy CSV of the statistics to `csv` and of the runs to `runs`. By default, 1000 jobs, one CPU, the seeds 1 to 10 and the policies of `Compare`.
+/// * `Replay` : run a policy on the workload of a recording and fail at the first decision which differs. An event log holds its workload and is replayed with round robin by default, a trace needs the `workload` it ran.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    Simulate {
+        workload: String,
+        policy: Option<Policy>,
+        quantum: Option<u64>,
+        cpus: Option<u32>,
+        gantt: Option<String>,
+        swf: Option<String>,
+        trace: Option<String>,
+    },
+    Compare {
+        workload: String,
+        policies: Option<Vec<Policy>>,
+        quantum: Option<u64>,
+        cpus: Option<u32>,
+    },
+    Validate {
+        workload: String,
+        cpus: Option<u32>,
+    },
+    Sweep {
+        model: String,
+        policies: Option<Vec<Policy>>,
+        quanta: Vec<u64>,
+        cpus: Vec<u32>,
+        seeds: Vec<u64>,
+        jobs: usize,
+        threads: Option<usize>,
+        csv: Option<String>,
+        runs: Option<String>,
+    },
+    Replay {
+        recording: String,
+        workload: Option<String>,
+        policy: Option<Policy>,
+        quantum: Option<u64>,
+        cpus: Option<u32>,
+    },
+}
+
+/// Parse the arguments
+///
+/// The arguments after the name of the program. `--quantum` sets the quantum of round robin.
+///
+/// # Example :
+/// ```rust, ignore
+///# use filasse::cli::*;
+/// let args: Vec<String> = ["simulate", "jobs.swf", "--policy", "easy", "--cpus", "64"]
+///     .iter()
+///     .map(|s| s.to_string())
+///     .collect();
+/// let command = parse(&args).unwrap();
+///```
+pub fn parse(args: &[String]) -> Result<Command, CliError> {
+    let usage = |message: &str| CliError::Usage(message.to_string());
+    let mut args = args.iter();
+    let subcommand = args.next().ok_or_else(|| usage("missing subcommand"))?;
+    let workload = args
+        .next()
+        .filter(|arg| !arg.starts_with("--"))
+        .ok_or_else(|| usage("missing workload file"))?
+        .clone();
+    let mut policies: Option<Vec<Policy>> = None;
+    let mut quantum: Option<u64> = None;
+    let mut cpus: Option<u32> = None;
+    let (mut gantt, mut swf, mut trace) = (None, None, None);
+    let (mut quanta, mut cpu_list, mut seeds) = (Vec::new(), vec![1], (1..=10).collect());
+    let (mut jobs, mut threads, mut csv, mut runs) = (1000, None, None, None);
+    let mut recorded_workload = None;
+    while let Some(option) = args.next() {
+        let value = args
+            .next()
+            .ok_or_else(|| CliError::Usage(format!("missing value of {}", option)))?;
+        let number =
+            || {
+                value.parse::<u64>().ok().filter(|&n| n > 0).ok_or_else(|| {
+                    CliError::Usage(format!("invalid value of {}: {}", option, value))
+                })
+            };
+        match (subcommand.as_str(), option.as_str()) {
+            ("simulate" | "replay", "--policy") | ("compare" | "sweep", "--policies") => {
+                let list = value
+                    .split(',')
+                    .map(|name| name.trim().parse())
+                    .collect::<Result<Vec<Policy>, PolicyError>>()?;
+                policies = Some(list);
+            }
+            ("simulate" | "compare" | "replay", "--quantum") => quantum = Some(number()?),
+            ("sweep", "--quanta") => quanta = positive_list(option, value)?,
+            ("sweep", "--cpus") => cpu_list = positive_list(option, value)?,
+            ("sweep", "--seeds") => seeds = seed_list(option, value)?,
+            ("sweep", "--jobs") => jobs = number()? as usize,
+            ("sweep", "--threads") => threads = Some(number()? as usize),
+            ("sweep", "--csv") => csv = Some(value.clone()),
+            ("sweep", "--runs") => runs = Some(value.clone()),
+            (_, "--cpus") => {
+                cpus = Some(u32::try_from(number()?).map_err(|_| usage("too many cpus"))?);
+            }
+            ("simulate", "--gantt") => gantt = Some(value.clone()),
+            ("simulate", "--swf") => swf = Some(value.clone()),
+            ("simulate", "--trace") => trace = Some(value.clone()),
+            ("replay", "--workload") => recorded_workload = Some(value.clone()),
+            _ => return Err(CliError::Usage(format!("unknown option {}", option))),
+        }
+    }
+    let policy = match (subcommand.as_str(), policies.as_deref()) {
+        ("simulate" | "replay", Some([policy])) => Some(*policy),
+        ("simulate" | "replay", Some(_)) => {
+            return Err(CliError::Usage(format!("{} runs one policy", subcommand)))
+        }
+        _ => None,
+    };
+    match subcommand.as_str() {
+        "simulate" => Ok(Command::Simulate {
+            workload,
+            policy,
+            quantum,
+            cpus,
+            gantt,
+            swf,
+            trace,
+        }),
+        "compare" => Ok(Command::Compare {
+            workload,
+            policies,
+            quantum,
+            cpus,
+        }),
+        "validate" => Ok(Command::Validate { workload, cpus }),
+        "sweep" => {
+            workload.parse::<Source>()?;
+            Ok(Command::Sweep {
+                model: workload,
+                policies,
+                quanta,
+                cpus: cpu_list,
+                seeds,
+                jobs,
+                threads,
+                csv,
+                runs,
+            })
+        }
+        "replay" => {
+            if recorded_workload.is_none() && !is_event_log(&workload) {
+                return Err(usage("a trace is replayed with its --workload"));
+            }
+            Ok(Command::Replay {
+                recording: workload,
+                workload: recorded_workload,
+                policy,
+                quantum,
+                cpus,
+            })
+        }
+        other => Err(CliError::Usage(format!("unknown subcommand {}", other))),
+    }
+}
+
+/// Loaded workload
+///
+/// The jobs, with the policy and the CPUs of the scheduler configuration of a TOML, JSON or YAML workload.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Experiment {
+    pub jobs: Vec<BatchJob>,
+    pub policy: Option<Policy>,
+    pub cpus: Option<u32>,
+}
+
+impl Experiment {
+    /// Settings of a run
+    ///
+    /// The policy and the CPUs given, else those of the workload, else `fcfs` on one CPU. `quantum` applies to round robin.
+    pub fn settings(
+        &self,
+        policy: Option<Policy>,
+        quantum: Option<u64>,
+        cpus: Option<u32>,
+    ) -> (Policy, u32) {
+        let policy = policy.or(self.policy).unwrap_or(Policy::Fcfs);
+        (
+            with_quantum(policy, quantum),
+            cpus.or(self.cpus).unwrap_or(1),
+        )
+    }
+}
+
+/// Load a workload file
+///
+/// A TOML, JSON or YAML workload according to the extension, SWF otherwise.
+pub fn load(path: &str) -> Result<Experiment, CliError> {
+    let text = std::fs::read_to_string(path).map_err(|e| CliError::Io {
+        path: path.to_string(),
+        message: e.to_string(),
+    })?;
+    match Format::of(path) {
+        Some(format) => {
+            let workload = Workload::parse(&text, format).map_err(|error| CliError::Workload {
+                path: path.to_string(),
+                error,
+            })?;
+            let jobs = workload.batch_jobs().map_err(|error| CliError::Workload {
+                path: path.to_string(),
+                error,
+            })?;
+            Ok(Experiment {
+                jobs,
+                policy: workload.policy().ok(),
+                cpus: Some(workload.scheduler.cpus),
+            })
+        }
+        None => {
+            let jobs = swf::parse(&text).map_err(|error| CliError::Swf {
+                path: path.to_string(),
+                error,
+            })?;
+            Ok(Experiment {
+                jobs,
+                policy: None,
+                cpus: None,
+            })
+        }
+    }
+}
+
+/// Run a command
+///
+/// Returns the text to print.
+pub fn run(command: &Command) -> Result<String, CliError> {
+    match command {
+        Command::Simulate {
+            workload,
+            policy,
+            quantum,
+            cpus,
+            gantt: gantt_path,
+            swf: swf_path,
+            trace: trace_path,
+        } => {
+            let experiment = load(workload)?;
+            let (policy, cpus) = experiment.settings(*policy, *quantum, *cpus);
+            let simulation = policy.simulate(&experiment.jobs, cpus)?;
+            let records = &simulation.records;
+            let outputs = [
+                (gantt_path, gantt(records, 80)),
+                (swf_path, swf::write(records)),
+                (trace_path, trace(records)),
+            ];
+            for (path, text) in outputs {
+                if let Some(path) = path {
+                    std::fs::write(path, text).map_err(|e| CliError::Io {
+                        path: path.clone(),
+                        message: e.to_string(),
+                    })?;
+                }
+            }
+            Ok(format!(
+                "policy          {}\ncpus            {}\n{}\n",
+                policy, cpus, simulation.metrics
+            ))
+        }
+        Command::Compare {
+            workload,
+            policies,
+            quantum,
+            cpus,
+        } => {
+            let experiment = load(workload)?;
+            let (_, cpus) = experiment.settings(None, None, *cpus);
+            let policies = policies
+                .clone()
+                .unwrap_or_else(|| default_policies(cpus == 1));
+            let mut table = format!(
+                "{:<14} {:>10} {:>12} {:>12} {:>16}\n",
+                "policy", "makespan", "utilization", "mean wait", "mean turnaround"
+            );
+            for policy in policies {
+                let policy = with_quantum(policy, *quantum);
+                let metrics = policy.simulate(&experiment.jobs, cpus)?.metrics;
+                table.push_str(&format!(
+                    "{:<14} {:>10} {:>12.4} {:>12.2} {:>16.2}\n",
+                    policy.to_string(),
+                    metrics.makespan,
+                    metrics.utilization,
+                    metrics.mean_wait,
+                    metrics.mean_turnaround
+                ));
+            }
+            Ok(table)
+        }
+        Command::Validate { workload, cpus } => {
+            let jobs = load(workload)?.jobs;
+            if let Some(cpus) = cpus {
+                if let Some(index) = jobs
+                    .iter()
+                    .position(|job| job.resources.total_cores() > *cpus as u64)
+                {
+                    return Err(CliError::Unsatisfiable {
+                        job: index + 1,
+                        cpus: *cpus,
+                    });
+                }
+            }
+            Ok(format!("{}: {} jobs, valid\n", workload, jobs.len()))
+        }
+        Command::Sweep {
+            model,
+            policies,
+            quanta,
+            cpus,
+            seeds,
+            jobs,
+            threads,
+            csv,
+            runs,
+        } => {
+            let mut matrix = Matrix::new(model.parse()?, *jobs)
+                .policies(
+                    policies
+                        .clone()
+                        .unwrap_or_else(|| default_policies(cpus.contains(&1))),
+                )
+                .quanta(quanta.clone())
+                .cpus(cpus.clone())
+                .seeds(seeds.clone());
+            if let Some(threads) = threads {
+                matrix = matrix.threads(*threads);
+            }
+            let comparison = matrix.run()?;
+            for (path, text) in [(csv, comparison.summary_csv()), (runs, comparison.csv())] {
+                if let Some(path) = path {
+                    std::fs::write(path, text).map_err(|e| CliError::Io {
+                        path: path.clone(),
+                        message: e.to_string(),
+                    })?;
+                }
+            }
+            let mut table = format!(
+                "{:<14} {:>5} {:>20} {:>20} {:>20} {:>20}\n",
+                "policy", "cpus", "makespan", "utilization", "mean wait", "mean turnaround"
+            );
+            for cell in comparison.summaries().chunks(METRICS.len()) {
+                table.push_str(&format!(
+                    "{:<14} {:>5}",
+                    cell[0].policy.to_string(),
+                    cell[0].cpus
+                ));
+                for summary in cell {
+                    let interval = format!("{:.2} ± {:.2}", summary.mean, summary.half_width);
+                    table.push_str(&format!(" {:>20}", interval));
+                }
+                table.push('\n');
+            }
+            Ok(table)
+        }
+        Command::Replay {
+            recording,
+            workload,
+            policy,
+            quantum,
+            cpus,
+        } => {
+            let replay_error = |error| CliError::Replay {
+                path: recording.clone(),
+                error,
+            };
+            let (decisions, divergence) = if is_event_log(recording) {
+                let records = eventlog::read(recording).map_err(|error| CliError::EventLog {
+                    path: recording.clone(),
+                    error,
+                })?;
+                let policy = with_quantum(
+                    policy.unwrap_or(Policy::RoundRobin {
+                        quantum: DEFAULT_QUANTUM,
+                    }),
+                    *quantum,
+                );
+                let divergence = replay_log(policy, cpus.unwrap_or(1), &records);
+                (log_decisions(&records).len(), divergence)
+            } else {
+                let text = std::fs::read_to_string(recording).map_err(|e| CliError::Io {
+                    path: recording.clone(),
+                    message: e.to_string(),
+                })?;
+                let experiment = load(workload.as_deref().unwrap_or_default())?;
+                let (policy, cpus) = experiment.settings(*policy, *quantum, *cpus);
+                let decisions = trace_decisions(&text).map_err(replay_error)?.len();
+                (
+                    decisions,
+                    replay_trace(policy, &experiment.jobs, cpus, &text),
+                )
+            };
+            match divergence.map_err(replay_error)? {
+                Some(divergence) => Err(CliError::Divergence(divergence)),
+                None => Ok(format!("{}: {} decisions replayed\n", recording, decisions)),
+            }
+        }
+    }
+}
+
+// An event log is a JSON-lines file, any other recording a trace.
+fn is_event_log(path: &str) -> bool {
+    path.ends_with(".jsonl")
+}
+
+// The batch policies, and round robin if it can run.
+fn default_policies(round_robin: bool) -> Vec<Policy> {
+    let mut all = vec![
+        Policy::Fcfs,
+        Policy::Easy,
+        Policy::Conservative,
+        Policy::Priority,
+    ];
+    if round_robin {
+        all.push(Policy::RoundRobin {
+            quantum: DEFAULT_QUANTUM,
+        });
+    }
+    all
+}
+
+// Comma separated positive numbers.
+fn positive_list<T: std::str::FromStr + Default + PartialOrd>(
+    option: &str,
+    value: &str,
+) -> Result<Vec<T>, CliError> {
+    value
+        .split(',')
+        .map(|n| n.trim().parse::<T>().ok().filter(|n| *n > T::default()))
+        .collect::<Option<Vec<T>>>()
+        .ok_or_else(|| CliError::Usage(format!("invalid value of {}: {}", option, value)))
+}
+
+// Seeds, comma separated or a range `first-last`.
+fn seed_list(option: &str, value: &str) -> Result<Vec<u64>, CliError> {
+    let invalid = || CliError::Usage(format!("invalid value of {}: {}", option, value));
+    match value.split_once('-') {
+        Some((first, last)) => {
+            let first: u64 = first.trim().parse().map_err(|_| invalid())?;
+            let last: u64 = last.trim().parse().map_err(|_| invalid())?;
+            if last < first {
+                return Err(invalid());
+            }
+            Ok((first..=last).collect())
+        }
+        None => value
+            .split(',')
+            .map(|seed| seed.trim().parse().map_err(|_| invalid()))
+            .collect(),
+    }
+}
+
+// `policy` with the quantum `quantum` if it is round robin.
+fn with_quantum(policy: Policy, quantum: Option<u64>) -> Policy {
+    match (policy, quantum) {
+        (Policy::RoundRobin { .. }, Some(quantum)) => Policy::RoundRobin { quantum },
+        _ => policy,
+    }
+}
@@ -0,0 +1,258 @@
+use crate::batch::*;
+
+/// Resources asked by a job
+///
+/// `nodes` nodes with `cores` cores and `memory` memory free on each of them.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Resources {
+    pub nodes: u32,
+    pub cores: u32,
+    pub memory: u64,
+}
+
+impl Resources {
+    pub fn new(nodes: u32, cores: u32, memory: u64) -> Self {
+        Resources {
+            nodes,
+            cores,
+            memory,
+        }
+    }
+
+    /// Total number of cores
+    pub fn total_cores(&self) -> u64 {
+        self.nodes as u64 * self.cores as u64
+    }
+}
+
+/// Node of a cluster
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Node {
+    pub id: usize,
+    pub cores: u32,
+    pub memory: u64,
+    pub free_cores: u32,
+    pub free_memory: u64,
+}
+
+impl Node {
+    pub fn new(id: usize, cores: u32, memory: u64) -> Self {
+        Node {
+            id,
+            cores,
+            memory,
+            free_cores: cores,
+            free_memory: memory,
+        }
+    }
+
+    /// Free
+    ///
+    /// Returns true if `cores` cores and `memory` memory are free on the node.
+    pub fn fits(&self, cores: u32, memory: u64) -> bool {
+        self.free_cores >= cores && self.free_memory >= memory
+    }
+}
+
+/// Partition of a cluster
+///
+/// A named subset of the nodes, jobs submitted to a partition only run on its nodes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Partition {
+    pub name: String,
+    pub nodes: Vec<usize>,
+}
+
+/// Resources given to a job
+///
+/// The nodes used, and the cores and memory taken on each of them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Allocation {
+    pub nodes: Vec<usize>,
+    pub cores: u32,
+    pub memory: u64,
+}
+
+/// Cluster of nodes
+///
+/// # Example :
+/// ```rust, ignore
+///# use filasse::cluster::*;
+/// let mut cluster = Cluster::new();
+/// let a = cluster.add_node(4, 16);
+/// let b = cluster.add_node(4, 16);
+/// cluster.add_partition("debug", vec![b]).unwrap();
+/// let allocation = cluster.allocate(&Resources::new(2, 2, 8), None).unwrap();
+/// cluster.release(&allocation);
+///```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Cluster {
+    nodes: Vec<Node>,
+    partitions: Vec<Partition>,
+}
+
+impl Cluster {
+    pub fn new() -> Self {
+        Cluster::default()
+    }
+
+    /// Homogeneous cluster
+    ///
+    /// `count` nodes with `cores` cores and `memory` memory each.
+    pub fn homogeneous(count: usize, cores: u32, memory: u64) -> Self {
+        let mut cluster = Cluster::new();
+        for _ in 0..count {
+            cluster.add_node(cores, memory);
+        }
+        cluster
+    }
+
+    /// Add a node
+    ///
+    /// Returns the id of the node.
+    pub fn add_node(&mut self, cores: u32, memory: u64) -> usize {
+        let id = self.nodes.len();
+        self.nodes.push(Node::new(id, cores, memory));
+        id
+    }
+
+    /// Add a partition
+    ///
+    /// A partition with the same name is replaced.
+    pub fn add_partition(&mut self, name: &str, nodes: Vec<usize>) -> Result<(), BatchError> {
+        if let Some(&node) = nodes.iter().find(|&&node| node >= self.nodes.len()) {
+            return Err(BatchError::UnknownNode(node));
+        }
+        self.partitions.retain(|partition| partition.name != name);
+        self.partitions.push(Partition {
+            name: name.to_string(),
+            nodes,
+        });
+        Ok(())
+    }
+
+    /// Getter nodes
+    pub fn nodes(&self) -> &[Node] {
+        &self.nodes
+    }
+
+    /// Getter node
+    pub fn node(&self, id: usize) -> Option<&Node> {
+        self.nodes.get(id)
+    }
+
+    /// Getter partitions
+    pub fn partitions(&self) -> &[Partition] {
+        &self.partitions
+    }
+
+    /// Getter partition
+    pub fn partition(&self, name: &str) -> Option<&Partition> {
+        self.partitions
+            .iter()
+            .find(|partition| partition.name == name)
+    }
+
+    /// Total number of cores
+    pub fn total_cores(&self) -> u64 {
+        self.nodes.iter().map(|node| node.cores as u64).sum()
+    }
+
+    /// Number of free cores
+    pub fn free_cores(&self) -> u64 {
+        self.nodes.iter().map(|node| node.free_cores as u64).sum()
+    }
+
+    /// Satisfiable
+    ///
+    /// Returns true if the request fits in the partition once every node is free. `None` stands for the whole cluster.
+    pub fn can_ever_fit(&self, resources: &Resources, partition: Option<&str>) -> bool {
+        self.candidates(partition)
+            .filter(|node| node.cores >= resources.cores && node.memory >= resources.memory)
+            .count()
+            >= resources.nodes as usize
+    }
+
+    /// Free now
+    ///
+    /// Returns true if the request fits in the free resources of the partition.
+    pub fn fits(&self, resources: &Resources, partition: Option<&str>) -> bool {
+        self.candidates(partition)
+            .filter(|node| node.fits(resources.cores, resources.memory))
+            .count()
+            >= resources.nodes as usize
+    }
+
+    /// Allocate resources
+    ///
+    /// The first nodes of the partition with enough free cores and memory are taken. Returns `None` if the request does not fit now.
+    pub fn allocate(
+        &mut self,
+        resources: &Resources,
+        partition: Option<&str>,
+    ) -> Option<Allocation> {
+        let nodes: Vec<usize> = self
+            .candidates(partition)
+            .filter(|node| node.fits(resources.cores, resources.memory))
+            .map(|node| node.id)
+            .take(resources.nodes as usize)
+            .collect();
+        if nodes.len() < resources.nodes as usize {
+            return None;
+        }
+        for &id in nodes.iter() {
+            self.nodes[id].free_cores -= resources.cores;
+            self.nodes[id].free_memory -= resources.memory;
+        }
+        Some(Allocation {
+            nodes,
+            cores: resources.cores,
+            memory: resources.memory,
+        })
+    }
+
+    /// Claim given resources
+    ///
+    /// Take exactly the nodes of an allocation chosen beforehand. Returns false, taking nothing, if one of them lacks free resources.
+    pub fn claim(&mut self, allocation: &Allocation) -> bool {
+        if !allocation.nodes.iter().all(|&id| {
+            self.nodes
+                .get(id)
+                .is_some_and(|node| node.fits(allocation.cores, allocation.memory))
+        }) {
+            return false;
+        }
+        for &id in allocation.nodes.iter() {
+            self.nodes[id].free_cores -= allocation.cores;
+            self.nodes[id].free_memory -= allocation.memory;
+        }
+        true
+    }
+
+    /// Release resources
+    pub fn release(&mut self, allocation: &Allocation) {
+        for &id in allocation.nodes.iter() {
+            let node = &mut self.nodes[id];
+            node.free_cores = (node.free_cores + allocation.cores).min(node.cores);
+            node.free_memory = (node.free_memory + allocation.memory).min(node.memory);
+        }
+    }
+
+    /// Nodes of a partition
+    ///
+    /// `None` stands for the whole cluster, an unknown partition has no node.
+    pub fn partition_nodes(&self, partition: Option<&str>) -> Vec<usize> {
+        self.candidates(partition).map(|node| node.id).collect()
+    }
+
+    fn candidates<'a>(
+        &'a self,
+        partition: Option<&str>,
+    ) -> Box<dyn Iterator<Item = &'a Node> + 'a> {
+        match partition.map(|name| self.partition(name)) {
+            None => Box::new(self.nodes.iter()),
+            Some(Some(partition)) => Box::new(partition.nodes.iter().map(|&id| &self.nodes[id])),
+            Some(None) => Box::new(std::iter::empty()),
+        }
+    }
+}
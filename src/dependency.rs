@@ -0,0 +1,76 @@
+use crate::job::*;
+use crate::registry::*;
+
+/// Dependencies
+///
+/// Slurm-style conditions a job waits for before entering the queue.
+///
+/// * `After` : the job `pid` has started, or has exited without starting.
+/// * `AfterAny` : the job `pid` has exited, whatever the reason.
+/// * `AfterOk` : the job `pid` has completed successfully.
+/// * `AfterNotOk` : the job `pid` has exited without completing.
+/// * `Singleton` : no other admitted job of the same group is alive.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Dependency {
+    After(u64),
+    AfterAny(u64),
+    AfterOk(u64),
+    AfterNotOk(u64),
+    Singleton(String),
+}
+
+/// Status of a dependency
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Status {
+    Satisfied,
+    Waiting,
+    Never,
+}
+
+impl Dependency {
+    /// Pid depended on
+    ///
+    /// Returns `None` for a singleton.
+    pub fn pid(&self) -> Option<u64> {
+        match self {
+            Dependency::After(pid)
+            | Dependency::AfterAny(pid)
+            | Dependency::AfterOk(pid)
+            | Dependency::AfterNotOk(pid) => Some(*pid),
+            Dependency::Singleton(_) => None,
+        }
+    }
+
+    /// Status of a dependency on a job
+    ///
+    /// `entry` is the registry entry of the job depended on. A singleton is always satisfied here, the scheduler checks its group.
+    ///
+    /// # Example :
+    /// ```rust, ignore
+    ///# use filasse::dependency::*;
+    ///# use filasse::registry::*;
+    ///# use filasse::job::*;
+    /// let mut entry = Entry::new(1, 0, JobState::Zombie, 0);
+    /// entry.exit = Some(ExitReason::Completed);
+    /// assert!(Dependency::AfterNotOk(1).status(&entry) == Status::Never);
+    ///```
+    pub fn status(&self, entry: &Entry) -> Status {
+        let exited = matches!(entry.state, JobState::Zombie | JobState::Terminated);
+        let success = entry.exit.map(|reason| reason.is_success());
+        match self {
+            Dependency::After(_) if entry.started || exited => Status::Satisfied,
+            Dependency::AfterAny(_) if exited => Status::Satisfied,
+            Dependency::AfterOk(_) if exited => match success {
+                Some(true) => Status::Satisfied,
+                _ => Status::Never,
+            },
+            Dependency::AfterNotOk(_) if exited => match success {
+                Some(true) => Status::Never,
+                _ => Status::Satisfied,
+            },
+            Dependency::Singleton(_) => Status::Satisfied,
+            _ => Status::Waiting,
+        }
+    }
+}
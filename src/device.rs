@@ -0,0 +1,294 @@
+use std::collections::VecDeque;
+
+/// Disk scheduling algorithms
+///
+/// The algorithm used by a device to choose the next request to service.
+///
+/// * `Fcfs` : requests are serviced in their arrival order.
+/// * `Sstf` : the request closest to the head is serviced first.
+/// * `Scan` : the head sweeps to the end of the disk then reverses (elevator).
+/// * `CScan` : the head sweeps upward to the end, jumps back to cylinder 0 and sweeps again.
+/// * `Look` : like `Scan`, but the head reverses at the last request instead of the end of the disk.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DiskPolicy {
+    Fcfs,
+    Sstf,
+    Scan,
+    CScan,
+    Look,
+}
+
+/// Direction of the head
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Direction {
+    Up,
+    Down,
+}
+
+/// I/O request
+///
+/// Pid of the blocked job, cylinder (or offset) targeted and date of the request.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct IoRequest {
+    pub pid: u64,
+    pub cylinder: u64,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub time: u64,
+}
+
+/// Completion of an I/O request
+///
+/// The serviced request, the distance travelled by the head, the time taken to service it and the date it ends.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Completion {
+    pub request: IoRequest,
+    pub seek: u64,
+    pub service_time: u64,
+    pub end: u64,
+}
+
+/// Simulated I/O device
+///
+/// A device owns a queue of pending requests over a space of `cylinders` positions. The service time of a request is `transfer_time + seek * seek_time`. The device has its own timeline : a request is serviced from its date, or from the end of the previous one if the device is still busy.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Device {
+    policy: DiskPolicy,
+    cylinders: u64,
+    head: u64,
+    direction: Direction,
+    seek_time: u64,
+    transfer_time: u64,
+    pending: VecDeque<IoRequest>,
+    total_seek: u64,
+    busy_time: u64,
+    #[cfg_attr(feature = "serde", serde(default))]
+    time: u64,
+}
+
+impl Default for Device {
+    fn default() -> Self {
+        Device::new(DiskPolicy::Fcfs, 200)
+    }
+}
+
+impl Device {
+    /// Initialisation method
+    ///
+    /// Create a device with the head at cylinder 0 moving up, a seek time of 1 per cylinder and no transfer time.
+    ///
+    /// # Example :
+    /// ```rust, ignore
+    ///# use filasse::device::*;
+    /// let disk = Device::new(DiskPolicy::Scan, 200);
+    ///```
+    pub fn new(policy: DiskPolicy, cylinders: u64) -> Self {
+        Device {
+            policy,
+            cylinders: cylinders.max(1),
+            head: 0,
+            direction: Direction::Up,
+            seek_time: 1,
+            transfer_time: 0,
+            pending: VecDeque::<IoRequest>::new(),
+            total_seek: 0,
+            busy_time: 0,
+            time: 0,
+        }
+    }
+
+    /// Setter head
+    ///
+    /// Place the head on a cylinder and give its direction. The cylinder is clamped to the disk.
+    pub fn set_head(&mut self, cylinder: u64, direction: Direction) {
+        self.head = cylinder.min(self.cylinders - 1);
+        self.direction = direction;
+    }
+
+    /// Setter timings
+    ///
+    /// Set the time needed to travel one cylinder and the fixed transfer time of a request.
+    pub fn set_timings(&mut self, seek_time: u64, transfer_time: u64) {
+        self.seek_time = seek_time;
+        self.transfer_time = transfer_time;
+    }
+
+    /// Getter policy
+    pub fn policy(&self) -> DiskPolicy {
+        self.policy
+    }
+
+    /// Getter cylinders
+    pub fn cylinders(&self) -> u64 {
+        self.cylinders
+    }
+
+    /// Getter head
+    pub fn head(&self) -> u64 {
+        self.head
+    }
+
+    /// Getter direction
+    pub fn direction(&self) -> Direction {
+        self.direction
+    }
+
+    /// Getter pending
+    ///
+    /// The requests waiting to be serviced, in their arrival order.
+    pub fn pending(&self) -> &VecDeque<IoRequest> {
+        &self.pending
+    }
+
+    /// Getter total seek
+    ///
+    /// The distance travelled by the head since the creation of the device.
+    pub fn total_seek(&self) -> u64 {
+        self.total_seek
+    }
+
+    /// Getter busy time
+    ///
+    /// The time spent servicing requests since the creation of the device.
+    pub fn busy_time(&self) -> u64 {
+        self.busy_time
+    }
+
+    /// Getter time
+    ///
+    /// The end of the last serviced request.
+    pub fn time(&self) -> u64 {
+        self.time
+    }
+
+    /// Submit a request
+    ///
+    /// The cylinder is clamped to the disk.
+    ///
+    /// # Example :
+    /// ```rust, ignore
+    ///# use filasse::device::*;
+    /// let mut disk = Device::default();
+    /// disk.submit(IoRequest { pid: 1, cylinder: 98, time: 0 });
+    ///```
+    pub fn submit(&mut self, mut request: IoRequest) {
+        request.cylinder = request.cylinder.min(self.cylinders - 1);
+        self.pending.push_back(request);
+    }
+
+    /// Cancel requests
+    ///
+    /// Remove the pending requests of a job. Returns the number of removed requests.
+    pub fn cancel(&mut self, pid: u64) -> usize {
+        let before = self.pending.len();
+        self.pending.retain(|request| request.pid != pid);
+        before - self.pending.len()
+    }
+
+    /// Service the next request
+    ///
+    /// Choose the next request according to the policy, move the head on it and return the completion. Returns `None` when no request is pending.
+    ///
+    /// # Example :
+    /// ```rust, ignore
+    ///# use filasse::device::*;
+    /// let mut disk = Device::new(DiskPolicy::Sstf, 200);
+    /// disk.submit(IoRequest { pid: 1, cylinder: 98, time: 0 });
+    /// let done = disk.service().unwrap();
+    /// assert!(done.seek == 98);
+    ///```
+    pub fn service(&mut self) -> Option<Completion> {
+        let (index, seek) = match self.policy {
+            DiskPolicy::Fcfs => self.pick_fcfs()?,
+            DiskPolicy::Sstf => self.pick_sstf()?,
+            DiskPolicy::Scan => self.pick_sweep(true)?,
+            DiskPolicy::Look => self.pick_sweep(false)?,
+            DiskPolicy::CScan => self.pick_cscan()?,
+        };
+        let request = self.pending.remove(index)?;
+        let service_time = self.transfer_time + seek * self.seek_time;
+        self.head = request.cylinder;
+        self.total_seek += seek;
+        self.busy_time += service_time;
+        self.time = self.time.max(request.time) + service_time;
+        Some(Completion {
+            request,
+            seek,
+            service_time,
+            end: self.time,
+        })
+    }
+
+    fn pick_fcfs(&self) -> Option<(usize, u64)> {
+        let request = self.pending.front()?;
+        Some((0, self.head.abs_diff(request.cylinder)))
+    }
+
+    fn pick_sstf(&self) -> Option<(usize, u64)> {
+        self.pending
+            .iter()
+            .enumerate()
+            .map(|(i, r)| (i, self.head.abs_diff(r.cylinder)))
+            .min_by_key(|&(i, seek)| (seek, i))
+    }
+
+    // Nearest request in `direction`, including the cylinder under the head.
+    fn nearest(&self, direction: Direction) -> Option<(usize, u64)> {
+        self.nearest_from(self.head, direction)
+    }
+
+    fn nearest_from(&self, from: u64, direction: Direction) -> Option<(usize, u64)> {
+        self.pending
+            .iter()
+            .enumerate()
+            .filter(|(_, r)| match direction {
+                Direction::Up => r.cylinder >= from,
+                Direction::Down => r.cylinder <= from,
+            })
+            .map(|(i, r)| (i, from.abs_diff(r.cylinder)))
+            .min_by_key(|&(i, seek)| (seek, i))
+    }
+
+    // SCAN when `to_end` is true, LOOK otherwise.
+    fn pick_sweep(&mut self, to_end: bool) -> Option<(usize, u64)> {
+        if self.pending.is_empty() {
+            return None;
+        }
+        if let Some(found) = self.nearest(self.direction) {
+            return Some(found);
+        }
+        let end = match self.direction {
+            Direction::Up => self.cylinders - 1,
+            Direction::Down => 0,
+        };
+        self.direction = match self.direction {
+            Direction::Up => Direction::Down,
+            Direction::Down => Direction::Up,
+        };
+        let (index, seek) = if to_end {
+            let travel = self.head.abs_diff(end);
+            let (index, _) = self.nearest_from(end, self.direction)?;
+            (index, travel + end.abs_diff(self.pending[index].cylinder))
+        } else {
+            self.nearest(self.direction)?
+        };
+        Some((index, seek))
+    }
+
+    // The return from the last cylinder to cylinder 0 is counted in the seek distance.
+    fn pick_cscan(&mut self) -> Option<(usize, u64)> {
+        if self.pending.is_empty() {
+            return None;
+        }
+        self.direction = Direction::Up;
+        if let Some(found) = self.nearest(Direction::Up) {
+            return Some(found);
+        }
+        let last = self.cylinders - 1;
+        let (index, from_zero) = self.nearest_from(0, Direction::Up)?;
+        Some((index, (last - self.head) + last + from_zero))
+    }
+}
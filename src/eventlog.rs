@@ -0,0 +1,162 @@
+use crate::job::*;
+use crate::observer::*;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::io::{BufWriter, Write};
+
+/// Errors of an event log
+///
+/// * `Io` : the log can not be read or written.
+/// * `Parse` : the line of the log is not an event.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EventLogError {
+    Io(String),
+    Parse { line: usize, message: String },
+}
+
+impl fmt::Display for EventLogError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EventLogError::Io(message) => write!(f, "{}", message),
+            EventLogError::Parse { line, message } => write!(f, "line {}: {}", line, message),
+        }
+    }
+}
+
+impl std::error::Error for EventLogError {}
+
+/// Line of an event log
+///
+/// A transition of a job : the clock, the event, the job with its states before and after the transition, the CPU it runs on, its exit reason and the lengths of the queues.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EventRecord {
+    pub time: u64,
+    pub event: Event,
+    pub pid: u64,
+    pub parent: u64,
+    pub old: JobState,
+    pub state: JobState,
+    pub cpu: Option<u32>,
+    pub exit: Option<ExitReason>,
+    pub queues: Queues,
+}
+
+impl EventRecord {
+    /// Record of a transition
+    ///
+    /// The scheduler has one processor, the CPU 0 of the running job.
+    pub fn new(event: Event, transition: &Transition) -> Self {
+        EventRecord {
+            time: transition.time,
+            event,
+            pid: transition.job.pid(),
+            parent: transition.job.parent(),
+            old: transition.old,
+            state: transition.new,
+            cpu: (transition.new == JobState::Running).then_some(0),
+            exit: transition.exit,
+            queues: transition.queues,
+        }
+    }
+}
+
+/// Event log
+///
+/// An observer writing every transition as a line of JSON. Writing stops at the first error, kept by `error`.
+///
+/// # Example :
+/// ```rust, ignore
+///# use filasse::eventlog::*;
+///# use filasse::scheduler::*;
+///# use std::sync::{Arc, Mutex};
+/// let mut sched = Scheduler::new(10);
+/// let log = Arc::new(Mutex::new(EventLog::create("events.jsonl").unwrap()));
+/// sched.add_observer(log.clone());
+/// sched.round_robin();
+/// log.lock().unwrap().flush().unwrap();
+/// let records = read("events.jsonl").unwrap();
+///```
+pub struct EventLog {
+    writer: Box<dyn Write + Send>,
+    error: Option<String>,
+}
+
+impl EventLog {
+    /// Initialisation method
+    pub fn new(writer: Box<dyn Write + Send>) -> Self {
+        EventLog {
+            writer,
+            error: None,
+        }
+    }
+
+    /// Event log in a file
+    ///
+    /// The file is created, or truncated if it exists.
+    pub fn create(path: &str) -> Result<Self, EventLogError> {
+        let file = std::fs::File::create(path).map_err(|e| io(path, e))?;
+        Ok(EventLog::new(Box::new(BufWriter::new(file))))
+    }
+
+    /// Getter error
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    /// Flush the writer
+    pub fn flush(&mut self) -> Result<(), EventLogError> {
+        if let Some(error) = &self.error {
+            return Err(EventLogError::Io(error.clone()));
+        }
+        self.writer
+            .flush()
+            .map_err(|e| EventLogError::Io(e.to_string()))
+    }
+}
+
+impl fmt::Debug for EventLog {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EventLog")
+            .field("error", &self.error)
+            .finish()
+    }
+}
+
+impl SchedulerObserver for EventLog {
+    fn on_event(&mut self, event: Event, transition: &Transition) {
+        if self.error.is_some() {
+            return;
+        }
+        let record = EventRecord::new(event, transition);
+        let line = serde_json::to_string(&record).expect("an event record is serializable");
+        if let Err(e) = writeln!(self.writer, "{}", line) {
+            self.error = Some(e.to_string());
+        }
+    }
+}
+
+/// Parse an event log
+///
+/// One event per line, the empty lines being skipped.
+pub fn parse(text: &str) -> Result<Vec<EventRecord>, EventLogError> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(number, line)| {
+            serde_json::from_str(line).map_err(|e| EventLogError::Parse {
+                line: number + 1,
+                message: e.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Read an event log
+pub fn read(path: &str) -> Result<Vec<EventRecord>, EventLogError> {
+    let text = std::fs::read_to_string(path).map_err(|e| io(path, e))?;
+    parse(&text)
+}
+
+fn io(path: &str, error: std::io::Error) -> EventLogError {
+    EventLogError::Io(format!("{}: {}", path, error))
+}
@@ -0,0 +1,268 @@
+use std::collections::HashMap;
+use std::fmt;
+
+/// Name of the root account
+pub const ROOT_ACCOUNT: &str = "root";
+
+/// Errors of the fair-share tree
+///
+/// * `UnknownAccount` : no account with this name is in the tree.
+/// * `Duplicate` : an account or a user with this name is already in the tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FairShareError {
+    UnknownAccount(String),
+    Duplicate(String),
+}
+
+impl fmt::Display for FairShareError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FairShareError::UnknownAccount(name) => write!(f, "unknown account {}", name),
+            FairShareError::Duplicate(name) => write!(f, "{} is already in the tree", name),
+        }
+    }
+}
+
+impl std::error::Error for FairShareError {}
+
+/// Node of the fair-share tree
+///
+/// An account, or a user when it is a leaf added with `add_user`. `usage` is the decayed number of core-seconds consumed by the node and its descendants.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Association {
+    pub name: String,
+    pub parent: Option<usize>,
+    pub shares: u32,
+    pub usage: f64,
+    pub user: bool,
+}
+
+/// Fair-share accounting
+///
+/// A tree of accounts with users as leaves. Each node is given shares relative to its siblings, and the usage charged to a user is added to all its ancestors. Usage decays with a half-life, 0 meaning no decay.
+///
+/// The factor of a user is 2^(-U/S), like the classic fair-share of Slurm : S is the normalized share, product of the shares of the path relative to the siblings, and U the effective usage, the normalized usage of the user mixed with the effective usage of its account.
+///
+/// # Example :
+/// ```rust, ignore
+///# use filasse::fairshare::*;
+/// let mut tree = FairShare::new(3600);
+/// tree.add_account("physics", ROOT_ACCOUNT, 2).unwrap();
+/// tree.add_account("biology", ROOT_ACCOUNT, 1).unwrap();
+/// tree.add_user("alice", "physics", 1).unwrap();
+/// tree.charge("alice", 7200.0);
+/// tree.decay(3600);
+/// let factor = tree.factor("alice").unwrap();
+///```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FairShare {
+    nodes: Vec<Association>,
+    index: HashMap<String, usize>,
+    half_life: u64,
+    last_decay: u64,
+}
+
+impl FairShare {
+    /// Initialisation method
+    ///
+    /// A tree holding only the root account.
+    pub fn new(half_life: u64) -> Self {
+        FairShare {
+            nodes: vec![Association {
+                name: ROOT_ACCOUNT.to_string(),
+                parent: None,
+                shares: 1,
+                usage: 0.0,
+                user: false,
+            }],
+            index: HashMap::from([(ROOT_ACCOUNT.to_string(), 0)]),
+            half_life,
+            last_decay: 0,
+        }
+    }
+
+    /// Getter half-life
+    pub fn half_life(&self) -> u64 {
+        self.half_life
+    }
+
+    /// Add an account
+    pub fn add_account(
+        &mut self,
+        name: &str,
+        parent: &str,
+        shares: u32,
+    ) -> Result<(), FairShareError> {
+        self.insert(name, parent, shares, false)
+    }
+
+    /// Add a user
+    ///
+    /// The user is a leaf of the account `account`.
+    pub fn add_user(
+        &mut self,
+        name: &str,
+        account: &str,
+        shares: u32,
+    ) -> Result<(), FairShareError> {
+        self.insert(name, account, shares, true)
+    }
+
+    /// Getter association
+    pub fn get(&self, name: &str) -> Option<&Association> {
+        self.index.get(name).map(|&i| &self.nodes[i])
+    }
+
+    /// Account of a user
+    pub fn account(&self, user: &str) -> Option<&str> {
+        let node = self.get(user).filter(|node| node.user)?;
+        node.parent.map(|p| self.nodes[p].name.as_str())
+    }
+
+    /// Users of the tree
+    pub fn users(&self) -> Vec<&str> {
+        self.nodes
+            .iter()
+            .filter(|node| node.user)
+            .map(|node| node.name.as_str())
+            .collect()
+    }
+
+    /// Charge usage
+    ///
+    /// Add `core_seconds` to the user and to its ancestors. Returns false if the user is unknown.
+    pub fn charge(&mut self, user: &str, core_seconds: f64) -> bool {
+        let mut current = match self.index.get(user) {
+            Some(&i) if self.nodes[i].user => Some(i),
+            _ => return false,
+        };
+        while let Some(i) = current {
+            self.nodes[i].usage += core_seconds;
+            current = self.nodes[i].parent;
+        }
+        true
+    }
+
+    /// Decay usage
+    ///
+    /// Halve the usage of every node each half-life elapsed since the last decay, up to `now`.
+    pub fn decay(&mut self, now: u64) {
+        if now <= self.last_decay {
+            return;
+        }
+        if self.half_life > 0 {
+            let elapsed = (now - self.last_decay) as f64;
+            let factor = 0.5f64.powf(elapsed / self.half_life as f64);
+            for node in self.nodes.iter_mut() {
+                node.usage *= factor;
+            }
+        }
+        self.last_decay = now;
+    }
+
+    /// Normalized share
+    ///
+    /// The product of the shares of the path from the root, each relative to its siblings.
+    pub fn normalized_share(&self, name: &str) -> Option<f64> {
+        let mut current = *self.index.get(name)?;
+        let mut share = 1.0;
+        while let Some(parent) = self.nodes[current].parent {
+            let siblings = self.sibling_shares(current, parent);
+            if siblings == 0 {
+                return Some(0.0);
+            }
+            share *= self.nodes[current].shares as f64 / siblings as f64;
+            current = parent;
+        }
+        Some(share)
+    }
+
+    /// Effective usage
+    ///
+    /// The normalized usage of the node, pulled towards the effective usage of its parent in proportion of its shares among its siblings.
+    pub fn effective_usage(&self, name: &str) -> Option<f64> {
+        let mut path = vec![*self.index.get(name)?];
+        while let Some(parent) = self.nodes[*path.last().unwrap()].parent {
+            path.push(parent);
+        }
+        let total = self.nodes[0].usage;
+        let normalized = |i: usize| {
+            if total > 0.0 {
+                self.nodes[i].usage / total
+            } else {
+                0.0
+            }
+        };
+        let mut effective = normalized(0);
+        for &i in path.iter().rev().skip(1) {
+            let parent = self.nodes[i].parent.unwrap();
+            let siblings = self.sibling_shares(i, parent);
+            let ratio = if siblings == 0 {
+                0.0
+            } else {
+                self.nodes[i].shares as f64 / siblings as f64
+            };
+            let own = normalized(i);
+            effective = own + (effective - own) * ratio;
+        }
+        Some(effective)
+    }
+
+    /// Fair-share factor
+    ///
+    /// Between 0 and 1, 1 for a node which has used nothing, 0.5 for a node which has used exactly its share. Returns `None` if the name is unknown.
+    pub fn factor(&self, name: &str) -> Option<f64> {
+        let share = self.normalized_share(name)?;
+        let usage = self.effective_usage(name)?;
+        if share == 0.0 {
+            return Some(0.0);
+        }
+        Some(2f64.powf(-usage / share))
+    }
+
+    /// Factors of every user
+    pub fn factors(&self) -> HashMap<String, f64> {
+        self.users()
+            .into_iter()
+            .filter_map(|user| Some((user.to_string(), self.factor(user)?)))
+            .collect()
+    }
+
+    fn insert(
+        &mut self,
+        name: &str,
+        parent: &str,
+        shares: u32,
+        user: bool,
+    ) -> Result<(), FairShareError> {
+        if self.index.contains_key(name) {
+            return Err(FairShareError::Duplicate(name.to_string()));
+        }
+        let parent = match self.index.get(parent) {
+            Some(&i) if !self.nodes[i].user => i,
+            _ => return Err(FairShareError::UnknownAccount(parent.to_string())),
+        };
+        self.index.insert(name.to_string(), self.nodes.len());
+        self.nodes.push(Association {
+            name: name.to_string(),
+            parent: Some(parent),
+            shares,
+            usage: 0.0,
+            user,
+        });
+        Ok(())
+    }
+
+    // Sum of the shares of the children of `parent`, `node` included.
+    fn sibling_shares(&self, node: usize, parent: usize) -> u64 {
+        let sum: u64 = self
+            .nodes
+            .iter()
+            .filter(|n| n.parent == Some(parent))
+            .map(|n| n.shares as u64)
+            .sum();
+        sum.max(self.nodes[node].shares as u64)
+    }
+}
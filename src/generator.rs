@@ -0,0 +1,377 @@
+use crate::batch::*;
+use crate::job::*;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use rand_distr::{Distribution, Exp, Gamma, LogNormal, Weibull};
+use std::fmt;
+
+/// Errors of a generator
+///
+/// * `InvalidParameter` : the parameter of a model is out of its domain.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GeneratorError {
+    InvalidParameter(&'static str),
+}
+
+impl fmt::Display for GeneratorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GeneratorError::InvalidParameter(parameter) => {
+                write!(f, "invalid generator parameter {}", parameter)
+            }
+        }
+    }
+}
+
+impl std::error::Error for GeneratorError {}
+
+/// Arrival models
+///
+/// * `Poisson` : exponential inter-arrival times, `rate` jobs per tick.
+/// * `Weibull` : inter-arrival times following a Weibull distribution, a `shape` below 1 giving bursts and long silences.
+/// * `OnOff` : a Poisson process of `rate` during on periods, nothing during off periods, the lengths of the periods being exponential of means `on` and `off`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Arrival {
+    Poisson { rate: f64 },
+    Weibull { shape: f64, scale: f64 },
+    OnOff { rate: f64, on: f64, off: f64 },
+}
+
+/// Runtime models
+///
+/// * `Exponential` : exponential of mean `mean`.
+/// * `HyperExponential` : exponential of mean `short` with probability `p`, of mean `long` otherwise.
+/// * `LogNormal` : the logarithm of the runtime is normal of mean `mu` and deviation `sigma`.
+/// * `Bimodal` : `short` with probability `p`, `long` otherwise, both multiplied by a log-normal noise of deviation `sigma`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Runtime {
+    Exponential {
+        mean: f64,
+    },
+    HyperExponential {
+        p: f64,
+        short: f64,
+        long: f64,
+    },
+    LogNormal {
+        mu: f64,
+        sigma: f64,
+    },
+    Bimodal {
+        p: f64,
+        short: f64,
+        long: f64,
+        sigma: f64,
+    },
+}
+
+/// Workload generator
+///
+/// Jobs of one CPU arriving after an arrival model, with runtimes drawn from a runtime model and priorities uniform up to `priorities`. The same seed always gives the same workload.
+///
+/// # Example :
+/// ```rust, ignore
+///# use filasse::generator::*;
+/// let generator = Generator::new(
+///     Arrival::Poisson { rate: 0.1 },
+///     Runtime::HyperExponential { p: 0.9, short: 5.0, long: 200.0 },
+/// )
+/// .priorities(4);
+/// for (arrival, job) in generator.stream(42).unwrap().take(1000) {
+///     println!("{} {:?}", arrival, job);
+/// }
+///```
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Generator {
+    pub arrival: Arrival,
+    pub runtime: Runtime,
+    pub priorities: u32,
+}
+
+impl Generator {
+    /// Initialisation method
+    pub fn new(arrival: Arrival, runtime: Runtime) -> Self {
+        Generator {
+            arrival,
+            runtime,
+            priorities: 0,
+        }
+    }
+
+    pub fn priorities(mut self, max: u32) -> Self {
+        self.priorities = max;
+        self
+    }
+
+    /// Validate the models
+    ///
+    /// Rates, scales, shapes, means and period lengths are positive, probabilities within 0 and 1 and deviations not negative.
+    pub fn validate(&self) -> Result<(), GeneratorError> {
+        match self.arrival {
+            Arrival::Poisson { rate } => positive("rate", rate)?,
+            Arrival::Weibull { shape, scale } => {
+                positive("shape", shape)?;
+                positive("scale", scale)?;
+            }
+            Arrival::OnOff { rate, on, off } => {
+                positive("rate", rate)?;
+                positive("on", on)?;
+                positive("off", off)?;
+            }
+        }
+        match self.runtime {
+            Runtime::Exponential { mean } => positive("mean", mean)?,
+            Runtime::HyperExponential { p, short, long } => {
+                probability("p", p)?;
+                positive("short", short)?;
+                positive("long", long)?;
+            }
+            Runtime::LogNormal { mu, sigma } => {
+                if !mu.is_finite() {
+                    return Err(GeneratorError::InvalidParameter("mu"));
+                }
+                deviation("sigma", sigma)?;
+            }
+            Runtime::Bimodal {
+                p,
+                short,
+                long,
+                sigma,
+            } => {
+                probability("p", p)?;
+                positive("short", short)?;
+                positive("long", long)?;
+                deviation("sigma", sigma)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Stream of jobs
+    ///
+    /// An endless iterator over the jobs by arrival, each with its arrival time. A runtime is at least one tick.
+    pub fn stream(&self, seed: u64) -> Result<Stream, GeneratorError> {
+        self.validate()?;
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let on_until = match self.arrival {
+            Arrival::OnOff { on, .. } => exponential(&mut rng, on),
+            _ => f64::INFINITY,
+        };
+        Ok(Stream {
+            generator: *self,
+            rng,
+            clock: 0.0,
+            on_until,
+        })
+    }
+
+    /// Batch jobs
+    ///
+    /// The `count` first jobs of the stream as batch jobs of one node of one core, their walltimes being their runtimes.
+    pub fn batch_jobs(&self, seed: u64, count: usize) -> Result<Vec<BatchJob>, GeneratorError> {
+        Ok(self
+            .stream(seed)?
+            .take(count)
+            .map(|(arrival, job)| {
+                BatchJob::new(1, 1, 0, job.state.duration)
+                    .runtime(job.state.duration)
+                    .submit(arrival)
+            })
+            .collect())
+    }
+}
+
+/// Stream of generated jobs
+///
+/// The iterator returned by `Generator::stream`.
+#[derive(Debug, Clone)]
+pub struct Stream {
+    generator: Generator,
+    rng: ChaCha8Rng,
+    clock: f64,
+    on_until: f64,
+}
+
+impl Stream {
+    // Time of the next arrival.
+    fn arrival(&mut self) -> f64 {
+        match self.generator.arrival {
+            Arrival::Poisson { rate } => self.clock + exponential(&mut self.rng, 1.0 / rate),
+            Arrival::Weibull { shape, scale } => {
+                let weibull = Weibull::new(scale, shape).unwrap();
+                self.clock + weibull.sample(&mut self.rng)
+            }
+            Arrival::OnOff { rate, on, off } => {
+                // Exponential gaps are memoryless : a gap crossing the end of the on period is drawn again from the start of the next one.
+                let mut time = self.clock + exponential(&mut self.rng, 1.0 / rate);
+                while time > self.on_until {
+                    let start = self.on_until + exponential(&mut self.rng, off);
+                    self.on_until = start + exponential(&mut self.rng, on);
+                    time = start + exponential(&mut self.rng, 1.0 / rate);
+                }
+                time
+            }
+        }
+    }
+
+    // Runtime of the next job.
+    fn runtime(&mut self) -> f64 {
+        match self.generator.runtime {
+            Runtime::Exponential { mean } => exponential(&mut self.rng, mean),
+            Runtime::HyperExponential { p, short, long } => {
+                let mean = if self.rng.gen_bool(p) { short } else { long };
+                exponential(&mut self.rng, mean)
+            }
+            Runtime::LogNormal { mu, sigma } => {
+                LogNormal::new(mu, sigma).unwrap().sample(&mut self.rng)
+            }
+            Runtime::Bimodal {
+                p,
+                short,
+                long,
+                sigma,
+            } => {
+                let mode = if self.rng.gen_bool(p) { short } else { long };
+                mode * LogNormal::new(0.0, sigma).unwrap().sample(&mut self.rng)
+            }
+        }
+    }
+}
+
+impl Iterator for Stream {
+    type Item = (u64, Job<New>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.clock = self.arrival();
+        let runtime = ticks(self.runtime());
+        let priority = self.rng.gen_range(0..=self.generator.priorities);
+        Some((self.clock as u64, Job::new(0, 0, runtime, priority)))
+    }
+}
+
+// Parameters of the model of Lublin and Feitelson.
+const SERIAL: f64 = 0.24;
+const POWER_OF_TWO: f64 = 0.75;
+const SIZE_LOW: f64 = 0.8;
+const SIZE_MEDIUM_GAP: f64 = 2.5;
+const SIZE_PROB: f64 = 0.86;
+const RUNTIME_SHORT: (f64, f64) = (4.2, 0.94);
+const RUNTIME_LONG: (f64, f64) = (312.0, 0.03);
+const RUNTIME_PA: f64 = -0.0054;
+const RUNTIME_PB: f64 = 0.78;
+const ARRIVAL: (f64, f64) = (10.23, 0.4871);
+
+/// Model of Lublin and Feitelson
+///
+/// The parallel workload model of Lublin and Feitelson (2003) for a machine of `nodes` nodes :
+/// * a job is serial with probability 0.24, otherwise the logarithm of its size follows a two-stage uniform distribution, rounded to a power of two with probability 0.75.
+/// * the logarithm of its runtime follows a hyper-gamma distribution, the long component being more likely for larger jobs.
+/// * the logarithm of the inter-arrival times follows a gamma distribution, multiplied by `load` to tune the load of the machine.
+///
+/// The daily cycle of the arrivals is not modelled.
+///
+/// # Example :
+/// ```rust, ignore
+///# use filasse::generator::*;
+///# use filasse::policy::*;
+/// let jobs = Lublin::new(128).batch_jobs(7, 10_000).unwrap();
+/// let simulation = Policy::Easy.simulate(&jobs, 128).unwrap();
+///```
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Lublin {
+    pub nodes: u32,
+    pub load: f64,
+}
+
+impl Lublin {
+    /// Initialisation method
+    pub fn new(nodes: u32) -> Self {
+        Lublin { nodes, load: 1.0 }
+    }
+
+    pub fn load(mut self, factor: f64) -> Self {
+        self.load = factor;
+        self
+    }
+
+    /// Batch jobs
+    ///
+    /// `count` jobs of nodes of one core, their walltimes being their runtimes.
+    pub fn batch_jobs(&self, seed: u64, count: usize) -> Result<Vec<BatchJob>, GeneratorError> {
+        if self.nodes == 0 {
+            return Err(GeneratorError::InvalidParameter("nodes"));
+        }
+        positive("load", self.load)?;
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let arrival = Gamma::new(ARRIVAL.0, ARRIVAL.1).unwrap();
+        let short = Gamma::new(RUNTIME_SHORT.0, RUNTIME_SHORT.1).unwrap();
+        let long = Gamma::new(RUNTIME_LONG.0, RUNTIME_LONG.1).unwrap();
+        let high = f64::from(self.nodes).log2();
+        let medium = (high - SIZE_MEDIUM_GAP).max(SIZE_LOW);
+        let mut clock = 0.0;
+        let mut jobs = Vec::with_capacity(count);
+        for _ in 0..count {
+            clock += self.load * arrival.sample(&mut rng).exp();
+            let size = if self.nodes == 1 || rng.gen_bool(SERIAL) {
+                1
+            } else {
+                let mut log = if rng.gen_bool(SIZE_PROB) {
+                    rng.gen_range(SIZE_LOW..=medium)
+                } else {
+                    rng.gen_range(medium..=high)
+                };
+                if rng.gen_bool(POWER_OF_TWO) {
+                    log = log.round();
+                }
+                (log.exp2().round() as u32).clamp(1, self.nodes)
+            };
+            let p = (RUNTIME_PA * f64::from(size).log2() + RUNTIME_PB).clamp(0.0, 1.0);
+            let log = if rng.gen_bool(p) {
+                short.sample(&mut rng)
+            } else {
+                long.sample(&mut rng)
+            };
+            let runtime = ticks(log.exp());
+            jobs.push(
+                BatchJob::new(size, 1, 0, runtime)
+                    .runtime(runtime)
+                    .submit(clock as u64),
+            );
+        }
+        Ok(jobs)
+    }
+}
+
+// Sample of an exponential distribution of mean `mean`.
+fn exponential(rng: &mut ChaCha8Rng, mean: f64) -> f64 {
+    Exp::new(1.0 / mean).unwrap().sample(rng)
+}
+
+// A duration in whole ticks, at least one.
+fn ticks(time: f64) -> u64 {
+    (time.round() as u64).max(1)
+}
+
+fn positive(parameter: &'static str, value: f64) -> Result<(), GeneratorError> {
+    if value.is_finite() && value > 0.0 {
+        Ok(())
+    } else {
+        Err(GeneratorError::InvalidParameter(parameter))
+    }
+}
+
+fn probability(parameter: &'static str, value: f64) -> Result<(), GeneratorError> {
+    if (0.0..=1.0).contains(&value) {
+        Ok(())
+    } else {
+        Err(GeneratorError::InvalidParameter(parameter))
+    }
+}
+
+fn deviation(parameter: &'static str, value: f64) -> Result<(), GeneratorError> {
+    if value.is_finite() && value >= 0.0 {
+        Ok(())
+    } else {
+        Err(GeneratorError::InvalidParameter(parameter))
+    }
+}
@@ -0,0 +1,404 @@
+use crate::batch::*;
+use crate::generator::*;
+use crate::metrics::*;
+use crate::policy::*;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+/// Metrics compared by the harness
+pub const METRICS: [&str; 4] = ["makespan", "utilization", "mean_wait", "mean_turnaround"];
+
+// Quantiles of the Student t distribution at 97.5 % for 1 to 30 degrees of freedom.
+const STUDENT: [f64; 30] = [
+    12.706, 4.303, 3.182, 2.776, 2.571, 2.447, 2.365, 2.306, 2.262, 2.228, 2.201, 2.179, 2.160,
+    2.145, 2.131, 2.120, 2.110, 2.101, 2.093, 2.086, 2.080, 2.074, 2.069, 2.064, 2.060, 2.056,
+    2.052, 2.048, 2.045, 2.042,
+];
+
+/// Errors of a comparison
+///
+/// * `InvalidSource` : the workload model can not be parsed.
+/// * `Generator` : the workload model has an invalid parameter.
+/// * `Policy` : a simulation failed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HarnessError {
+    InvalidSource(String),
+    Generator(GeneratorError),
+    Policy(PolicyError),
+}
+
+impl fmt::Display for HarnessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HarnessError::InvalidSource(source) => write!(f, "invalid workload model {}", source),
+            HarnessError::Generator(error) => write!(f, "{}", error),
+            HarnessError::Policy(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for HarnessError {}
+
+impl From<GeneratorError> for HarnessError {
+    fn from(error: GeneratorError) -> Self {
+        HarnessError::Generator(error)
+    }
+}
+
+impl From<PolicyError> for HarnessError {
+    fn from(error: PolicyError) -> Self {
+        HarnessError::Policy(error)
+    }
+}
+
+/// Workload models of a comparison
+///
+/// * `Generator` : jobs of one CPU from a generator.
+/// * `Lublin` : the model of Lublin and Feitelson for the number of CPUs of the run, its inter-arrival times multiplied by `load`.
+///
+/// A model is named by `lublin`, optionally followed by the load like `lublin:0.5`, or by `poisson:RATE:MEAN` for Poisson arrivals and exponential runtimes.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Source {
+    Generator(Generator),
+    Lublin { load: f64 },
+}
+
+impl FromStr for Source {
+    type Err = HarnessError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        let invalid = || HarnessError::InvalidSource(name.to_string());
+        let parts: Vec<&str> = name.split(':').collect();
+        let number = |part: &str| part.parse::<f64>().map_err(|_| invalid());
+        let source = match parts.as_slice() {
+            ["lublin"] => Source::Lublin { load: 1.0 },
+            ["lublin", load] => Source::Lublin {
+                load: number(load)?,
+            },
+            ["poisson", rate, mean] => Source::Generator(Generator::new(
+                Arrival::Poisson {
+                    rate: number(rate)?,
+                },
+                Runtime::Exponential {
+                    mean: number(mean)?,
+                },
+            )),
+            _ => return Err(invalid()),
+        };
+        source.jobs(0, 0, 1)?;
+        Ok(source)
+    }
+}
+
+impl Source {
+    /// Jobs of a run
+    ///
+    /// `count` jobs of the model drawn with `seed` for `cpus` CPUs.
+    pub fn jobs(
+        &self,
+        seed: u64,
+        count: usize,
+        cpus: u32,
+    ) -> Result<Vec<BatchJob>, GeneratorError> {
+        match self {
+            Source::Generator(generator) => generator.batch_jobs(seed, count),
+            Source::Lublin { load } => Lublin::new(cpus.max(1)).load(*load).batch_jobs(seed, count),
+        }
+    }
+}
+
+/// Matrix of a comparison
+///
+/// Every policy runs on every number of CPUs for every seed, round robin once per quantum of `quanta` or with its own quantum if `quanta` is empty. Round robin only runs on one CPU, its other cells are skipped. The runs are spread over `threads` threads.
+///
+/// # Example :
+/// ```rust, ignore
+///# use filasse::harness::*;
+///# use filasse::policy::*;
+/// let comparison = Matrix::new(Source::Lublin { load: 1.0 }, 1000)
+///     .policies(vec![Policy::Fcfs, Policy::Easy])
+///     .cpus(vec![32, 64])
+///     .seeds((1..=10).collect())
+///     .run()
+///     .unwrap();
+/// std::fs::write("summary.csv", comparison.summary_csv()).unwrap();
+///```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Matrix {
+    pub source: Source,
+    pub jobs: usize,
+    pub policies: Vec<Policy>,
+    pub quanta: Vec<u64>,
+    pub cpus: Vec<u32>,
+    pub seeds: Vec<u64>,
+    pub threads: usize,
+}
+
+impl Matrix {
+    /// Initialisation method
+    ///
+    /// `fcfs` on one CPU with the seed 0, on as many threads as the machine runs at once.
+    pub fn new(source: Source, jobs: usize) -> Self {
+        Matrix {
+            source,
+            jobs,
+            policies: vec![Policy::Fcfs],
+            quanta: Vec::new(),
+            cpus: vec![1],
+            seeds: vec![0],
+            threads: std::thread::available_parallelism()
+                .map(|n| n.get())
+                .unwrap_or(1),
+        }
+    }
+
+    pub fn policies(mut self, policies: Vec<Policy>) -> Self {
+        self.policies = policies;
+        self
+    }
+
+    pub fn quanta(mut self, quanta: Vec<u64>) -> Self {
+        self.quanta = quanta;
+        self
+    }
+
+    pub fn cpus(mut self, cpus: Vec<u32>) -> Self {
+        self.cpus = cpus;
+        self
+    }
+
+    pub fn seeds(mut self, seeds: Vec<u64>) -> Self {
+        self.seeds = seeds;
+        self
+    }
+
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.threads = threads;
+        self
+    }
+
+    /// Cells of the matrix
+    ///
+    /// The policies, round robin with its quantum, and the CPUs of the runs, in the order of the results.
+    pub fn cells(&self) -> Vec<(Policy, u32)> {
+        let mut cells = Vec::new();
+        for &policy in self.policies.iter() {
+            let variants = match policy {
+                Policy::RoundRobin { .. } if !self.quanta.is_empty() => self
+                    .quanta
+                    .iter()
+                    .map(|&quantum| Policy::RoundRobin { quantum })
+                    .collect(),
+                _ => vec![policy],
+            };
+            for policy in variants {
+                for &cpus in self.cpus.iter() {
+                    if matches!(policy, Policy::RoundRobin { .. }) && cpus != 1 {
+                        continue;
+                    }
+                    cells.push((policy, cpus));
+                }
+            }
+        }
+        cells
+    }
+
+    /// Run the matrix
+    ///
+    /// Returns the runs by cell, then by seed.
+    pub fn run(&self) -> Result<Comparison, HarnessError> {
+        let tasks: Vec<(Policy, u32, u64)> = self
+            .cells()
+            .into_iter()
+            .flat_map(|(policy, cpus)| self.seeds.iter().map(move |&seed| (policy, cpus, seed)))
+            .collect();
+        let next = AtomicUsize::new(0);
+        let results = Mutex::new(Vec::with_capacity(tasks.len()));
+        std::thread::scope(|scope| {
+            for _ in 0..self.threads.clamp(1, tasks.len().max(1)) {
+                scope.spawn(|| loop {
+                    let index = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(&(policy, cpus, seed)) = tasks.get(index) else {
+                        break;
+                    };
+                    let result = self
+                        .source
+                        .jobs(seed, self.jobs, cpus)
+                        .map_err(HarnessError::from)
+                        .and_then(|jobs| Ok(policy.simulate(&jobs, cpus)?));
+                    results.lock().unwrap().push((index, result));
+                });
+            }
+        });
+        let mut results = results.into_inner().unwrap();
+        results.sort_by_key(|&(index, _)| index);
+        let mut runs = Vec::with_capacity(results.len());
+        for (index, result) in results {
+            let (policy, cpus, seed) = tasks[index];
+            runs.push(Run {
+                policy,
+                cpus,
+                seed,
+                metrics: result?.metrics,
+            });
+        }
+        Ok(Comparison { runs })
+    }
+}
+
+/// Run of a comparison
+#[derive(Debug, Clone, PartialEq)]
+pub struct Run {
+    pub policy: Policy,
+    pub cpus: u32,
+    pub seed: u64,
+    pub metrics: Metrics,
+}
+
+/// Statistics of a metric over the seeds of a cell
+///
+/// The mean, the sample standard deviation and the half width of the 95 % confidence interval of the mean, from the Student t distribution. With one run, the deviation and the interval are 0.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Summary {
+    pub policy: Policy,
+    pub cpus: u32,
+    pub metric: &'static str,
+    pub runs: usize,
+    pub mean: f64,
+    pub deviation: f64,
+    pub half_width: f64,
+}
+
+impl Summary {
+    /// Lower bound of the confidence interval
+    pub fn low(&self) -> f64 {
+        self.mean - self.half_width
+    }
+
+    /// Upper bound of the confidence interval
+    pub fn high(&self) -> f64 {
+        self.mean + self.half_width
+    }
+}
+
+/// Results of a comparison
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comparison {
+    pub runs: Vec<Run>,
+}
+
+impl Comparison {
+    /// Statistics of the cells
+    ///
+    /// One summary per cell and metric of `METRICS`, in the order of the runs.
+    pub fn summaries(&self) -> Vec<Summary> {
+        let mut cells: Vec<(Policy, u32)> = Vec::new();
+        for run in self.runs.iter() {
+            if !cells.contains(&(run.policy, run.cpus)) {
+                cells.push((run.policy, run.cpus));
+            }
+        }
+        let mut summaries = Vec::new();
+        for (policy, cpus) in cells {
+            let runs: Vec<&Run> = self
+                .runs
+                .iter()
+                .filter(|run| run.policy == policy && run.cpus == cpus)
+                .collect();
+            for metric in METRICS {
+                let values: Vec<f64> = runs.iter().map(|run| value(&run.metrics, metric)).collect();
+                let n = values.len() as f64;
+                let mean = values.iter().sum::<f64>() / n;
+                let (deviation, half_width) = if values.len() > 1 {
+                    let variance =
+                        values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.0);
+                    let t = STUDENT.get(values.len() - 2).copied().unwrap_or(1.96);
+                    (variance.sqrt(), t * variance.sqrt() / n.sqrt())
+                } else {
+                    (0.0, 0.0)
+                };
+                summaries.push(Summary {
+                    policy,
+                    cpus,
+                    metric,
+                    runs: values.len(),
+                    mean,
+                    deviation,
+                    half_width,
+                });
+            }
+        }
+        summaries
+    }
+
+    /// Runs in CSV
+    ///
+    /// A tidy table, one line per run and metric : `policy,quantum,cpus,seed,metric,value`, the quantum being empty for the batch policies.
+    pub fn csv(&self) -> String {
+        let mut text = String::from("policy,quantum,cpus,seed,metric,value\n");
+        for run in self.runs.iter() {
+            let (name, quantum) = columns(run.policy);
+            for metric in METRICS {
+                text.push_str(&format!(
+                    "{},{},{},{},{},{}\n",
+                    name,
+                    quantum,
+                    run.cpus,
+                    run.seed,
+                    metric,
+                    value(&run.metrics, metric)
+                ));
+            }
+        }
+        text
+    }
+
+    /// Statistics in CSV
+    ///
+    /// A tidy table, one line per cell and metric : `policy,quantum,cpus,metric,runs,mean,sd,low,high`.
+    pub fn summary_csv(&self) -> String {
+        let mut text = String::from("policy,quantum,cpus,metric,runs,mean,sd,low,high\n");
+        for s in self.summaries() {
+            let (name, quantum) = columns(s.policy);
+            text.push_str(&format!(
+                "{},{},{},{},{},{},{},{},{}\n",
+                name,
+                quantum,
+                s.cpus,
+                s.metric,
+                s.runs,
+                s.mean,
+                s.deviation,
+                s.low(),
+                s.high()
+            ));
+        }
+        text
+    }
+}
+
+/// Value of a metric
+///
+/// One of `METRICS`, 0 for an unknown name.
+pub fn value(metrics: &Metrics, metric: &str) -> f64 {
+    match metric {
+        "makespan" => metrics.makespan as f64,
+        "utilization" => metrics.utilization,
+        "mean_wait" => metrics.mean_wait,
+        "mean_turnaround" => metrics.mean_turnaround,
+        _ => 0.0,
+    }
+}
+
+// Name and quantum columns of a policy.
+fn columns(policy: Policy) -> (&'static str, String) {
+    match policy {
+        Policy::Fcfs => ("fcfs", String::new()),
+        Policy::Easy => ("easy", String::new()),
+        Policy::Conservative => ("conservative", String::new()),
+        Policy::Priority => ("priority", String::new()),
+        Policy::RoundRobin { quantum } => ("rr", quantum.to_string()),
+    }
+}
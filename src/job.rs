@@ -2,7 +2,7 @@
 ///
 /// This trait allows you to obtain only the right States from the automaton.
 ///
-/// The states : `New, Ready, Running, Bloked, Zombie, Terminated`
+/// The states : `New, Ready, Running, Bloked, Stopped, SuspendedReady, SuspendedBlocked, Zombie, Terminated`
 pub trait State {}
 
 /// State New
@@ -11,6 +11,7 @@ pub trait State {}
 ///
 ///
 #[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct New {
     pub duration: u64,
     pub priority: u32,
@@ -23,6 +24,7 @@ impl State for New {}
 ///
 ///
 #[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Ready {
     pub duration: u64,
     pub priority: u32,
@@ -35,6 +37,7 @@ impl State for Ready {}
 ///
 ///
 #[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Running {
     pub duration: u64,
     pub priority: u32,
@@ -47,18 +50,59 @@ impl State for Running {}
 ///
 ///
 #[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Blocked {
     pub duration: u64,
     pub priority: u32,
 }
 impl State for Blocked {}
 
+/// State Stopped
+///
+/// The stopped state is optainable by sending a STOP signal to a ready, running or blocked job. A CONT signal makes it ready again.
+///
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Stopped {
+    pub duration: u64,
+    pub priority: u32,
+}
+impl State for Stopped {}
+
+/// State SuspendedReady
+///
+/// The suspended ready state is optainable by swapping out a ready or running job. The job is ready to run once it is swapped in.
+///
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SuspendedReady {
+    pub duration: u64,
+    pub priority: u32,
+}
+impl State for SuspendedReady {}
+
+/// State SuspendedBlocked
+///
+/// The suspended blocked state is optainable by swapping out a blocked job. The job becomes suspended ready when its event occurs.
+///
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SuspendedBlocked {
+    pub duration: u64,
+    pub priority: u32,
+}
+impl State for SuspendedBlocked {}
+
 /// State Zombie
 ///
 /// The zombie state is optainable at the creation of the job.
 ///
 ///
 #[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Zombie {}
 impl State for Zombie {}
 
@@ -68,9 +112,72 @@ impl State for Zombie {}
 ///
 ///
 #[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Terminated {}
 impl State for Terminated {}
 
+/// Runtime state
+///
+/// The state of a job known only at runtime, one variant per typestate.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum JobState {
+    New,
+    Ready,
+    Running,
+    Blocked,
+    Stopped,
+    SuspendedReady,
+    SuspendedBlocked,
+    Zombie,
+    Terminated,
+}
+
+impl std::fmt::Display for JobState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            JobState::New => "new",
+            JobState::Ready => "ready",
+            JobState::Running => "running",
+            JobState::Blocked => "blocked",
+            JobState::Stopped => "stopped",
+            JobState::SuspendedReady => "suspended ready",
+            JobState::SuspendedBlocked => "suspended blocked",
+            JobState::Zombie => "zombie",
+            JobState::Terminated => "terminated",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Exit reason
+///
+/// Why a job became a zombie.
+///
+/// * `Completed` : the job ran for its whole duration.
+/// * `Killed` : the job was terminated by a signal.
+/// * `Cancelled` : the job was cancelled by the scheduler or the user.
+/// * `DependencyNeverSatisfied` : the job was cancelled because one of its dependencies can never be satisfied.
+/// * `Timeout` : the job was killed for exceeding its time limit.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ExitReason {
+    Completed,
+    Killed,
+    Cancelled,
+    DependencyNeverSatisfied,
+    Timeout,
+}
+
+impl ExitReason {
+    /// Success
+    ///
+    /// Only a completed job is successful.
+    pub fn is_success(&self) -> bool {
+        *self == ExitReason::Completed
+    }
+}
+
 /// Job Creation
 ///
 /// # Example :
@@ -114,6 +221,7 @@ impl Default for Job<New> {
 ///
 /// Pid, parent pid, state
 #[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Job<Status: State> {
     pub pid: u64,
     pub parent: u64,
@@ -223,3 +331,235 @@ impl From<Job<New>> for Job<Ready> {
         }
     }
 }
+
+impl From<Job<Ready>> for Job<Stopped> {
+    fn from(prev: Job<Ready>) -> Job<Stopped> {
+        Job {
+            pid: prev.pid,
+            parent: prev.parent,
+            state: Stopped {
+                duration: prev.state.duration,
+                priority: prev.state.priority,
+            },
+        }
+    }
+}
+
+impl From<Job<Running>> for Job<Stopped> {
+    fn from(prev: Job<Running>) -> Job<Stopped> {
+        Job {
+            pid: prev.pid,
+            parent: prev.parent,
+            state: Stopped {
+                duration: prev.state.duration,
+                priority: prev.state.priority,
+            },
+        }
+    }
+}
+
+impl From<Job<Blocked>> for Job<Stopped> {
+    fn from(prev: Job<Blocked>) -> Job<Stopped> {
+        Job {
+            pid: prev.pid,
+            parent: prev.parent,
+            state: Stopped {
+                duration: prev.state.duration,
+                priority: prev.state.priority,
+            },
+        }
+    }
+}
+
+/// Job conversion from Stopped to Ready
+///
+/// # Example :
+/// ```rust, ignore
+/// # use filasse::job::*;
+///# let foo : Job<Ready>= Job::from(Job::new(5, 2, 2, 1));
+///# let bar : Job<Stopped> = Job::from(foo);
+/// let foo: Job<Ready> = Job::from(bar);
+/// # assert!(foo.state == Ready{duration: 2, priority: 1});
+/// ```
+impl From<Job<Stopped>> for Job<Ready> {
+    fn from(prev: Job<Stopped>) -> Job<Ready> {
+        Job {
+            pid: prev.pid,
+            parent: prev.parent,
+            state: Ready {
+                duration: prev.state.duration,
+                priority: prev.state.priority,
+            },
+        }
+    }
+}
+
+impl From<Job<Ready>> for Job<Zombie> {
+    fn from(prev: Job<Ready>) -> Job<Zombie> {
+        Job {
+            pid: prev.pid,
+            parent: prev.parent,
+            state: Zombie {},
+        }
+    }
+}
+
+impl From<Job<Blocked>> for Job<Zombie> {
+    fn from(prev: Job<Blocked>) -> Job<Zombie> {
+        Job {
+            pid: prev.pid,
+            parent: prev.parent,
+            state: Zombie {},
+        }
+    }
+}
+
+impl From<Job<Stopped>> for Job<Zombie> {
+    fn from(prev: Job<Stopped>) -> Job<Zombie> {
+        Job {
+            pid: prev.pid,
+            parent: prev.parent,
+            state: Zombie {},
+        }
+    }
+}
+
+/// Job conversion from Ready to SuspendedReady
+///
+/// # Example :
+/// ```rust, ignore
+/// # use filasse::job::*;
+///# let foo : Job<Ready>= Job::from(Job::new(5, 2, 2, 1));
+/// let bar: Job<SuspendedReady> = Job::from(foo);
+/// # assert!(bar.state == SuspendedReady{duration: 2, priority: 1});
+/// ```
+impl From<Job<Ready>> for Job<SuspendedReady> {
+    fn from(prev: Job<Ready>) -> Job<SuspendedReady> {
+        Job {
+            pid: prev.pid,
+            parent: prev.parent,
+            state: SuspendedReady {
+                duration: prev.state.duration,
+                priority: prev.state.priority,
+            },
+        }
+    }
+}
+
+impl From<Job<SuspendedReady>> for Job<Ready> {
+    fn from(prev: Job<SuspendedReady>) -> Job<Ready> {
+        Job {
+            pid: prev.pid,
+            parent: prev.parent,
+            state: Ready {
+                duration: prev.state.duration,
+                priority: prev.state.priority,
+            },
+        }
+    }
+}
+
+impl From<Job<Running>> for Job<SuspendedReady> {
+    fn from(prev: Job<Running>) -> Job<SuspendedReady> {
+        Job {
+            pid: prev.pid,
+            parent: prev.parent,
+            state: SuspendedReady {
+                duration: prev.state.duration,
+                priority: prev.state.priority,
+            },
+        }
+    }
+}
+
+impl From<Job<New>> for Job<SuspendedReady> {
+    fn from(prev: Job<New>) -> Job<SuspendedReady> {
+        Job {
+            pid: prev.pid,
+            parent: prev.parent,
+            state: SuspendedReady {
+                duration: prev.state.duration,
+                priority: prev.state.priority,
+            },
+        }
+    }
+}
+
+/// Job conversion from Blocked to SuspendedBlocked
+///
+/// # Example :
+/// ```rust, ignore
+/// # use filasse::job::*;
+///# let foo : Job<Running>= Job::from(Job::from(Job::new(5, 2, 2, 1)));
+///# let foo : Job<Blocked>= Job::from(foo);
+/// let bar: Job<SuspendedBlocked> = Job::from(foo);
+/// # assert!(bar.state == SuspendedBlocked{duration: 2, priority: 1});
+/// ```
+impl From<Job<Blocked>> for Job<SuspendedBlocked> {
+    fn from(prev: Job<Blocked>) -> Job<SuspendedBlocked> {
+        Job {
+            pid: prev.pid,
+            parent: prev.parent,
+            state: SuspendedBlocked {
+                duration: prev.state.duration,
+                priority: prev.state.priority,
+            },
+        }
+    }
+}
+
+impl From<Job<SuspendedBlocked>> for Job<Blocked> {
+    fn from(prev: Job<SuspendedBlocked>) -> Job<Blocked> {
+        Job {
+            pid: prev.pid,
+            parent: prev.parent,
+            state: Blocked {
+                duration: prev.state.duration,
+                priority: prev.state.priority,
+            },
+        }
+    }
+}
+
+impl From<Job<SuspendedBlocked>> for Job<SuspendedReady> {
+    fn from(prev: Job<SuspendedBlocked>) -> Job<SuspendedReady> {
+        Job {
+            pid: prev.pid,
+            parent: prev.parent,
+            state: SuspendedReady {
+                duration: prev.state.duration,
+                priority: prev.state.priority,
+            },
+        }
+    }
+}
+
+impl From<Job<SuspendedReady>> for Job<Zombie> {
+    fn from(prev: Job<SuspendedReady>) -> Job<Zombie> {
+        Job {
+            pid: prev.pid,
+            parent: prev.parent,
+            state: Zombie {},
+        }
+    }
+}
+
+impl From<Job<SuspendedBlocked>> for Job<Zombie> {
+    fn from(prev: Job<SuspendedBlocked>) -> Job<Zombie> {
+        Job {
+            pid: prev.pid,
+            parent: prev.parent,
+            state: Zombie {},
+        }
+    }
+}
+
+impl From<Job<New>> for Job<Zombie> {
+    fn from(prev: Job<New>) -> Job<Zombie> {
+        Job {
+            pid: prev.pid,
+            parent: prev.parent,
+            state: Zombie {},
+        }
+    }
+}
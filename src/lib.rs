@@ -1,2 +1,31 @@
+pub mod any_job;
+pub mod array;
+pub mod backfill;
+pub mod batch;
+#[cfg(feature = "cli")]
+pub mod cli;
+pub mod cluster;
+pub mod dependency;
+pub mod device;
+#[cfg(feature = "serde")]
+pub mod eventlog;
+pub mod fairshare;
+pub mod generator;
+pub mod harness;
 pub mod job;
+pub mod metrics;
+pub mod observer;
+pub mod policy;
+pub mod preemption;
+pub mod priority;
+pub mod registry;
+pub mod replay;
+pub mod report;
+pub mod reservation;
 pub mod scheduler;
+pub mod signal;
+pub mod swap;
+pub mod swf;
+pub mod workflow;
+#[cfg(feature = "workload")]
+pub mod workload;
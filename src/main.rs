@@ -1,19 +1,19 @@
-use filasse::job::*;
-use filasse::scheduler::*;
+use filasse::cli::*;
+use std::process::ExitCode;
 
-fn main() {
-    let mut sched = Scheduler::new(1);
-    let mut job1 = Job::new(1, 2, 2, 2);
-    let mut job2 = Job::new(1, 3, 3, 3);
-    let mut job3 = Job::new(1, 4, 4, 4);
-
-    sched.add_to_scheduler(&mut job1);
-    sched.add_to_scheduler(&mut job2);
-    sched.add_to_scheduler(&mut job3);
-
-    sched.round_robin();
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match parse(&args).and_then(|command| run(&command)) {
+        Ok(output) => {
+            print!("{}", output);
+            ExitCode::SUCCESS
+        }
+        Err(error) => {
+            eprintln!("filasse: {}", error);
+            if let CliError::Usage(_) = error {
+                eprintln!("{}", USAGE);
+            }
+            ExitCode::FAILURE
+        }
+    }
 }
-
-// fn main() {
-// println!("Hellow, world!");
-// }
@@ -0,0 +1,107 @@
+use crate::batch::*;
+use crate::job::*;
+use crate::registry::*;
+use std::collections::HashMap;
+use std::fmt;
+
+/// Metrics of a run
+///
+/// `exits` counts the jobs which exited by reason. The times and the preemptions are only known for batch runs and stay at 0 otherwise, `lost_work` being the core-seconds lost by the preempted jobs.
+///
+/// # Example :
+/// ```rust, ignore
+///# use filasse::batch::*;
+///# use filasse::cluster::*;
+///# use filasse::metrics::*;
+/// let mut batch = BatchScheduler::new(Cluster::homogeneous(1, 4, 8));
+/// batch.submit(BatchJob::new(1, 4, 8, 10).runtime(20)).unwrap();
+/// batch.run();
+/// assert!(batch.metrics().timeouts() == 1);
+///```
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Metrics {
+    pub jobs: usize,
+    pub exits: HashMap<ExitReason, usize>,
+    pub makespan: u64,
+    pub utilization: f64,
+    pub mean_wait: f64,
+    pub mean_turnaround: f64,
+    pub preemptions: usize,
+    pub lost_work: u64,
+}
+
+impl fmt::Display for Metrics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "jobs            {}", self.jobs)?;
+        writeln!(f, "makespan        {}", self.makespan)?;
+        writeln!(f, "utilization     {:.4}", self.utilization)?;
+        writeln!(f, "mean wait       {:.2}", self.mean_wait)?;
+        writeln!(f, "mean turnaround {:.2}", self.mean_turnaround)?;
+        let mut exits: Vec<(String, usize)> = self
+            .exits
+            .iter()
+            .map(|(reason, &count)| (format!("{:?}", reason), count))
+            .collect();
+        exits.sort();
+        for (reason, count) in exits {
+            writeln!(f, "{:<15} {}", reason.to_lowercase(), count)?;
+        }
+        writeln!(f, "preemptions     {}", self.preemptions)?;
+        write!(f, "lost work       {}", self.lost_work)
+    }
+}
+
+impl Metrics {
+    /// Metrics of batch records
+    ///
+    /// `cores` is the number of cores of the cluster.
+    pub fn from_records(records: &[Record], cores: u64) -> Self {
+        let mut metrics = Metrics {
+            jobs: records.len(),
+            makespan: records.iter().map(|r| r.end).max().unwrap_or(0),
+            ..Metrics::default()
+        };
+        for record in records {
+            *metrics.exits.entry(record.exit).or_insert(0) += 1;
+        }
+        if records.is_empty() {
+            return metrics;
+        }
+        let used: u64 = records
+            .iter()
+            .map(|r| r.resources.total_cores() * r.run_time())
+            .sum();
+        if cores * metrics.makespan > 0 {
+            metrics.utilization = used as f64 / (cores * metrics.makespan) as f64;
+        }
+        let count = records.len() as f64;
+        metrics.mean_wait = records.iter().map(|r| r.wait()).sum::<u64>() as f64 / count;
+        metrics.mean_turnaround =
+            records.iter().map(|r| r.turnaround()).sum::<u64>() as f64 / count;
+        metrics
+    }
+
+    /// Metrics of a registry
+    ///
+    /// Only the exit reasons of the jobs are known.
+    pub fn from_registry(registry: &Registry) -> Self {
+        let mut metrics = Metrics {
+            jobs: registry.len(),
+            ..Metrics::default()
+        };
+        for reason in registry.iter().filter_map(|entry| entry.exit) {
+            *metrics.exits.entry(reason).or_insert(0) += 1;
+        }
+        metrics
+    }
+
+    /// Count of an exit reason
+    pub fn count(&self, reason: ExitReason) -> usize {
+        self.exits.get(&reason).copied().unwrap_or(0)
+    }
+
+    /// Number of timeouts
+    pub fn timeouts(&self) -> usize {
+        self.count(ExitReason::Timeout)
+    }
+}
@@ -0,0 +1,156 @@
+use crate::any_job::*;
+use crate::job::*;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+/// Events of the scheduler
+///
+/// * `Submit` : a job enters the scheduler, ready or waiting for its dependencies.
+/// * `Dispatch` : a ready job runs on the processor.
+/// * `Preempt` : the running job leaves the processor before the end of its quantum, requeued, stopped or suspended.
+/// * `Block` : the running job waits for a lock or an I/O.
+/// * `Unblock` : the lock or the I/O of a blocked job is done.
+/// * `Exit` : a job becomes a zombie.
+/// * `Reap` : a zombie is reaped by its parent.
+/// * `QuantumExpiry` : the running job used its whole quantum and goes back to the queue.
+/// * `Other` : any other transition, like the admission of a job whose dependencies are satisfied, a suspension, a resumption, a stop or a continuation outside of the processor.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Event {
+    Submit,
+    Dispatch,
+    Preempt,
+    Block,
+    Unblock,
+    Exit,
+    Reap,
+    QuantumExpiry,
+    Other,
+}
+
+/// Lengths of the queues of the scheduler
+///
+/// `suspended` counts the suspended ready and the suspended blocked jobs.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Queues {
+    pub waiting: usize,
+    pub ready: usize,
+    pub held: usize,
+    pub blocked: usize,
+    pub stopped: usize,
+    pub suspended: usize,
+    pub zombie: usize,
+}
+
+/// Transition of a job
+///
+/// The job in its new state, the states before and after the transition, the clock of the scheduler and the lengths of its queues after the transition. `exit` is the exit reason of a zombie or a terminated job.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Transition {
+    pub job: AnyJob,
+    pub old: JobState,
+    pub new: JobState,
+    pub time: u64,
+    pub exit: Option<ExitReason>,
+    pub queues: Queues,
+}
+
+/// Observer of the scheduler
+///
+/// Every callback does nothing by default. `on_event` is called for every transition and calls the callback of the event, an observer interested in all the events may override it alone.
+///
+/// # Example :
+/// ```rust, ignore
+///# use filasse::observer::*;
+///# use filasse::scheduler::*;
+///# use std::sync::{Arc, Mutex};
+/// #[derive(Default)]
+/// struct Dispatches(usize);
+///
+/// impl SchedulerObserver for Dispatches {
+///     fn on_dispatch(&mut self, _: &Transition) {
+///         self.0 += 1;
+///     }
+/// }
+///
+/// let dispatches = Arc::new(Mutex::new(Dispatches::default()));
+/// let mut sched = Scheduler::new(10);
+/// sched.add_observer(dispatches.clone());
+///```
+pub trait SchedulerObserver {
+    fn on_event(&mut self, event: Event, transition: &Transition) {
+        match event {
+            Event::Submit => self.on_submit(transition),
+            Event::Dispatch => self.on_dispatch(transition),
+            Event::Preempt => self.on_preempt(transition),
+            Event::Block => self.on_block(transition),
+            Event::Unblock => self.on_unblock(transition),
+            Event::Exit => self.on_exit(transition),
+            Event::Reap => self.on_reap(transition),
+            Event::QuantumExpiry => self.on_quantum_expiry(transition),
+            Event::Other => self.on_transition(transition),
+        }
+    }
+
+    fn on_submit(&mut self, _transition: &Transition) {}
+
+    fn on_dispatch(&mut self, _transition: &Transition) {}
+
+    fn on_preempt(&mut self, _transition: &Transition) {}
+
+    fn on_block(&mut self, _transition: &Transition) {}
+
+    fn on_unblock(&mut self, _transition: &Transition) {}
+
+    fn on_exit(&mut self, _transition: &Transition) {}
+
+    fn on_reap(&mut self, _transition: &Transition) {}
+
+    fn on_quantum_expiry(&mut self, _transition: &Transition) {}
+
+    fn on_transition(&mut self, _transition: &Transition) {}
+}
+
+/// Observer shared with the scheduler
+///
+/// The caller keeps a clone to read the observer back.
+pub type SharedObserver = Arc<Mutex<dyn SchedulerObserver + Send>>;
+
+// Observers registered in a scheduler, by id. A clone of the scheduler shares them, a snapshot does not keep them.
+#[derive(Clone, Default)]
+pub(crate) struct Observers {
+    next: usize,
+    list: Vec<(usize, SharedObserver)>,
+}
+
+impl Observers {
+    pub(crate) fn add(&mut self, observer: SharedObserver) -> usize {
+        let id = self.next;
+        self.next += 1;
+        self.list.push((id, observer));
+        id
+    }
+
+    pub(crate) fn remove(&mut self, id: usize) -> Option<SharedObserver> {
+        let index = self.list.iter().position(|(i, _)| *i == id)?;
+        Some(self.list.remove(index).1)
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    pub(crate) fn notify(&self, event: Event, transition: &Transition) {
+        for (_, observer) in self.list.iter() {
+            let mut observer = observer.lock().unwrap_or_else(|e| e.into_inner());
+            observer.on_event(event, transition);
+        }
+    }
+}
+
+impl fmt::Debug for Observers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} observers", self.list.len())
+    }
+}
@@ -0,0 +1,361 @@
+use crate::backfill::*;
+use crate::batch::*;
+use crate::cluster::*;
+use crate::dependency::*;
+use crate::job::*;
+use crate::metrics::*;
+use crate::observer::*;
+use crate::priority::*;
+use crate::scheduler::*;
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+
+/// Default quantum of round robin
+pub const DEFAULT_QUANTUM: u64 = 100;
+
+/// Errors of a policy
+///
+/// * `UnknownPolicy` : no policy has this name.
+/// * `InvalidParameter` : the parameter of the policy is not valid.
+/// * `Cpus` : the policy can not run on this number of CPUs.
+/// * `Batch` : a job was rejected by the batch scheduler.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PolicyError {
+    UnknownPolicy(String),
+    InvalidParameter(String),
+    Cpus { policy: Policy, cpus: u32 },
+    Batch(BatchError),
+}
+
+impl fmt::Display for PolicyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PolicyError::UnknownPolicy(name) => write!(f, "unknown policy {}", name),
+            PolicyError::InvalidParameter(parameter) => {
+                write!(f, "invalid policy parameter {}", parameter)
+            }
+            PolicyError::Cpus { policy, cpus } => {
+                write!(f, "policy {} can not run on {} cpus", policy, cpus)
+            }
+            PolicyError::Batch(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for PolicyError {}
+
+impl From<BatchError> for PolicyError {
+    fn from(error: BatchError) -> Self {
+        PolicyError::Batch(error)
+    }
+}
+
+/// Scheduling policies
+///
+/// * `Fcfs` : batch mode, first come, first served.
+/// * `Easy` : batch mode with EASY backfilling.
+/// * `Conservative` : batch mode with conservative backfilling.
+/// * `Priority` : batch mode ordered by the default multifactor priority.
+/// * `RoundRobin` : time-sharing of one CPU, every job running one quantum in turn.
+///
+/// A policy is named by `fcfs`, `easy`, `conservative`, `priority` or `rr`, the quantum of round robin following a colon, like `rr:10`.
+///
+/// # Example :
+/// ```rust, ignore
+///# use filasse::policy::*;
+/// let policy: Policy = "rr:10".parse().unwrap();
+/// assert!(policy == Policy::RoundRobin { quantum: 10 });
+///```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Policy {
+    Fcfs,
+    Easy,
+    Conservative,
+    Priority,
+    RoundRobin { quantum: u64 },
+}
+
+impl fmt::Display for Policy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Policy::Fcfs => write!(f, "fcfs"),
+            Policy::Easy => write!(f, "easy"),
+            Policy::Conservative => write!(f, "conservative"),
+            Policy::Priority => write!(f, "priority"),
+            Policy::RoundRobin { quantum } => write!(f, "rr:{}", quantum),
+        }
+    }
+}
+
+impl FromStr for Policy {
+    type Err = PolicyError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        let (name, parameter) = match name.split_once(':') {
+            Some((name, parameter)) => (name, Some(parameter)),
+            None => (name, None),
+        };
+        let policy = match name.to_lowercase().as_str() {
+            "fcfs" => Policy::Fcfs,
+            "easy" => Policy::Easy,
+            "conservative" => Policy::Conservative,
+            "priority" => Policy::Priority,
+            "rr" | "round-robin" => {
+                let quantum = match parameter {
+                    Some(quantum) => quantum
+                        .parse()
+                        .ok()
+                        .filter(|&q| q > 0)
+                        .ok_or_else(|| PolicyError::InvalidParameter(quantum.to_string()))?,
+                    None => DEFAULT_QUANTUM,
+                };
+                return Ok(Policy::RoundRobin { quantum });
+            }
+            _ => return Err(PolicyError::UnknownPolicy(name.to_string())),
+        };
+        match parameter {
+            Some(parameter) => Err(PolicyError::InvalidParameter(parameter.to_string())),
+            None => Ok(policy),
+        }
+    }
+}
+
+/// Decision of a scheduler
+///
+/// The job, by its position in the workload from 1, is put on the processors at `time`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Decision {
+    pub time: u64,
+    pub job: u64,
+}
+
+/// Result of a simulation
+///
+/// The records of the jobs, their ids being their positions in the workload from 1, their metrics and the dispatches by time. A batch job is dispatched once, when it starts, round robin dispatches a job for each of its quanta.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Simulation {
+    pub policy: Policy,
+    pub cpus: u32,
+    pub records: Vec<Record>,
+    pub metrics: Metrics,
+    pub dispatches: Vec<Decision>,
+}
+
+impl Policy {
+    /// Simulate a workload
+    ///
+    /// The batch policies run on `cpus` nodes of one core, a job asking for one node per processor. Round robin needs a single CPU, each job running for its runtime, and the jobs are admitted at the end of the quantum during which they are submitted. The dependencies of a job are the positions of other jobs of the workload from 1 : it starts once they completed, and is cancelled if one of them did not. A workload with a dependency outside it or a cycle of dependencies is rejected.
+    ///
+    /// # Example :
+    /// ```rust, ignore
+    ///# use filasse::batch::*;
+    ///# use filasse::policy::*;
+    /// let jobs = vec![BatchJob::new(2, 1, 0, 10), BatchJob::new(1, 1, 0, 5).submit(3)];
+    /// let simulation = Policy::Easy.simulate(&jobs, 4).unwrap();
+    /// println!("{}", simulation.metrics.makespan);
+    ///```
+    pub fn simulate(&self, jobs: &[BatchJob], cpus: u32) -> Result<Simulation, PolicyError> {
+        check_dependencies(jobs)?;
+        let (records, dispatches) = match *self {
+            Policy::RoundRobin { quantum } if cpus == 1 => round_robin(jobs, quantum),
+            Policy::RoundRobin { .. } => {
+                return Err(PolicyError::Cpus {
+                    policy: *self,
+                    cpus,
+                })
+            }
+            _ => {
+                let records = self.batch(jobs, cpus)?;
+                let mut starts: Vec<Decision> = records
+                    .iter()
+                    .map(|r| Decision {
+                        time: r.start,
+                        job: r.id,
+                    })
+                    .collect();
+                starts.sort();
+                (records, starts)
+            }
+        };
+        let mut metrics = Metrics::from_records(&records, cpus as u64);
+        if let Policy::RoundRobin { .. } = self {
+            // The jobs share the CPU between their start and their end : only their runtimes are used.
+            let used: u64 = jobs.iter().map(|job| job.runtime).sum();
+            if metrics.makespan > 0 {
+                metrics.utilization = used as f64 / metrics.makespan as f64;
+            }
+        }
+        Ok(Simulation {
+            policy: *self,
+            cpus,
+            metrics,
+            records,
+            dispatches,
+        })
+    }
+
+    fn batch(&self, jobs: &[BatchJob], cpus: u32) -> Result<Vec<Record>, PolicyError> {
+        let memory = jobs
+            .iter()
+            .map(|job| job.resources.memory)
+            .max()
+            .unwrap_or(0);
+        let mut batch = BatchScheduler::new(Cluster::homogeneous(cpus as usize, 1, memory));
+        match self {
+            Policy::Easy => batch.set_backfill(Backfill::Easy),
+            Policy::Conservative => batch.set_backfill(Backfill::Conservative),
+            Policy::Priority => batch.set_priority(Some(Multifactor::default())),
+            _ => {}
+        }
+        for job in jobs {
+            batch.submit(job.clone())?;
+        }
+        batch.run();
+        Ok(batch.finished().to_vec())
+    }
+}
+
+// Dependencies on jobs of the workload, without cycle.
+fn check_dependencies(jobs: &[BatchJob]) -> Result<(), BatchError> {
+    for (i, job) in jobs.iter().enumerate() {
+        if let Some(&id) = job
+            .dependencies
+            .iter()
+            .find(|&&id| id == 0 || id > jobs.len() as u64 || id == i as u64 + 1)
+        {
+            return Err(BatchError::InvalidDependency(id));
+        }
+    }
+    // 0 unvisited, 1 on the path, 2 done.
+    let mut marks = vec![0u8; jobs.len()];
+    for root in 0..jobs.len() {
+        if marks[root] != 0 {
+            continue;
+        }
+        let mut stack = vec![(root, 0)];
+        marks[root] = 1;
+        while let Some(&mut (node, ref mut next)) = stack.last_mut() {
+            match jobs[node].dependencies.get(*next) {
+                Some(&id) => {
+                    *next += 1;
+                    let child = id as usize - 1;
+                    match marks[child] {
+                        0 => {
+                            marks[child] = 1;
+                            stack.push((child, 0));
+                        }
+                        1 => return Err(BatchError::CyclicDependency(id)),
+                        _ => {}
+                    }
+                }
+                None => {
+                    marks[node] = 2;
+                    stack.pop();
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+// Dispatches of a scheduler, by pid.
+#[derive(Default)]
+struct Dispatches(Vec<(u64, u64)>);
+
+impl SchedulerObserver for Dispatches {
+    fn on_dispatch(&mut self, transition: &Transition) {
+        self.0.push((transition.time, transition.job.pid()));
+    }
+}
+
+// Records and dispatches of the jobs time-sharing one CPU. A job is admitted once it is submitted and its dependencies are admitted, the scheduler holding it until they complete.
+fn round_robin(jobs: &[BatchJob], quantum: u64) -> (Vec<Record>, Vec<Decision>) {
+    let mut arrivals: Vec<usize> = (0..jobs.len()).collect();
+    arrivals.sort_by_key(|&i| jobs[i].submit);
+    let mut sched = Scheduler::new(quantum);
+    let dispatches = Arc::new(Mutex::new(Dispatches::default()));
+    sched.add_observer(dispatches.clone());
+    let mut ids = HashMap::new();
+    let mut pids = HashMap::new();
+    let mut live: Vec<(u64, usize, Option<u64>)> = Vec::new();
+    let mut records = Vec::new();
+    loop {
+        while let Some(next) = arrivals.iter().position(|&i| {
+            jobs[i].submit <= sched.clock()
+                && jobs[i].dependencies.iter().all(|id| pids.contains_key(id))
+        }) {
+            let index = arrivals.remove(next);
+            let job = &jobs[index];
+            let mut new = Job::new(0, 0, job.runtime, 0);
+            let pid = if job.dependencies.is_empty() {
+                let pid = sched.pid_count();
+                sched.add_to_scheduler(&mut new);
+                pid
+            } else {
+                let dependencies = job
+                    .dependencies
+                    .iter()
+                    .map(|id| Dependency::AfterOk(pids[id]))
+                    .collect();
+                sched.add_with_dependencies(&mut new, dependencies).unwrap()
+            };
+            ids.insert(pid, index as u64 + 1);
+            pids.insert(index as u64 + 1, pid);
+            live.push((pid, index, None));
+        }
+        let next = arrivals
+            .iter()
+            .map(|&i| jobs[i].submit)
+            .filter(|&submit| submit > sched.clock())
+            .min();
+        match sched.dispatch() {
+            Some(pid) => {
+                let clock = sched.clock();
+                if let Some(entry) = live.iter_mut().find(|(p, _, _)| *p == pid) {
+                    entry.2.get_or_insert(clock);
+                }
+                sched.process();
+                sched.reap_init();
+            }
+            None => match next {
+                Some(submit) => {
+                    sched.idle(submit);
+                }
+                None => break,
+            },
+        }
+        let clock = sched.clock();
+        live.retain(|&(pid, index, start)| {
+            let exit = sched.registry().get(pid).and_then(|entry| entry.exit);
+            match exit {
+                Some(exit) => {
+                    let job = &jobs[index];
+                    records.push(Record {
+                        id: index as u64 + 1,
+                        resources: job.resources,
+                        submit: job.submit,
+                        start: start.unwrap_or(clock),
+                        end: clock,
+                        exit,
+                        suspended: 0,
+                    });
+                    false
+                }
+                None => true,
+            }
+        });
+    }
+    let dispatches = dispatches
+        .lock()
+        .unwrap()
+        .0
+        .iter()
+        .map(|&(time, pid)| Decision {
+            time,
+            job: ids[&pid],
+        })
+        .collect();
+    (records, dispatches)
+}
@@ -0,0 +1,161 @@
+use crate::batch::*;
+use crate::cluster::*;
+use std::collections::HashMap;
+
+/// Preemption modes
+///
+/// What happens to a running job preempted by a job of a higher tier :
+///
+/// * `Off` : the job is never preempted.
+/// * `Suspend` : the job is stopped, keeps its memory and gives its cores back until it resumes.
+/// * `Requeue` : the job goes back to the pending queue and restarts from its last checkpoint.
+/// * `Cancel` : the job is cancelled.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub enum PreemptMode {
+    #[default]
+    Off,
+    Suspend,
+    Requeue,
+    Cancel,
+}
+
+/// Cost of preempting a job
+///
+/// The candidates of lowest cost are preempted first.
+///
+/// * `LostWork` : the core-seconds lost by the job under its preemption mode.
+/// * `Youngest` : the time the job has run, so the most recent jobs go first.
+/// * `Smallest` : the number of cores of the job.
+/// * `Custom` : a function of the running job and the current time.
+#[derive(Debug, Copy, Clone, Default)]
+pub enum Cost {
+    #[default]
+    LostWork,
+    Youngest,
+    Smallest,
+    Custom(fn(&Execution, u64) -> f64),
+}
+
+/// Preemption settings of a QOS
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct QosPreemption {
+    pub tier: u32,
+    pub mode: PreemptMode,
+}
+
+/// Preemption of a running job
+///
+/// The job `id` was preempted by the job `by` at `time`, losing `lost` core-seconds of work.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Preempted {
+    pub id: u64,
+    pub by: u64,
+    pub time: u64,
+    pub mode: PreemptMode,
+    pub lost: u64,
+}
+
+/// Suspended job
+///
+/// A running job stopped at `since`, holding the memory of its allocation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Suspension {
+    pub execution: Execution,
+    pub since: u64,
+}
+
+impl Suspension {
+    /// Cores given back while suspended
+    pub fn cores(&self) -> Allocation {
+        Allocation {
+            memory: 0,
+            ..self.execution.allocation.clone()
+        }
+    }
+}
+
+/// Preemption policy
+///
+/// Each QOS has a tier and a preemption mode, like the QOS preemption of Slurm. A pending job which does not fit may preempt the running jobs of a strictly lower tier whose mode is not `Off`, chosen by increasing cost, and starts ahead of the queue if it fits without preempting anyone. A job without QOS, or with an unknown one, has tier 0 and is never preempted.
+///
+/// `checkpoint` is the interval between the checkpoints of the jobs, 0 meaning that a requeued job restarts from scratch.
+///
+/// # Example :
+/// ```rust, ignore
+///# use filasse::preemption::*;
+/// let mut policy = Preemption::new();
+/// policy.set_qos("high", 10, PreemptMode::Off);
+/// policy.set_qos("scavenger", 0, PreemptMode::Requeue);
+/// policy.checkpoint = 600;
+///```
+#[derive(Debug, Clone, Default)]
+pub struct Preemption {
+    pub cost: Cost,
+    pub checkpoint: u64,
+    qos: HashMap<String, QosPreemption>,
+}
+
+impl Preemption {
+    /// Initialisation method
+    pub fn new() -> Self {
+        Preemption::default()
+    }
+
+    /// Setter QOS
+    pub fn set_qos(&mut self, name: &str, tier: u32, mode: PreemptMode) {
+        self.qos
+            .insert(name.to_string(), QosPreemption { tier, mode });
+    }
+
+    /// Getter QOS
+    pub fn qos(&self, name: Option<&str>) -> QosPreemption {
+        name.and_then(|name| self.qos.get(name))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Preemption allowed
+    ///
+    /// Returns true if `preemptor` may preempt `victim`.
+    pub fn can_preempt(&self, preemptor: &BatchJob, victim: &BatchJob) -> bool {
+        let victim = self.qos(victim.qos.as_deref());
+        victim.mode != PreemptMode::Off && self.qos(preemptor.qos.as_deref()).tier > victim.tier
+    }
+
+    /// Mode of a job
+    pub fn mode(&self, job: &BatchJob) -> PreemptMode {
+        self.qos(job.qos.as_deref()).mode
+    }
+
+    /// Time saved by the last checkpoint
+    ///
+    /// The run time of `execution` at `now` rounded down to the checkpoint interval.
+    pub fn saved(&self, execution: &Execution, now: u64) -> u64 {
+        match self.checkpoint {
+            0 => 0,
+            interval => execution.run_time(now) / interval * interval,
+        }
+    }
+
+    /// Lost work
+    ///
+    /// The core-seconds lost if `execution` is preempted at `now` : nothing when suspended, the work since the last checkpoint when requeued, all of it when cancelled.
+    pub fn lost(&self, execution: &Execution, now: u64) -> u64 {
+        let run = match self.mode(&execution.job) {
+            PreemptMode::Off | PreemptMode::Suspend => 0,
+            PreemptMode::Requeue => execution.run_time(now) - self.saved(execution, now),
+            PreemptMode::Cancel => execution.run_time(now),
+        };
+        run * execution.job.resources.total_cores()
+    }
+
+    /// Cost of preempting a job
+    pub fn cost(&self, execution: &Execution, now: u64) -> f64 {
+        match self.cost {
+            Cost::LostWork => self.lost(execution, now) as f64,
+            Cost::Youngest => execution.run_time(now) as f64,
+            Cost::Smallest => execution.job.resources.total_cores() as f64,
+            Cost::Custom(cost) => cost(execution, now),
+        }
+    }
+}
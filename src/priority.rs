@@ -0,0 +1,208 @@
+use crate::batch::*;
+use std::collections::HashMap;
+use std::fmt;
+
+/// Weights of the priority factors
+///
+/// Each normalized factor, between 0 and 1, is multiplied by its weight.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Weights {
+    pub age: u32,
+    pub size: u32,
+    pub fairshare: u32,
+    pub qos: u32,
+    pub partition: u32,
+}
+
+impl Default for Weights {
+    fn default() -> Self {
+        Weights {
+            age: 1000,
+            size: 1000,
+            fairshare: 1000,
+            qos: 1000,
+            partition: 1000,
+        }
+    }
+}
+
+/// Jobs favored by the size factor
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum SizeFavor {
+    #[default]
+    Large,
+    Small,
+}
+
+/// Values of the priority factors
+///
+/// Either the normalized factors or their contributions to the priority.
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub struct Factors {
+    pub age: f64,
+    pub size: f64,
+    pub fairshare: f64,
+    pub qos: f64,
+    pub partition: f64,
+}
+
+impl Factors {
+    /// Sum of the factors
+    pub fn sum(&self) -> f64 {
+        self.age + self.size + self.fairshare + self.qos + self.partition
+    }
+}
+
+/// Explanation of a priority
+///
+/// The normalized factors of a pending job and the contribution of each of them.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Explanation {
+    pub id: u64,
+    pub factors: Factors,
+    pub contributions: Factors,
+    pub priority: f64,
+}
+
+impl fmt::Display for Explanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let c = &self.contributions;
+        write!(
+            f,
+            "job {} priority {:.0} = age {:.0} + size {:.0} + fairshare {:.0} + qos {:.0} + partition {:.0}",
+            self.id, self.priority, c.age, c.size, c.fairshare, c.qos, c.partition
+        )
+    }
+}
+
+/// Multifactor priority
+///
+/// The priority of a pending job is the weighted sum of normalized factors, like the multifactor plugin of Slurm :
+///
+/// * age : the time spent pending, up to `max_age`.
+/// * size : the share of the cores of the cluster asked, or its complement when favoring small jobs.
+/// * fairshare : the fair-share factor of the user, 1 when unknown.
+/// * qos : the priority of the QOS divided by the highest one.
+/// * partition : the priority of the partition divided by the highest one.
+///
+/// # Example :
+/// ```rust, ignore
+///# use filasse::priority::*;
+/// let mut policy = Multifactor::new(Weights { qos: 5000, ..Weights::default() });
+/// policy.set_qos("high", 10);
+/// policy.set_qos("normal", 1);
+/// policy.set_fairshare("alice", 0.25);
+///```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Multifactor {
+    pub weights: Weights,
+    pub max_age: u64,
+    pub favor: SizeFavor,
+    qos: HashMap<String, u32>,
+    partitions: HashMap<String, u32>,
+    fairshare: HashMap<String, f64>,
+}
+
+impl Default for Multifactor {
+    fn default() -> Self {
+        Multifactor::new(Weights::default())
+    }
+}
+
+impl Multifactor {
+    /// Initialisation method
+    ///
+    /// The maximum age is 7 days in seconds, like Slurm, and large jobs are favored.
+    pub fn new(weights: Weights) -> Self {
+        Multifactor {
+            weights,
+            max_age: 7 * 24 * 3600,
+            favor: SizeFavor::Large,
+            qos: HashMap::new(),
+            partitions: HashMap::new(),
+            fairshare: HashMap::new(),
+        }
+    }
+
+    /// Setter QOS priority
+    pub fn set_qos(&mut self, name: &str, priority: u32) {
+        self.qos.insert(name.to_string(), priority);
+    }
+
+    /// Setter partition priority
+    pub fn set_partition(&mut self, name: &str, priority: u32) {
+        self.partitions.insert(name.to_string(), priority);
+    }
+
+    /// Setter fair-share factor
+    ///
+    /// The factor of a user, clamped between 0 and 1.
+    pub fn set_fairshare(&mut self, user: &str, factor: f64) {
+        self.fairshare
+            .insert(user.to_string(), factor.clamp(0.0, 1.0));
+    }
+
+    /// Normalized factors
+    ///
+    /// The factors of `job` at time `now`, on a cluster of `cores` cores.
+    pub fn factors(&self, job: &BatchJob, now: u64, cores: u64) -> Factors {
+        let age = match self.max_age {
+            0 => 1.0,
+            max => now.saturating_sub(job.submit).min(max) as f64 / max as f64,
+        };
+        let share = match cores {
+            0 => 0.0,
+            cores => (job.resources.total_cores() as f64 / cores as f64).min(1.0),
+        };
+        Factors {
+            age,
+            size: match self.favor {
+                SizeFavor::Large => share,
+                SizeFavor::Small => 1.0 - share,
+            },
+            fairshare: job
+                .user
+                .as_ref()
+                .and_then(|user| self.fairshare.get(user))
+                .copied()
+                .unwrap_or(1.0),
+            qos: relative(&self.qos, job.qos.as_deref()),
+            partition: relative(&self.partitions, job.partition.as_deref()),
+        }
+    }
+
+    /// Explain a priority
+    ///
+    /// The factors of `job`, their weighted contributions and the resulting priority.
+    pub fn explain(&self, job: &BatchJob, now: u64, cores: u64) -> Explanation {
+        let factors = self.factors(job, now, cores);
+        let w = &self.weights;
+        let contributions = Factors {
+            age: factors.age * w.age as f64,
+            size: factors.size * w.size as f64,
+            fairshare: factors.fairshare * w.fairshare as f64,
+            qos: factors.qos * w.qos as f64,
+            partition: factors.partition * w.partition as f64,
+        };
+        Explanation {
+            id: job.id,
+            factors,
+            contributions,
+            priority: contributions.sum(),
+        }
+    }
+
+    /// Priority
+    pub fn priority(&self, job: &BatchJob, now: u64, cores: u64) -> f64 {
+        self.explain(job, now, cores).priority
+    }
+}
+
+// Priority of `name` divided by the highest priority of the table, 0 when unknown.
+fn relative(table: &HashMap<String, u32>, name: Option<&str>) -> f64 {
+    let max = table.values().copied().max().unwrap_or(0);
+    match name.and_then(|name| table.get(name)) {
+        Some(&priority) if max > 0 => priority as f64 / max as f64,
+        _ => 0.0,
+    }
+}
@@ -0,0 +1,200 @@
+use crate::job::*;
+use std::collections::{BTreeMap, HashMap};
+use std::ops::RangeInclusive;
+
+/// Entry of the registry
+///
+/// Pid, parent pid, current state and priority of a job, whether it has ever run and why it exited.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Entry {
+    pub pid: u64,
+    pub parent: u64,
+    pub state: JobState,
+    pub priority: u32,
+    pub started: bool,
+    pub exit: Option<ExitReason>,
+}
+
+impl Entry {
+    pub fn new(pid: u64, parent: u64, state: JobState, priority: u32) -> Self {
+        Entry {
+            pid,
+            parent,
+            state,
+            priority,
+            started: state == JobState::Running,
+            exit: None,
+        }
+    }
+}
+
+/// Filter of the registry
+///
+/// Every criterion left to `None` matches all the jobs.
+///
+/// # Example :
+/// ```rust, ignore
+///# use filasse::registry::*;
+///# use filasse::job::*;
+/// let filter = Filter::new().state(JobState::Ready).priority(1..=5);
+///```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Filter {
+    pub state: Option<JobState>,
+    pub parent: Option<u64>,
+    pub priority: Option<RangeInclusive<u32>>,
+}
+
+impl Filter {
+    pub fn new() -> Self {
+        Filter::default()
+    }
+
+    pub fn state(mut self, state: JobState) -> Self {
+        self.state = Some(state);
+        self
+    }
+
+    pub fn parent(mut self, parent: u64) -> Self {
+        self.parent = Some(parent);
+        self
+    }
+
+    pub fn priority(mut self, range: RangeInclusive<u32>) -> Self {
+        self.priority = Some(range);
+        self
+    }
+
+    /// Match
+    ///
+    /// Returns true if the entry meets every criterion of the filter.
+    pub fn matches(&self, entry: &Entry) -> bool {
+        self.state.is_none_or(|state| entry.state == state)
+            && self.parent.is_none_or(|parent| entry.parent == parent)
+            && self
+                .priority
+                .as_ref()
+                .is_none_or(|range| range.contains(&entry.priority))
+    }
+}
+
+/// Registry of the jobs
+///
+/// The jobs known by the scheduler indexed by pid, terminated jobs included.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Registry {
+    entries: BTreeMap<u64, Entry>,
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Registry::default()
+    }
+
+    /// Insert a job
+    ///
+    /// A job already registered with the same pid is replaced.
+    pub fn insert(&mut self, entry: Entry) {
+        self.entries.insert(entry.pid, entry);
+    }
+
+    /// Getter entry
+    pub fn get(&self, pid: u64) -> Option<&Entry> {
+        self.entries.get(&pid)
+    }
+
+    /// Getter state
+    pub fn state(&self, pid: u64) -> Option<JobState> {
+        self.entries.get(&pid).map(|entry| entry.state)
+    }
+
+    /// Setter state
+    ///
+    /// A job becoming running is marked as started.
+    pub fn set_state(&mut self, pid: u64, state: JobState) {
+        if let Some(entry) = self.entries.get_mut(&pid) {
+            entry.state = state;
+            entry.started |= state == JobState::Running;
+        }
+    }
+
+    /// Setter exit reason
+    pub fn set_exit(&mut self, pid: u64, reason: ExitReason) {
+        if let Some(entry) = self.entries.get_mut(&pid) {
+            entry.exit = Some(reason);
+        }
+    }
+
+    /// Setter parent
+    pub fn set_parent(&mut self, pid: u64, parent: u64) {
+        if let Some(entry) = self.entries.get_mut(&pid) {
+            entry.parent = parent;
+        }
+    }
+
+    /// Setter priority
+    pub fn set_priority(&mut self, pid: u64, priority: u32) {
+        if let Some(entry) = self.entries.get_mut(&pid) {
+            entry.priority = priority;
+        }
+    }
+
+    /// Alive
+    ///
+    /// Returns true if the job is registered and not terminated.
+    pub fn is_alive(&self, pid: u64) -> bool {
+        self.state(pid)
+            .is_some_and(|state| state != JobState::Terminated)
+    }
+
+    /// Number of jobs
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Iterator
+    ///
+    /// The entries in increasing pid order.
+    pub fn iter(&self) -> impl Iterator<Item = &Entry> {
+        self.entries.values()
+    }
+
+    /// Filter query
+    ///
+    /// The entries matching the filter, in increasing pid order.
+    ///
+    /// # Example :
+    /// ```rust, ignore
+    ///# use filasse::scheduler::*;
+    ///# use filasse::registry::*;
+    ///# use filasse::job::*;
+    /// let mut sched = Scheduler::default();
+    /// sched.add_to_scheduler(&mut Job::default());
+    /// let ready = sched.registry().filter(&Filter::new().state(JobState::Ready));
+    ///```
+    pub fn filter(&self, filter: &Filter) -> Vec<&Entry> {
+        self.iter().filter(|entry| filter.matches(entry)).collect()
+    }
+
+    /// Count of a state
+    pub fn count(&self, state: JobState) -> usize {
+        self.iter().filter(|entry| entry.state == state).count()
+    }
+
+    /// Counts per state
+    ///
+    /// The states without any job are not in the map.
+    pub fn counts(&self) -> HashMap<JobState, usize> {
+        let mut counts = HashMap::new();
+        for entry in self.iter() {
+            *counts.entry(entry.state).or_insert(0) += 1;
+        }
+        counts
+    }
+}
@@ -0,0 +1,231 @@
+use crate::batch::*;
+#[cfg(feature = "serde")]
+use crate::eventlog::*;
+#[cfg(feature = "serde")]
+use crate::observer::*;
+use crate::policy::*;
+#[cfg(feature = "serde")]
+use std::collections::HashMap;
+use std::fmt;
+
+/// Errors of a replay
+///
+/// * `Trace` : the line of the trace is not an event.
+/// * `Policy` : the policy can not simulate the workload.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReplayError {
+    Trace { line: usize, message: String },
+    Policy(PolicyError),
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplayError::Trace { line, message } => write!(f, "line {}: {}", line, message),
+            ReplayError::Policy(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+impl From<PolicyError> for ReplayError {
+    fn from(e: PolicyError) -> Self {
+        ReplayError::Policy(e)
+    }
+}
+
+/// First difference between two schedules
+///
+/// `index` is the position of the first decision which differs, `recorded` and `replayed` the decisions at this position, `None` when a schedule has fewer decisions.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Divergence {
+    pub index: usize,
+    pub recorded: Option<Decision>,
+    pub replayed: Option<Decision>,
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn decision(d: &Option<Decision>) -> String {
+            match d {
+                Some(d) => format!("job {} at {}", d.job, d.time),
+                None => String::from("nothing"),
+            }
+        }
+        write!(
+            f,
+            "decision {} differs : recorded {}, replayed {}",
+            self.index,
+            decision(&self.recorded),
+            decision(&self.replayed)
+        )
+    }
+}
+
+/// First divergence of two schedules
+///
+/// The decisions are compared in order of time, then of job, the simultaneous decisions being unordered. `None` if the schedules are the same.
+///
+/// # Example :
+/// ```rust, ignore
+///# use filasse::policy::*;
+///# use filasse::replay::*;
+/// let a = vec![Decision { time: 0, job: 1 }, Decision { time: 0, job: 2 }];
+/// let b = vec![Decision { time: 0, job: 2 }, Decision { time: 0, job: 1 }];
+/// assert!(divergence(&a, &b) == None);
+///```
+pub fn divergence(recorded: &[Decision], replayed: &[Decision]) -> Option<Divergence> {
+    let mut recorded = recorded.to_vec();
+    let mut replayed = replayed.to_vec();
+    recorded.sort();
+    replayed.sort();
+    let index =
+        (0..recorded.len().max(replayed.len())).find(|&i| recorded.get(i) != replayed.get(i))?;
+    Some(Divergence {
+        index,
+        recorded: recorded.get(index).copied(),
+        replayed: replayed.get(index).copied(),
+    })
+}
+
+/// Decisions of a trace
+///
+/// The start events of a trace written by `report::trace`, the header and the empty lines being skipped.
+pub fn trace_decisions(text: &str) -> Result<Vec<Decision>, ReplayError> {
+    let mut decisions = Vec::new();
+    for (number, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with("time,") {
+            continue;
+        }
+        let error = |message: &str| ReplayError::Trace {
+            line: number + 1,
+            message: message.to_string(),
+        };
+        let fields: Vec<&str> = line.split(',').collect();
+        if fields.len() != 5 {
+            return Err(error("expected 5 fields"));
+        }
+        match fields[1] {
+            "start" => decisions.push(Decision {
+                time: fields[0].parse().map_err(|_| error("invalid time"))?,
+                job: fields[2].parse().map_err(|_| error("invalid job"))?,
+            }),
+            "end" => {}
+            _ => return Err(error("unknown event")),
+        }
+    }
+    Ok(decisions)
+}
+
+/// Replay a trace
+///
+/// Simulates the workload of the trace with the policy and compares the starts of the jobs with the recorded ones.
+///
+/// # Example :
+/// ```rust, ignore
+///# use filasse::batch::*;
+///# use filasse::policy::*;
+///# use filasse::replay::*;
+///# use filasse::report::*;
+/// let jobs = vec![BatchJob::new(1, 1, 0, 10), BatchJob::new(1, 1, 0, 5)];
+/// let recorded = trace(&Policy::Fcfs.simulate(&jobs, 1).unwrap().records);
+/// assert!(replay_trace(Policy::Fcfs, &jobs, 1, &recorded).unwrap() == None);
+///```
+pub fn replay_trace(
+    policy: Policy,
+    jobs: &[BatchJob],
+    cpus: u32,
+    trace: &str,
+) -> Result<Option<Divergence>, ReplayError> {
+    let recorded = trace_decisions(trace)?;
+    let simulation = policy.simulate(jobs, cpus)?;
+    let replayed: Vec<Decision> = simulation
+        .records
+        .iter()
+        .map(|r| Decision {
+            time: r.start,
+            job: r.id,
+        })
+        .collect();
+    Ok(divergence(&recorded, &replayed))
+}
+
+/// Workload of an event log
+///
+/// A job of one core for every submitted job, in order of submission, its runtime being the time it spent on the processor.
+#[cfg(feature = "serde")]
+pub fn log_jobs(records: &[EventRecord]) -> Vec<BatchJob> {
+    let ids = log_ids(records);
+    let mut submits = vec![None; ids.len()];
+    let mut runtimes = vec![0; ids.len()];
+    let mut running = HashMap::new();
+    for r in records {
+        if let Some(&id) = ids.get(&r.pid) {
+            if r.event == Event::Submit {
+                submits[id as usize - 1].get_or_insert(r.time);
+            } else if r.event == Event::Dispatch {
+                running.insert(id, r.time);
+            } else if let Some(start) = running.remove(&id) {
+                runtimes[id as usize - 1] += r.time - start;
+            }
+        }
+    }
+    submits
+        .into_iter()
+        .zip(runtimes)
+        .map(|(submit, runtime)| {
+            BatchJob::new(1, 1, 0, runtime.max(1))
+                .runtime(runtime)
+                .submit(submit.unwrap_or(0))
+        })
+        .collect()
+}
+
+/// Decisions of an event log
+///
+/// The dispatches of the log, the jobs being numbered from 1 in order of submission.
+#[cfg(feature = "serde")]
+pub fn log_decisions(records: &[EventRecord]) -> Vec<Decision> {
+    let ids = log_ids(records);
+    records
+        .iter()
+        .filter(|r| r.event == Event::Dispatch)
+        .filter_map(|r| ids.get(&r.pid).map(|&job| Decision { time: r.time, job }))
+        .collect()
+}
+
+/// Replay an event log
+///
+/// Simulates the workload of the log with the policy and compares its dispatches with the recorded ones.
+///
+/// # Example :
+/// ```rust, ignore
+///# use filasse::eventlog::*;
+///# use filasse::policy::*;
+///# use filasse::replay::*;
+/// let records = read("events.jsonl").unwrap();
+/// let policy = Policy::RoundRobin { quantum: 10 };
+/// assert!(replay_log(policy, 1, &records).unwrap() == None);
+///```
+#[cfg(feature = "serde")]
+pub fn replay_log(
+    policy: Policy,
+    cpus: u32,
+    records: &[EventRecord],
+) -> Result<Option<Divergence>, ReplayError> {
+    let simulation = policy.simulate(&log_jobs(records), cpus)?;
+    Ok(divergence(&log_decisions(records), &simulation.dispatches))
+}
+
+// Ids of the submitted jobs by pid, from 1 in order of submission.
+#[cfg(feature = "serde")]
+fn log_ids(records: &[EventRecord]) -> HashMap<u64, u64> {
+    let mut ids = HashMap::new();
+    for r in records.iter().filter(|r| r.event == Event::Submit) {
+        let next = ids.len() as u64 + 1;
+        ids.entry(r.pid).or_insert(next);
+    }
+    ids
+}
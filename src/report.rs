@@ -0,0 +1,71 @@
+use crate::batch::*;
+
+/// Gantt chart
+///
+/// One line per record by increasing id, the time from 0 to the makespan scaled to `width` columns, `#` marking the execution of the job.
+///
+/// # Example :
+/// ```rust, ignore
+///# use filasse::batch::*;
+///# use filasse::cluster::*;
+///# use filasse::report::*;
+/// let mut batch = BatchScheduler::new(Cluster::homogeneous(1, 4, 8));
+/// batch.submit(BatchJob::new(1, 4, 8, 10)).unwrap();
+/// batch.run();
+/// print!("{}", gantt(batch.finished(), 60));
+///```
+pub fn gantt(records: &[Record], width: usize) -> String {
+    let makespan = records.iter().map(|r| r.end).max().unwrap_or(0).max(1);
+    let width = width.max(1);
+    let column = |time: u64| (time as u128 * width as u128 / makespan as u128) as usize;
+    let mut records: Vec<&Record> = records.iter().collect();
+    records.sort_by_key(|r| r.id);
+    let label = records
+        .iter()
+        .map(|r| r.id.to_string().len())
+        .max()
+        .unwrap_or(1);
+    let mut chart = String::new();
+    for r in records {
+        let (start, end) = (column(r.start), column(r.end));
+        let end = end.max(start + 1).min(width);
+        let line: String = (0..width)
+            .map(|c| if start <= c && c < end { '#' } else { '.' })
+            .collect();
+        chart.push_str(&format!(
+            "{:>label$} |{}| {}-{}\n",
+            r.id, line, r.start, r.end
+        ));
+    }
+    chart.push_str(&format!("{:>label$} 0{:>width$}\n", "", makespan));
+    chart
+}
+
+/// Event trace
+///
+/// The starts and ends of the records in CSV, by time then id, the ends coming before the starts at the same time.
+pub fn trace(records: &[Record]) -> String {
+    let mut events: Vec<(u64, bool, u64, &Record)> = Vec::new();
+    for r in records {
+        events.push((r.start, true, r.id, r));
+        events.push((r.end, false, r.id, r));
+    }
+    events.sort_by_key(|&(time, start, id, _)| (time, start, id));
+    let mut text = String::from("time,event,job,processors,exit\n");
+    for (time, start, id, r) in events {
+        let (event, exit) = if start {
+            ("start", String::new())
+        } else {
+            ("end", format!("{:?}", r.exit))
+        };
+        text.push_str(&format!(
+            "{},{},{},{},{}\n",
+            time,
+            event,
+            id,
+            r.resources.total_cores(),
+            exit
+        ));
+    }
+    text
+}
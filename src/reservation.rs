@@ -0,0 +1,67 @@
+use crate::backfill::*;
+use crate::cluster::*;
+
+/// Access to a reservation
+///
+/// * `Users` : only the jobs of these users may run in the reservation.
+/// * `Accounts` : only the jobs charged to these accounts may run in the reservation.
+/// * `Maintenance` : no job may run in the reservation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Access {
+    Users(Vec<String>),
+    Accounts(Vec<String>),
+    Maintenance,
+}
+
+/// Advance reservation
+///
+/// Resources held from `start` to `end` for the jobs allowed by `access`, each allocation holding the same cores and memory on its nodes. A whole node is reserved with all its cores and memory, the nodes of different sizes being in different allocations.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reservation {
+    pub id: u64,
+    pub name: String,
+    pub start: u64,
+    pub end: u64,
+    pub allocations: Vec<Allocation>,
+    pub access: Access,
+}
+
+impl Reservation {
+    /// Allowed job
+    ///
+    /// Returns true if a job of `user` charged to `account` may run in the reservation.
+    pub fn allows(&self, user: Option<&str>, account: Option<&str>) -> bool {
+        let contains = |names: &Vec<String>, name: Option<&str>| {
+            name.is_some_and(|name| names.iter().any(|n| n == name))
+        };
+        match &self.access {
+            Access::Users(users) => contains(users, user),
+            Access::Accounts(accounts) => contains(accounts, account),
+            Access::Maintenance => false,
+        }
+    }
+
+    /// Overlap
+    ///
+    /// Returns true if the reservation is active at some time between `from` and `to`, `to` excluded.
+    pub fn overlaps(&self, from: u64, to: u64) -> bool {
+        self.start < to && from < self.end
+    }
+
+    /// Active
+    pub fn is_active(&self, time: u64) -> bool {
+        self.start <= time && time < self.end
+    }
+
+    /// Slots of the reservation in an availability profile
+    pub fn slots(&self) -> Vec<Slot> {
+        self.allocations
+            .iter()
+            .map(|allocation| Slot {
+                start: self.start,
+                end: self.end,
+                allocation: allocation.clone(),
+            })
+            .collect()
+    }
+}
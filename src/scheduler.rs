@@ -1,5 +1,138 @@
+use crate::any_job::*;
+use crate::array::*;
+use crate::dependency::*;
+use crate::device::*;
+use crate::fairshare::*;
 use crate::job::*;
-use std::collections::VecDeque;
+use crate::metrics::*;
+use crate::observer::*;
+use crate::registry::*;
+use crate::signal::*;
+use crate::swap::*;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::fmt;
+
+/// Default grace period
+///
+/// The time between the TERM and the KILL sent to a job exceeding its time limit, like the KillWait of Slurm.
+pub const DEFAULT_GRACE: u64 = 30;
+
+/// Default maximum size of a job array
+///
+/// The number of tasks an array may have, like the MaxArraySize of Slurm.
+pub const DEFAULT_MAX_ARRAY_SIZE: usize = 1001;
+
+/// Time limit of a job
+///
+/// `limit` is counted from `start`, the first dispatch of the job. `term` is the date the TERM signal was sent.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TimeLimit {
+    pub limit: u64,
+    pub start: Option<u64>,
+    pub term: Option<u64>,
+}
+
+/// Pid of the init job
+///
+/// The init job is the parent of every job added to the scheduler. Orphans are reparented to it, and it reaps its zombie children automatically.
+pub const INIT_PID: u64 = 0;
+
+/// Errors of the scheduler
+///
+/// * `UnknownPid` : no job with this pid is known by the scheduler.
+/// * `NotRunning` : the job must be running to perform this operation.
+/// * `NoChild` : the job has no child to wait for.
+/// * `NotChild` : the job is not a child of the given parent.
+/// * `IllegalState` : the operation is not allowed from the current state of the job.
+/// * `CyclicDependency` : the dependencies of the submitted jobs form a cycle through this job.
+/// * `UnknownArray` : no job array with this id is known by the scheduler.
+/// * `EmptyArray` : a job array must have at least one task.
+/// * `ZeroThrottle` : a job array must allow at least one active task.
+/// * `ArrayTooLarge` : the job array has more tasks than the maximum array size.
+/// * `InvalidSignal` : the user defined signal is out of range.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SchedulerError {
+    UnknownPid(u64),
+    NotRunning(u64),
+    NoChild(u64),
+    NotChild { parent: u64, child: u64 },
+    IllegalState { pid: u64, state: JobState },
+    CyclicDependency(u64),
+    UnknownArray(u64),
+    EmptyArray,
+    ZeroThrottle,
+    ArrayTooLarge { size: usize, max: usize },
+    InvalidSignal(Signal),
+}
+
+impl fmt::Display for SchedulerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SchedulerError::UnknownPid(pid) => write!(f, "unknown pid {}", pid),
+            SchedulerError::NotRunning(pid) => write!(f, "job {} is not running", pid),
+            SchedulerError::NoChild(pid) => write!(f, "job {} has no child", pid),
+            SchedulerError::NotChild { parent, child } => {
+                write!(f, "job {} is not a child of job {}", child, parent)
+            }
+            SchedulerError::IllegalState { pid, state } => {
+                write!(f, "illegal operation on job {} in state {}", pid, state)
+            }
+            SchedulerError::CyclicDependency(pid) => {
+                write!(f, "cyclic dependency through job {}", pid)
+            }
+            SchedulerError::UnknownArray(id) => write!(f, "unknown job array {}", id),
+            SchedulerError::EmptyArray => write!(f, "empty job array"),
+            SchedulerError::ZeroThrottle => write!(f, "job array throttled to zero tasks"),
+            SchedulerError::ArrayTooLarge { size, max } => {
+                write!(f, "job array of {} tasks, at most {} allowed", size, max)
+            }
+            SchedulerError::InvalidSignal(signal) => write!(f, "invalid signal {:?}", signal),
+        }
+    }
+}
+
+impl std::error::Error for SchedulerError {}
+
+/// Version of the snapshot format
+#[cfg(feature = "serde")]
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+/// Errors of a snapshot
+///
+/// * `Parse` : the snapshot is not valid JSON or does not describe a scheduler.
+/// * `Version` : the snapshot was written in another version of the format.
+#[cfg(feature = "serde")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SnapshotError {
+    Parse(String),
+    Version(u32),
+}
+
+#[cfg(feature = "serde")]
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SnapshotError::Parse(message) => write!(f, "invalid snapshot: {}", message),
+            SnapshotError::Version(version) => write!(
+                f,
+                "snapshot version {} instead of {}",
+                version, SNAPSHOT_VERSION
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl std::error::Error for SnapshotError {}
+
+// Versioned snapshot of a scheduler.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct Snapshot<S> {
+    version: u32,
+    scheduler: S,
+}
 /// Struct of the scheduler
 ///
 /// ```rust, ignore
@@ -12,10 +145,32 @@ use std::collections::VecDeque;
 ///     q: u64,
 ///     pid_count: u64,
 ///     available: bool,
+///     devices: Vec<Device>,
+///     running: Option<Job<Running>>,
+///     terminated: VecDeque<Job<Terminated>>,
+///     stopped: VecDeque<Job<Stopped>>,
+///     signals: HashMap<u64, SignalState>,
+///     suspended_ready: VecDeque<Job<SuspendedReady>>,
+///     suspended_blocked: VecDeque<Job<SuspendedBlocked>>,
+///     swap_policy: Option<SwapPolicy>,
+///     held: VecDeque<Job<Ready>>,
+///     holds: BTreeSet<u64>,
+///     registry: Registry,
+///     waiting: VecDeque<Job<New>>,
+///     dependencies: HashMap<u64, Vec<Dependency>>,
+///     clock: u64,
+///     limits: HashMap<u64, TimeLimit>,
+///     grace: u64,
+///     owners: HashMap<u64, String>,
+///     fairshare: Option<FairShare>,
+///     arrays: BTreeMap<u64, JobArray>,
+///     max_array_size: usize,
+///     observers: Observers,
 /// }
 ///```
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Scheduler {
     queue: VecDeque<Job<Ready>>,
     blocked: VecDeque<Job<Blocked>>,
@@ -23,6 +178,30 @@ pub struct Scheduler {
     q: u64,
     pid_count: u64,
     available: bool,
+    devices: Vec<Device>,
+    running: Option<Job<Running>>,
+    terminated: VecDeque<Job<Terminated>>,
+    stopped: VecDeque<Job<Stopped>>,
+    signals: HashMap<u64, SignalState>,
+    suspended_ready: VecDeque<Job<SuspendedReady>>,
+    suspended_blocked: VecDeque<Job<SuspendedBlocked>>,
+    swap_policy: Option<SwapPolicy>,
+    held: VecDeque<Job<Ready>>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    holds: BTreeSet<u64>,
+    registry: Registry,
+    waiting: VecDeque<Job<New>>,
+    dependencies: HashMap<u64, Vec<Dependency>>,
+    clock: u64,
+    limits: HashMap<u64, TimeLimit>,
+    grace: u64,
+    owners: HashMap<u64, String>,
+    fairshare: Option<FairShare>,
+    arrays: BTreeMap<u64, JobArray>,
+    #[cfg_attr(feature = "serde", serde(default = "default_max_array_size"))]
+    max_array_size: usize,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    observers: Observers,
 }
 
 impl Default for Scheduler {
@@ -34,6 +213,27 @@ impl Default for Scheduler {
             q: 100,
             pid_count: 1,
             available: true,
+            devices: Vec::<Device>::new(),
+            running: None,
+            terminated: VecDeque::<Job<Terminated>>::new(),
+            stopped: VecDeque::<Job<Stopped>>::new(),
+            signals: HashMap::<u64, SignalState>::new(),
+            suspended_ready: VecDeque::<Job<SuspendedReady>>::new(),
+            suspended_blocked: VecDeque::<Job<SuspendedBlocked>>::new(),
+            swap_policy: None,
+            held: VecDeque::<Job<Ready>>::new(),
+            holds: BTreeSet::<u64>::new(),
+            registry: Registry::new(),
+            waiting: VecDeque::<Job<New>>::new(),
+            dependencies: HashMap::<u64, Vec<Dependency>>::new(),
+            clock: 0,
+            limits: HashMap::<u64, TimeLimit>::new(),
+            grace: DEFAULT_GRACE,
+            owners: HashMap::<u64, String>::new(),
+            fairshare: None,
+            arrays: BTreeMap::<u64, JobArray>::new(),
+            max_array_size: DEFAULT_MAX_ARRAY_SIZE,
+            observers: Observers::default(),
         }
     }
 }
@@ -62,6 +262,27 @@ impl Scheduler {
             q,
             pid_count: 1,
             available: true,
+            devices: Vec::<Device>::new(),
+            running: None,
+            terminated: VecDeque::<Job<Terminated>>::new(),
+            stopped: VecDeque::<Job<Stopped>>::new(),
+            signals: HashMap::<u64, SignalState>::new(),
+            suspended_ready: VecDeque::<Job<SuspendedReady>>::new(),
+            suspended_blocked: VecDeque::<Job<SuspendedBlocked>>::new(),
+            swap_policy: None,
+            held: VecDeque::<Job<Ready>>::new(),
+            holds: BTreeSet::<u64>::new(),
+            registry: Registry::new(),
+            waiting: VecDeque::<Job<New>>::new(),
+            dependencies: HashMap::<u64, Vec<Dependency>>::new(),
+            clock: 0,
+            limits: HashMap::<u64, TimeLimit>::new(),
+            grace: DEFAULT_GRACE,
+            owners: HashMap::<u64, String>::new(),
+            fairshare: None,
+            arrays: BTreeMap::<u64, JobArray>::new(),
+            max_array_size: DEFAULT_MAX_ARRAY_SIZE,
+            observers: Observers::default(),
         }
     }
 
@@ -135,6 +356,20 @@ impl Scheduler {
         &self.queue
     }
 
+    /// Getter Blocked
+    ///
+    /// The method allows you to get the blocked vector.
+    ///
+    /// # Example :
+    /// ```rust, ignore
+    ///# use filasse::scheduler::*;
+    /// let sched = Scheduler::default();
+    /// sched.blocked();
+    ///```
+    pub fn blocked(&self) -> &VecDeque<Job<Blocked>> {
+        &self.blocked
+    }
+
     /// Getter Zombie
     ///
     /// The method allows you to get the zombie vector.
@@ -149,9 +384,146 @@ impl Scheduler {
         &self.zombie
     }
 
+    /// Getter Running
+    ///
+    /// The method allows you to get the job dispatched on the processor, if any.
+    ///
+    /// # Example :
+    /// ```rust, ignore
+    ///# use filasse::scheduler::*;
+    /// let sched = Scheduler::default();
+    /// sched.running();
+    ///```
+    pub fn running(&self) -> Option<&Job<Running>> {
+        self.running.as_ref()
+    }
+
+    /// Getter Terminated
+    ///
+    /// The method allows you to get the jobs reaped by their parent.
+    ///
+    /// # Example :
+    /// ```rust, ignore
+    ///# use filasse::scheduler::*;
+    /// let sched = Scheduler::default();
+    /// sched.terminated();
+    ///```
+    pub fn terminated(&self) -> &VecDeque<Job<Terminated>> {
+        &self.terminated
+    }
+
+    /// Getter Stopped
+    ///
+    /// The method allows you to get the jobs stopped by a signal.
+    ///
+    /// # Example :
+    /// ```rust, ignore
+    ///# use filasse::scheduler::*;
+    /// let sched = Scheduler::default();
+    /// sched.stopped();
+    ///```
+    pub fn stopped(&self) -> &VecDeque<Job<Stopped>> {
+        &self.stopped
+    }
+
+    /// Getter Suspended Ready
+    ///
+    /// The method allows you to get the ready jobs swapped out.
+    pub fn suspended_ready(&self) -> &VecDeque<Job<SuspendedReady>> {
+        &self.suspended_ready
+    }
+
+    /// Getter Suspended Blocked
+    ///
+    /// The method allows you to get the blocked jobs swapped out.
+    pub fn suspended_blocked(&self) -> &VecDeque<Job<SuspendedBlocked>> {
+        &self.suspended_blocked
+    }
+
+    /// Getter Held
+    ///
+    /// The method allows you to get the ready jobs kept from being dispatched.
+    pub fn held(&self) -> &VecDeque<Job<Ready>> {
+        &self.held
+    }
+
+    /// Getter Waiting
+    ///
+    /// The method allows you to get the jobs kept out of the queue until their dependencies are satisfied.
+    pub fn waiting(&self) -> &VecDeque<Job<New>> {
+        &self.waiting
+    }
+
+    /// Getter dependencies
+    ///
+    /// The method allows you to get the dependencies declared by a job at its submission.
+    pub fn dependencies(&self, pid: u64) -> &[Dependency] {
+        self.dependencies.get(&pid).map_or(&[], |d| d.as_slice())
+    }
+
+    /// Getter Registry
+    ///
+    /// The method allows you to get the registry of every job known by the scheduler, indexed by pid.
+    ///
+    /// # Example :
+    /// ```rust, ignore
+    ///# use filasse::scheduler::*;
+    ///# use filasse::job::*;
+    /// let mut sched = Scheduler::default();
+    /// sched.add_to_scheduler(&mut Job::default());
+    /// assert!(sched.registry().count(JobState::Ready) == 1);
+    ///```
+    pub fn registry(&self) -> &Registry {
+        &self.registry
+    }
+
+    /// Getter state
+    ///
+    /// The method allows you to get the current state of a job.
+    ///
+    /// # Example :
+    /// ```rust, ignore
+    ///# use filasse::scheduler::*;
+    ///# use filasse::job::*;
+    /// let mut sched = Scheduler::default();
+    /// sched.add_to_scheduler(&mut Job::default());
+    /// assert!(sched.state(1) == Some(JobState::Ready));
+    ///```
+    pub fn state(&self, pid: u64) -> Option<JobState> {
+        self.registry.state(pid)
+    }
+
+    /// Getter job
+    ///
+    /// The method allows you to get a copy of a job whatever its state.
+    ///
+    /// # Example :
+    /// ```rust, ignore
+    ///# use filasse::scheduler::*;
+    ///# use filasse::job::*;
+    /// let mut sched = Scheduler::default();
+    /// sched.add_to_scheduler(&mut Job::default());
+    /// assert!(sched.job(1).unwrap().state() == JobState::Ready);
+    ///```
+    pub fn job(&self, pid: u64) -> Option<AnyJob> {
+        match self.registry.state(pid)? {
+            JobState::Running => self.running.map(AnyJob::from),
+            JobState::Ready => find_job(&self.queue, pid)
+                .or_else(|| find_job(&self.held, pid))
+                .map(AnyJob::from),
+            JobState::Blocked => find_job(&self.blocked, pid).map(AnyJob::from),
+            JobState::Stopped => find_job(&self.stopped, pid).map(AnyJob::from),
+            JobState::SuspendedReady => find_job(&self.suspended_ready, pid).map(AnyJob::from),
+            JobState::SuspendedBlocked => find_job(&self.suspended_blocked, pid).map(AnyJob::from),
+            JobState::Zombie => find_job(&self.zombie, pid).map(AnyJob::from),
+            JobState::Terminated => find_job(&self.terminated, pid).map(AnyJob::from),
+            JobState::New => find_job(&self.waiting, pid).map(AnyJob::from),
+        }
+    }
+
     /// Add to the scheduler
     ///
-    /// The method takes in arguments a job. This job will be added in the queue.
+    /// The method takes in arguments a job. This job will be added in the queue as a child of the init job.
     ///
     /// # Example :
     /// ```rust, ignore
@@ -164,77 +536,1553 @@ impl Scheduler {
     pub fn add_to_scheduler(&mut self, job: &mut Job<New>) {
         let mut joba: Job<Ready> = Job::from(*job);
         joba.pid = self.pid_count;
+        joba.parent = INIT_PID;
+        self.register(joba.pid, joba.parent, JobState::Ready, joba.state.priority);
         self.queue.push_back(joba);
         self.pid_count += 1;
+        self.notify(Event::Submit, AnyJob::from(joba), JobState::New);
     }
 
-    pub fn lock(&mut self) {
-        let job: Job<Running> = Job::from(self.queue.pop_front().unwrap());
-        self.blocked.push_back(Job::from(job));
+    /// Dispatch
+    ///
+    /// The job at the head of the queue is dispatched on the processor, unless a job is already running. With fair-share, the first job of the owner with the highest factor is dispatched instead. Returns the pid of the running job.
+    ///
+    /// # Example :
+    /// ```rust, ignore
+    ///# use filasse::scheduler::*;
+    ///# use filasse::job::*;
+    /// let mut sched = Scheduler::default();
+    /// sched.add_to_scheduler(&mut Job::default());
+    /// assert!(sched.dispatch() == Some(1));
+    ///```
+    pub fn dispatch(&mut self) -> Option<u64> {
+        if self.running.is_none() {
+            let index = self.next_ready()?;
+            let job: Job<Running> = Job::from(self.queue.remove(index).unwrap());
+            self.registry.set_state(job.pid, JobState::Running);
+            if let Some(limit) = self.limits.get_mut(&job.pid) {
+                limit.start.get_or_insert(self.clock);
+            }
+            self.running = Some(job);
+            self.notify(Event::Dispatch, AnyJob::from(job), JobState::Ready);
+            self.resolve_dependencies();
+        }
+        self.running.map(|job| job.pid)
     }
 
-    pub fn unlock(&mut self) {
-        let job: Job<Ready> = Job::from(self.blocked.pop_front().unwrap());
-        self.queue.push_back(job);
+    // Index in the queue of the next job to dispatch.
+    fn next_ready(&self) -> Option<usize> {
+        if self.queue.is_empty() {
+            return None;
+        }
+        let tree = match self.fairshare.as_ref() {
+            Some(tree) => tree,
+            None => return Some(0),
+        };
+        let factor = |pid: u64| {
+            self.owners
+                .get(&pid)
+                .and_then(|user| tree.factor(user))
+                .unwrap_or(1.0)
+        };
+        let mut best = 0;
+        for (index, job) in self.queue.iter().enumerate().skip(1) {
+            if factor(job.pid) > factor(self.queue[best].pid) {
+                best = index;
+            }
+        }
+        Some(best)
     }
 
-    /// Process
+    /// Fork
     ///
-    /// TBD
+    /// The running job `parent` creates a child from the given job. The child gets a new pid and is added at the end of the queue. Returns the pid of the child.
     ///
     /// # Example :
     /// ```rust, ignore
     ///# use filasse::scheduler::*;
     ///# use filasse::job::*;
-    /// let mut sched = Scheduler::new(1);
-    ///    let mut job = Job::new(1, 2, 2, 2);
-    /// sched.add_to_scheduler(&mut job);
-    /// sched.process();
+    /// let mut sched = Scheduler::default();
+    /// sched.add_to_scheduler(&mut Job::default());
+    /// let parent = sched.dispatch().unwrap();
+    /// let child = sched.fork(parent, &mut Job::default()).unwrap();
     ///```
-    pub fn process(&mut self) {
-        let mut job: Job<Running> = Job::from(self.queue.pop_front().unwrap());
-        if job.state.duration > 0 {
-            if (job.state.duration - self.q) > 0 {
-                job.state.duration -= self.q;
-                let job = Job::from(job);
-                self.queue.push_back(job);
-            } else {
-                job.state.duration = 0;
-                let job = Job::from(job);
-                self.zombie.push_back(job);
-            }
+    pub fn fork(&mut self, parent: u64, job: &mut Job<New>) -> Result<u64, SchedulerError> {
+        match self.running {
+            Some(running) if running.pid == parent => {}
+            _ if self.contains(parent) => return Err(SchedulerError::NotRunning(parent)),
+            _ => return Err(SchedulerError::UnknownPid(parent)),
         }
+        let mut child: Job<Ready> = Job::from(*job);
+        child.pid = self.pid_count;
+        child.parent = parent;
+        self.register(
+            child.pid,
+            child.parent,
+            JobState::Ready,
+            child.state.priority,
+        );
+        if let Some(owner) = self.owners.get(&parent).cloned() {
+            self.owners.insert(child.pid, owner);
+        }
+        self.queue.push_back(child);
+        self.pid_count += 1;
+        self.notify(Event::Submit, AnyJob::from(child), JobState::New);
+        Ok(child.pid)
     }
 
-    /// Algorithm Round robin
+    /// Add with dependencies
     ///
-    /// TBD
+    /// The job is added as a child of the init job, but it is kept out of the queue until its dependencies are satisfied. It is cancelled if one of them can never be satisfied. Returns the pid of the job.
     ///
     /// # Example :
     /// ```rust, ignore
     ///# use filasse::scheduler::*;
+    ///# use filasse::dependency::*;
     ///# use filasse::job::*;
-    /// let mut sched = Scheduler::new(1);
-    /// let mut job = Job::new(1, 2, 2, 2);
-    /// sched.add_to_scheduler(&mut job);
-    /// sched.process();
-    /// sched.round_robin();
+    /// let mut sched = Scheduler::default();
+    /// sched.add_to_scheduler(&mut Job::default());
+    /// let pid = sched
+    ///     .add_with_dependencies(&mut Job::default(), vec![Dependency::AfterOk(1)])
+    ///     .unwrap();
     ///```
-    pub fn round_robin(&mut self) {
+    pub fn add_with_dependencies(
+        &mut self,
+        job: &mut Job<New>,
+        dependencies: Vec<Dependency>,
+    ) -> Result<u64, SchedulerError> {
+        let pids = self.add_workflow(vec![(*job, dependencies)])?;
+        Ok(pids[0])
+    }
+
+    /// Add a workflow
+    ///
+    /// The jobs get consecutive pids starting at `pid_count()`, in the given order, so their dependencies may refer to each other. The whole workflow is rejected if a dependency refers to an unknown job or if the dependencies form a cycle. Returns the pids of the jobs.
+    ///
+    /// # Example :
+    /// ```rust, ignore
+    ///# use filasse::scheduler::*;
+    ///# use filasse::dependency::*;
+    ///# use filasse::job::*;
+    /// let mut sched = Scheduler::default();
+    /// let first = sched.pid_count();
+    /// let pids = sched
+    ///     .add_workflow(vec![
+    ///         (Job::default(), vec![]),
+    ///         (Job::default(), vec![Dependency::AfterOk(first)]),
+    ///     ])
+    ///     .unwrap();
+    ///```
+    pub fn add_workflow(
+        &mut self,
+        jobs: Vec<(Job<New>, Vec<Dependency>)>,
+    ) -> Result<Vec<u64>, SchedulerError> {
+        let first = self.pid_count;
+        let last = first + jobs.len() as u64;
+        for (_, dependencies) in jobs.iter() {
+            for pid in dependencies.iter().filter_map(|d| d.pid()) {
+                if !(first..last).contains(&pid) && self.registry.get(pid).is_none() {
+                    return Err(SchedulerError::UnknownPid(pid));
+                }
+            }
+        }
+        let graph: Vec<Vec<u64>> = jobs
+            .iter()
+            .map(|(_, dependencies)| {
+                dependencies
+                    .iter()
+                    .filter_map(|d| d.pid())
+                    .filter(|pid| (first..last).contains(pid))
+                    .collect()
+            })
+            .collect();
+        if let Some(pid) = find_cycle(first, &graph) {
+            return Err(SchedulerError::CyclicDependency(pid));
+        }
+        let mut pids = Vec::new();
+        for (mut job, dependencies) in jobs {
+            job.pid = self.pid_count;
+            job.parent = INIT_PID;
+            self.pid_count += 1;
+            self.register(job.pid, job.parent, JobState::New, job.state.priority);
+            self.dependencies.insert(job.pid, dependencies);
+            self.waiting.push_back(job);
+            pids.push(job.pid);
+            self.notify(Event::Submit, AnyJob::from(job), JobState::New);
+        }
+        self.resolve_dependencies();
+        Ok(pids)
+    }
+
+    // Admit the waiting jobs whose dependencies are satisfied, cancel those whose dependencies never will be. The tasks of an array with as many active tasks as its throttle keep waiting.
+    fn resolve_dependencies(&mut self) {
         loop {
-            if !self.queue.is_empty() {
-                match self.queue.get(0) {
-                    Some(_) => {
-                        println!("SUR LE PROCESSUS : {:?}", self.queue[0]);
-                        self.process(); // pendant Q temps
+            let throttled = self.throttled_arrays();
+            let mut decision = None;
+            for job in self.waiting.iter() {
+                if self
+                    .array_of(job.pid)
+                    .is_some_and(|array| throttled.contains(&array.id))
+                {
+                    continue;
+                }
+                match self.dependency_status(job.pid) {
+                    Status::Waiting => continue,
+                    status => {
+                        decision = Some((job.pid, status));
+                        break;
                     }
-                    None => break,
                 }
-                println!("Queue : {:?}\nZombie : {:?}\n", self.queue(), self.zombie);
-            } else {
-                println!("No Processus");
-                break;
+            }
+            match decision {
+                Some((pid, Status::Satisfied)) => {
+                    let job: Job<Ready> = Job::from(take(&mut self.waiting, pid).unwrap());
+                    self.registry.set_state(pid, JobState::Ready);
+                    self.make_ready(job);
+                    self.notify(Event::Other, AnyJob::from(job), JobState::New);
+                }
+                Some((pid, _)) => self.terminate(pid, ExitReason::DependencyNeverSatisfied),
+                None => break,
+            }
+        }
+    }
+
+    // Ids of the arrays whose active tasks reached their throttle.
+    fn throttled_arrays(&self) -> HashSet<u64> {
+        self.arrays
+            .values()
+            .filter(|array| {
+                array.throttle.is_some_and(|max| {
+                    let active = array
+                        .pids()
+                        .filter(|&pid| {
+                            self.registry.state(pid).is_some_and(|state| {
+                                !matches!(
+                                    state,
+                                    JobState::New | JobState::Zombie | JobState::Terminated
+                                )
+                            })
+                        })
+                        .count();
+                    active >= max
+                })
+            })
+            .map(|array| array.id)
+            .collect()
+    }
+
+    /// Add a job array
+    ///
+    /// One task per distinct index of `spec`, each a copy of `template`, at most `max_array_size()` tasks. The tasks get consecutive pids and the id of the array is the pid of the first one. At most `spec.throttle` tasks are ready, running or blocked at once, the others wait in the new state.
+    ///
+    /// # Example :
+    /// ```rust, ignore
+    ///# use filasse::scheduler::*;
+    ///# use filasse::array::*;
+    ///# use filasse::job::*;
+    /// let mut sched = Scheduler::default();
+    /// let spec = ArraySpec::parse("0-9%2").unwrap().command("./sweep --seed %a");
+    /// let id = sched.add_array(&Job::default(), spec).unwrap();
+    /// assert!(sched.array_counts(id).unwrap()[&JobState::Ready] == 2);
+    ///```
+    pub fn add_array(
+        &mut self,
+        template: &Job<New>,
+        mut spec: ArraySpec,
+    ) -> Result<u64, SchedulerError> {
+        spec.indices.sort_unstable();
+        spec.indices.dedup();
+        if spec.indices.is_empty() {
+            return Err(SchedulerError::EmptyArray);
+        }
+        if spec.indices.len() > self.max_array_size {
+            return Err(SchedulerError::ArrayTooLarge {
+                size: spec.indices.len(),
+                max: self.max_array_size,
+            });
+        }
+        if spec.throttle == Some(0) {
+            return Err(SchedulerError::ZeroThrottle);
+        }
+        let id = self.pid_count;
+        let jobs = vec![(*template, Vec::new()); spec.indices.len()];
+        self.arrays.insert(
+            id,
+            JobArray {
+                id,
+                indices: spec.indices,
+                throttle: spec.throttle,
+                command: spec.command,
+            },
+        );
+        self.add_workflow(jobs)?;
+        Ok(id)
+    }
+
+    /// Getter maximum array size
+    pub fn max_array_size(&self) -> usize {
+        self.max_array_size
+    }
+
+    /// Setter maximum array size
+    ///
+    /// The arrays already added keep their tasks.
+    pub fn set_max_array_size(&mut self, max: usize) {
+        self.max_array_size = max;
+    }
+
+    /// Getter job array
+    pub fn array(&self, id: u64) -> Option<&JobArray> {
+        self.arrays.get(&id)
+    }
+
+    /// Array of a task
+    ///
+    /// The array the job `pid` is a task of, if any.
+    pub fn array_of(&self, pid: u64) -> Option<&JobArray> {
+        self.arrays
+            .range(..=pid)
+            .next_back()
+            .map(|(_, array)| array)
+            .filter(|array| array.pids().contains(&pid))
+    }
+
+    /// States of the tasks of an array
+    ///
+    /// The index and the state of every task, in the order of the indices.
+    pub fn array_states(&self, id: u64) -> Option<Vec<(u32, JobState)>> {
+        let array = self.arrays.get(&id)?;
+        Some(
+            array
+                .indices
+                .iter()
+                .zip(array.pids())
+                .filter_map(|(&index, pid)| Some((index, self.registry.state(pid)?)))
+                .collect(),
+        )
+    }
+
+    /// Aggregated state of an array
+    ///
+    /// The number of tasks in each state.
+    pub fn array_counts(&self, id: u64) -> Option<HashMap<JobState, usize>> {
+        let mut counts = HashMap::new();
+        for (_, state) in self.array_states(id)? {
+            *counts.entry(state).or_insert(0) += 1;
+        }
+        Some(counts)
+    }
+
+    /// Cancel an array
+    ///
+    /// Every task which has not exited yet is cancelled. Returns the number of cancelled tasks.
+    pub fn cancel_array(&mut self, id: u64) -> Result<usize, SchedulerError> {
+        let pids = self
+            .arrays
+            .get(&id)
+            .ok_or(SchedulerError::UnknownArray(id))?
+            .pids();
+        let mut cancelled = 0;
+        for pid in pids {
+            if self.cancel(pid).is_ok() {
+                cancelled += 1;
+            }
+        }
+        Ok(cancelled)
+    }
+
+    /// Setter array throttle
+    ///
+    /// Lowering the throttle does not stop the active tasks, raising it admits waiting tasks at once. `None` removes the limit, a throttle of 0 is rejected.
+    pub fn set_array_throttle(
+        &mut self,
+        id: u64,
+        throttle: Option<usize>,
+    ) -> Result<(), SchedulerError> {
+        if throttle == Some(0) {
+            return Err(SchedulerError::ZeroThrottle);
+        }
+        let array = self
+            .arrays
+            .get_mut(&id)
+            .ok_or(SchedulerError::UnknownArray(id))?;
+        array.throttle = throttle;
+        self.resolve_dependencies();
+        Ok(())
+    }
+
+    /// Command line of a task
+    ///
+    /// The command template of the array of `pid` with the index of the task substituted.
+    pub fn command_line(&self, pid: u64) -> Option<String> {
+        let array = self.array_of(pid)?;
+        array.command_line(array.index(pid)?)
+    }
+
+    fn dependency_status(&self, pid: u64) -> Status {
+        let mut status = Status::Satisfied;
+        for dependency in self.dependencies(pid) {
+            let current = match dependency {
+                Dependency::Singleton(_) => {
+                    let busy = self.dependencies.iter().any(|(other, dependencies)| {
+                        *other != pid
+                            && dependencies.contains(dependency)
+                            && self.registry.state(*other).is_some_and(|state| {
+                                !matches!(
+                                    state,
+                                    JobState::New | JobState::Zombie | JobState::Terminated
+                                )
+                            })
+                    });
+                    if busy {
+                        Status::Waiting
+                    } else {
+                        Status::Satisfied
+                    }
+                }
+                _ => match dependency.pid().and_then(|p| self.registry.get(p)) {
+                    Some(entry) => dependency.status(entry),
+                    None => Status::Waiting,
+                },
+            };
+            match current {
+                Status::Never => return Status::Never,
+                Status::Waiting => status = Status::Waiting,
+                Status::Satisfied => {}
             }
         }
+        status
     }
+
+    /// Wait
+    ///
+    /// The job `parent` reaps one of its zombie children, which becomes terminated. Returns `Ok(None)` if its children are all still alive.
+    ///
+    /// # Example :
+    /// ```rust, ignore
+    ///# use filasse::scheduler::*;
+    ///# use filasse::job::*;
+    /// let mut sched = Scheduler::new(1);
+    ///# sched.add_to_scheduler(&mut Job::new(0, 0, 5, 0));
+    ///# let parent = sched.dispatch().unwrap();
+    ///# sched.fork(parent, &mut Job::new(0, 0, 1, 0)).unwrap();
+    /// let reaped = sched.wait(parent);
+    ///```
+    pub fn wait(&mut self, parent: u64) -> Result<Option<Job<Terminated>>, SchedulerError> {
+        if !self.contains(parent) {
+            return Err(SchedulerError::UnknownPid(parent));
+        }
+        if self.children(parent).is_empty() {
+            return Err(SchedulerError::NoChild(parent));
+        }
+        Ok(self
+            .zombie
+            .iter()
+            .position(|job| job.parent == parent)
+            .map(|index| self.reap(index)))
+    }
+
+    /// Waitpid
+    ///
+    /// The job `parent` reaps its child `child` if it is a zombie. Returns `Ok(None)` if the child is still alive.
+    ///
+    /// # Example :
+    /// ```rust, ignore
+    ///# use filasse::scheduler::*;
+    ///# use filasse::job::*;
+    /// let mut sched = Scheduler::new(1);
+    ///# sched.add_to_scheduler(&mut Job::new(0, 0, 5, 0));
+    ///# let parent = sched.dispatch().unwrap();
+    /// let child = sched.fork(parent, &mut Job::new(0, 0, 1, 0)).unwrap();
+    /// let reaped = sched.waitpid(parent, child);
+    ///```
+    pub fn waitpid(
+        &mut self,
+        parent: u64,
+        child: u64,
+    ) -> Result<Option<Job<Terminated>>, SchedulerError> {
+        if !self.contains(parent) {
+            return Err(SchedulerError::UnknownPid(parent));
+        }
+        match self.parent(child) {
+            None => Err(SchedulerError::UnknownPid(child)),
+            Some(p) if p != parent => Err(SchedulerError::NotChild { parent, child }),
+            Some(_) => Ok(self
+                .zombie
+                .iter()
+                .position(|job| job.pid == child)
+                .map(|index| self.reap(index))),
+        }
+    }
+
+    /// Init reaping
+    ///
+    /// The init job reaps all its zombie children, orphans included. Returns the number of reaped jobs.
+    pub fn reap_init(&mut self) -> usize {
+        let mut count = 0;
+        while let Some(index) = self.zombie.iter().position(|job| job.parent == INIT_PID) {
+            self.reap(index);
+            count += 1;
+        }
+        count
+    }
+
+    /// Send a signal
+    ///
+    /// The signal is delivered to the job `pid`, or kept pending if the job masks it. Signals sent to a zombie are discarded.
+    ///
+    /// # Example :
+    /// ```rust, ignore
+    ///# use filasse::scheduler::*;
+    ///# use filasse::signal::*;
+    ///# use filasse::job::*;
+    /// let mut sched = Scheduler::default();
+    /// sched.add_to_scheduler(&mut Job::default());
+    /// sched.send_signal(1, Signal::Stop).unwrap();
+    /// sched.send_signal(1, Signal::Cont).unwrap();
+    ///```
+    pub fn send_signal(&mut self, pid: u64, signal: Signal) -> Result<(), SchedulerError> {
+        self.signal(pid, signal, ExitReason::Killed)
+    }
+
+    // Send a signal, a job terminated by it exits for `reason`.
+    fn signal(
+        &mut self,
+        pid: u64,
+        signal: Signal,
+        reason: ExitReason,
+    ) -> Result<(), SchedulerError> {
+        if !self.contains(pid) || pid == INIT_PID {
+            return Err(SchedulerError::UnknownPid(pid));
+        }
+        if !signal.is_valid() {
+            return Err(SchedulerError::InvalidSignal(signal));
+        }
+        if self.zombie.iter().any(|job| job.pid == pid) {
+            return Ok(());
+        }
+        let state = self.signals.entry(pid).or_default();
+        if state.is_masked(signal) {
+            if !state.pending.contains(&signal) {
+                state.pending.push(signal);
+            }
+            return Ok(());
+        }
+        self.deliver(pid, signal, reason);
+        Ok(())
+    }
+
+    /// Setter signal mask
+    ///
+    /// The signals in the mask stay pending until they are unmasked. The pending signals which are no longer masked are delivered.
+    pub fn set_signal_mask(&mut self, pid: u64, mask: SignalSet) -> Result<(), SchedulerError> {
+        if !self.contains(pid) || pid == INIT_PID {
+            return Err(SchedulerError::UnknownPid(pid));
+        }
+        let state = self.signals.entry(pid).or_default();
+        state.mask = mask;
+        let (masked, unmasked): (Vec<Signal>, Vec<Signal>) =
+            state.pending.drain(..).partition(|s| mask.contains(*s));
+        state.pending = masked;
+        for signal in unmasked {
+            self.deliver(pid, signal, ExitReason::Killed);
+        }
+        Ok(())
+    }
+
+    /// Setter signal handler
+    ///
+    /// The disposition of KILL and STOP cannot be changed.
+    pub fn set_signal_handler(
+        &mut self,
+        pid: u64,
+        signal: Signal,
+        disposition: Disposition,
+    ) -> Result<(), SchedulerError> {
+        if !self.contains(pid) || pid == INIT_PID {
+            return Err(SchedulerError::UnknownPid(pid));
+        }
+        if !signal.is_valid() {
+            return Err(SchedulerError::InvalidSignal(signal));
+        }
+        self.signals
+            .entry(pid)
+            .or_default()
+            .handlers
+            .insert(signal, disposition);
+        Ok(())
+    }
+
+    /// Getter signal state
+    ///
+    /// The method allows you to get the mask, the handlers, the pending and the caught signals of a job.
+    pub fn signal_state(&self, pid: u64) -> Option<&SignalState> {
+        self.signals.get(&pid)
+    }
+
+    fn deliver(&mut self, pid: u64, signal: Signal, reason: ExitReason) {
+        let state = self.signals.entry(pid).or_default();
+        let action = match state.disposition(signal) {
+            Disposition::Default => signal.default_action(),
+            Disposition::Ignore => Action::Ignore,
+            Disposition::Catch => {
+                state.caught.push(signal);
+                Action::Ignore
+            }
+        };
+        match action {
+            Action::Terminate => self.terminate(pid, reason),
+            Action::Stop => self.stop(pid),
+            Action::Continue => self.cont(pid),
+            Action::Ignore => {}
+        }
+    }
+
+    fn terminate(&mut self, pid: u64, reason: ExitReason) {
+        let job: Job<Zombie> = if self.running.map(|job| job.pid) == Some(pid) {
+            Job::from(self.running.take().unwrap())
+        } else if let Some(job) = take(&mut self.waiting, pid) {
+            Job::from(job)
+        } else if let Some(job) = take(&mut self.queue, pid) {
+            Job::from(job)
+        } else if let Some(job) = take(&mut self.held, pid) {
+            Job::from(job)
+        } else if let Some(job) = take(&mut self.blocked, pid) {
+            self.cancel_io(pid);
+            Job::from(job)
+        } else if let Some(job) = take(&mut self.stopped, pid) {
+            Job::from(job)
+        } else if let Some(job) = take(&mut self.suspended_ready, pid) {
+            Job::from(job)
+        } else if let Some(job) = take(&mut self.suspended_blocked, pid) {
+            self.cancel_io(pid);
+            Job::from(job)
+        } else {
+            return;
+        };
+        self.exit(job, reason);
+    }
+
+    fn stop(&mut self, pid: u64) {
+        let job: Job<Stopped> = if self.running.map(|job| job.pid) == Some(pid) {
+            Job::from(self.running.take().unwrap())
+        } else if let Some(job) = take(&mut self.queue, pid) {
+            Job::from(job)
+        } else if let Some(job) = take(&mut self.held, pid) {
+            Job::from(job)
+        } else if let Some(job) = take(&mut self.blocked, pid) {
+            self.cancel_io(pid);
+            Job::from(job)
+        } else {
+            return;
+        };
+        let parent = job.parent;
+        let old = self.registry.state(pid).unwrap_or(JobState::Ready);
+        self.registry.set_state(pid, JobState::Stopped);
+        self.stopped.push_back(job);
+        self.notify(off_processor(old), AnyJob::from(job), old);
+        self.notify_parent(parent);
+    }
+
+    fn cont(&mut self, pid: u64) {
+        if let Some(job) = take(&mut self.stopped, pid) {
+            let job: Job<Ready> = Job::from(job);
+            self.registry.set_state(pid, JobState::Ready);
+            self.make_ready(job);
+            self.notify(Event::Other, AnyJob::from(job), JobState::Stopped);
+        }
+    }
+
+    fn cancel_io(&mut self, pid: u64) {
+        for device in self.devices.iter_mut() {
+            device.cancel(pid);
+        }
+    }
+
+    fn notify_parent(&mut self, parent: u64) {
+        if parent != INIT_PID && self.contains(parent) {
+            let _ = self.send_signal(parent, Signal::Chld);
+        }
+    }
+
+    /// Suspend
+    ///
+    /// The job is swapped out. A ready or running job becomes suspended ready, a blocked job becomes suspended blocked.
+    ///
+    /// # Example :
+    /// ```rust, ignore
+    ///# use filasse::scheduler::*;
+    ///# use filasse::job::*;
+    /// let mut sched = Scheduler::default();
+    /// sched.add_to_scheduler(&mut Job::default());
+    /// sched.suspend(1).unwrap();
+    ///```
+    pub fn suspend(&mut self, pid: u64) -> Result<(), SchedulerError> {
+        let old = self.registry.state(pid).unwrap_or(JobState::Ready);
+        let job = if self.running.map(|job| job.pid) == Some(pid) {
+            let job: Job<SuspendedReady> = Job::from(self.running.take().unwrap());
+            self.registry.set_state(pid, JobState::SuspendedReady);
+            self.suspended_ready.push_back(job);
+            AnyJob::from(job)
+        } else if let Some(job) = take(&mut self.queue, pid).or_else(|| take(&mut self.held, pid)) {
+            let job: Job<SuspendedReady> = Job::from(job);
+            self.registry.set_state(pid, JobState::SuspendedReady);
+            self.suspended_ready.push_back(job);
+            AnyJob::from(job)
+        } else if let Some(job) = take(&mut self.blocked, pid) {
+            let job: Job<SuspendedBlocked> = Job::from(job);
+            self.registry.set_state(pid, JobState::SuspendedBlocked);
+            self.suspended_blocked.push_back(job);
+            AnyJob::from(job)
+        } else {
+            return Err(self.illegal(pid));
+        };
+        self.notify(off_processor(old), job, old);
+        Ok(())
+    }
+
+    /// Resume
+    ///
+    /// The job is swapped in. A suspended ready job goes back to the end of the queue, or to the held jobs if it is on hold, a suspended blocked job becomes blocked.
+    ///
+    /// # Example :
+    /// ```rust, ignore
+    ///# use filasse::scheduler::*;
+    ///# use filasse::job::*;
+    /// let mut sched = Scheduler::default();
+    /// sched.add_to_scheduler(&mut Job::default());
+    /// sched.suspend(1).unwrap();
+    /// sched.resume(1).unwrap();
+    ///```
+    pub fn resume(&mut self, pid: u64) -> Result<(), SchedulerError> {
+        if let Some(job) = take(&mut self.suspended_ready, pid) {
+            let job: Job<Ready> = Job::from(job);
+            self.registry.set_state(pid, JobState::Ready);
+            self.make_ready(job);
+            self.notify(Event::Other, AnyJob::from(job), JobState::SuspendedReady);
+        } else if let Some(job) = take(&mut self.suspended_blocked, pid) {
+            let job: Job<Blocked> = Job::from(job);
+            self.registry.set_state(pid, JobState::Blocked);
+            self.blocked.push_back(job);
+            self.notify(Event::Other, AnyJob::from(job), JobState::SuspendedBlocked);
+        } else {
+            return Err(self.illegal(pid));
+        }
+        Ok(())
+    }
+
+    /// Cancel
+    ///
+    /// The job becomes a zombie whatever its state, without going through its signal handlers.
+    ///
+    /// # Example :
+    /// ```rust, ignore
+    ///# use filasse::scheduler::*;
+    ///# use filasse::job::*;
+    /// let mut sched = Scheduler::default();
+    /// sched.add_to_scheduler(&mut Job::default());
+    /// sched.cancel(1).unwrap();
+    ///```
+    pub fn cancel(&mut self, pid: u64) -> Result<(), SchedulerError> {
+        match self.registry.state(pid) {
+            None => Err(SchedulerError::UnknownPid(pid)),
+            Some(JobState::Zombie) | Some(JobState::Terminated) => Err(self.illegal(pid)),
+            Some(_) => {
+                self.terminate(pid, ExitReason::Cancelled);
+                Ok(())
+            }
+        }
+    }
+
+    /// Hold
+    ///
+    /// The ready job is kept from being dispatched until it is released, even if it is stopped or suspended in the meantime. A job waiting for its dependencies or for its array throttle is held once it becomes ready. Holding a held job does nothing.
+    ///
+    /// # Example :
+    /// ```rust, ignore
+    ///# use filasse::scheduler::*;
+    ///# use filasse::job::*;
+    /// let mut sched = Scheduler::default();
+    /// sched.add_to_scheduler(&mut Job::default());
+    /// sched.hold(1).unwrap();
+    ///```
+    pub fn hold(&mut self, pid: u64) -> Result<(), SchedulerError> {
+        if self.holds.contains(&pid) {
+            return Ok(());
+        }
+        if let Some(job) = find_job(&self.waiting, pid) {
+            self.holds.insert(pid);
+            self.notify(Event::Other, AnyJob::from(job), JobState::New);
+            return Ok(());
+        }
+        let job = take(&mut self.queue, pid).ok_or_else(|| self.illegal(pid))?;
+        self.holds.insert(pid);
+        self.held.push_back(job);
+        self.notify(Event::Other, AnyJob::from(job), JobState::Ready);
+        Ok(())
+    }
+
+    /// Release
+    ///
+    /// The held job goes back to the end of the queue. A held job which is stopped or suspended is no longer on hold and goes back to the queue when it is continued or resumed, a held job which is still waiting goes to the queue once it becomes ready.
+    ///
+    /// # Example :
+    /// ```rust, ignore
+    ///# use filasse::scheduler::*;
+    ///# use filasse::job::*;
+    /// let mut sched = Scheduler::default();
+    /// sched.add_to_scheduler(&mut Job::default());
+    /// sched.hold(1).unwrap();
+    /// sched.release(1).unwrap();
+    ///```
+    pub fn release(&mut self, pid: u64) -> Result<(), SchedulerError> {
+        if !self.holds.remove(&pid) {
+            return Err(self.illegal(pid));
+        }
+        if let Some(job) = take(&mut self.held, pid) {
+            self.queue.push_back(job);
+            self.notify(Event::Other, AnyJob::from(job), JobState::Ready);
+        }
+        Ok(())
+    }
+
+    // A job becoming ready goes to the queue, or to the held jobs if it is on hold.
+    fn make_ready(&mut self, job: Job<Ready>) {
+        if self.holds.contains(&job.pid) {
+            self.held.push_back(job);
+        } else {
+            self.queue.push_back(job);
+        }
+    }
+
+    /// Requeue
+    ///
+    /// The running job is preempted and goes back to the end of the queue.
+    ///
+    /// # Example :
+    /// ```rust, ignore
+    ///# use filasse::scheduler::*;
+    ///# use filasse::job::*;
+    /// let mut sched = Scheduler::default();
+    /// sched.add_to_scheduler(&mut Job::default());
+    /// sched.dispatch();
+    /// sched.requeue(1).unwrap();
+    ///```
+    pub fn requeue(&mut self, pid: u64) -> Result<(), SchedulerError> {
+        match self.running {
+            Some(job) if job.pid == pid => {
+                let job: Job<Ready> = Job::from(job);
+                self.running = None;
+                self.registry.set_state(pid, JobState::Ready);
+                self.queue.push_back(job);
+                self.notify(Event::Preempt, AnyJob::from(job), JobState::Running);
+                Ok(())
+            }
+            _ => Err(self.illegal(pid)),
+        }
+    }
+
+    /// Renice
+    ///
+    /// Change the priority of a job which is not a zombie. A higher value means a more important job.
+    ///
+    /// # Example :
+    /// ```rust, ignore
+    ///# use filasse::scheduler::*;
+    ///# use filasse::job::*;
+    /// let mut sched = Scheduler::default();
+    /// sched.add_to_scheduler(&mut Job::default());
+    /// sched.renice(1, 10).unwrap();
+    ///```
+    pub fn renice(&mut self, pid: u64, priority: u32) -> Result<(), SchedulerError> {
+        if let Some(job) = self.running.as_mut().filter(|job| job.pid == pid) {
+            job.state.priority = priority;
+        } else if let Some(job) = self.queue.iter_mut().find(|job| job.pid == pid) {
+            job.state.priority = priority;
+        } else if let Some(job) = self.waiting.iter_mut().find(|job| job.pid == pid) {
+            job.state.priority = priority;
+        } else if let Some(job) = self.held.iter_mut().find(|job| job.pid == pid) {
+            job.state.priority = priority;
+        } else if let Some(job) = self.blocked.iter_mut().find(|job| job.pid == pid) {
+            job.state.priority = priority;
+        } else if let Some(job) = self.stopped.iter_mut().find(|job| job.pid == pid) {
+            job.state.priority = priority;
+        } else if let Some(job) = self.suspended_ready.iter_mut().find(|job| job.pid == pid) {
+            job.state.priority = priority;
+        } else if let Some(job) = self.suspended_blocked.iter_mut().find(|job| job.pid == pid) {
+            job.state.priority = priority;
+        } else {
+            return Err(self.illegal(pid));
+        }
+        self.registry.set_priority(pid, priority);
+        Ok(())
+    }
+
+    /// Setter time limit
+    ///
+    /// The job receives TERM `limit` after its first dispatch, then KILL after the grace period. The limit is counted from now if the job is running, and a job killed this way exits with `ExitReason::Timeout`.
+    ///
+    /// # Example :
+    /// ```rust, ignore
+    ///# use filasse::scheduler::*;
+    ///# use filasse::job::*;
+    /// let mut sched = Scheduler::new(10);
+    /// sched.add_to_scheduler(&mut Job::new(0, 0, 100, 0));
+    /// sched.set_time_limit(1, 50).unwrap();
+    /// sched.set_grace(0);
+    ///```
+    pub fn set_time_limit(&mut self, pid: u64, limit: u64) -> Result<(), SchedulerError> {
+        if pid == INIT_PID || !self.contains(pid) {
+            return Err(SchedulerError::UnknownPid(pid));
+        }
+        let start = match self.running {
+            Some(job) if job.pid == pid => Some(self.clock),
+            _ => None,
+        };
+        let start = self
+            .limits
+            .get(&pid)
+            .and_then(|limit| limit.start)
+            .or(start);
+        self.limits.insert(
+            pid,
+            TimeLimit {
+                limit,
+                start,
+                term: None,
+            },
+        );
+        Ok(())
+    }
+
+    /// Getter time limit
+    pub fn time_limit(&self, pid: u64) -> Option<TimeLimit> {
+        self.limits.get(&pid).copied()
+    }
+
+    /// Getter grace period
+    pub fn grace(&self) -> u64 {
+        self.grace
+    }
+
+    /// Setter grace period
+    pub fn set_grace(&mut self, grace: u64) {
+        self.grace = grace;
+    }
+
+    /// Getter clock
+    ///
+    /// The processor time spent running jobs since the creation of the scheduler.
+    pub fn clock(&self) -> u64 {
+        self.clock
+    }
+
+    /// Idle
+    ///
+    /// Advance the clock to `until` while no job is ready or running, to wait for the next arrival. Returns false if the processor is not idle.
+    ///
+    /// # Example :
+    /// ```rust, ignore
+    ///# use filasse::scheduler::*;
+    /// let mut sched = Scheduler::default();
+    /// sched.idle(50);
+    /// assert!(sched.clock() == 50);
+    ///```
+    pub fn idle(&mut self, until: u64) -> bool {
+        if self.running.is_some() || !self.queue.is_empty() {
+            return false;
+        }
+        self.clock = self.clock.max(until);
+        if let Some(tree) = self.fairshare.as_mut() {
+            tree.decay(self.clock);
+        }
+        self.enforce_limits();
+        true
+    }
+
+    /// Setter owner
+    ///
+    /// The user owning a job, charged for the processor time it uses. The children forked afterwards inherit it.
+    ///
+    /// # Example :
+    /// ```rust, ignore
+    ///# use filasse::scheduler::*;
+    ///# use filasse::job::*;
+    /// let mut sched = Scheduler::default();
+    /// sched.add_to_scheduler(&mut Job::default());
+    /// sched.set_owner(1, "alice").unwrap();
+    ///```
+    pub fn set_owner(&mut self, pid: u64, user: &str) -> Result<(), SchedulerError> {
+        if pid == INIT_PID || !self.contains(pid) {
+            return Err(SchedulerError::UnknownPid(pid));
+        }
+        self.owners.insert(pid, user.to_string());
+        Ok(())
+    }
+
+    /// Getter owner
+    pub fn owner(&self, pid: u64) -> Option<&str> {
+        self.owners.get(&pid).map(|user| user.as_str())
+    }
+
+    /// Getter fair-share tree
+    pub fn fairshare(&self) -> Option<&FairShare> {
+        self.fairshare.as_ref()
+    }
+
+    /// Setter fair-share tree
+    ///
+    /// The owners are charged for the processor time of their jobs, and ready jobs are dispatched by decreasing fair-share factor of their owner. `None` restores plain round robin.
+    pub fn set_fairshare(&mut self, tree: Option<FairShare>) {
+        self.fairshare = tree;
+    }
+
+    /// Metrics
+    ///
+    /// The exit reasons of the jobs, the makespan being the clock.
+    pub fn metrics(&self) -> Metrics {
+        Metrics {
+            makespan: self.clock,
+            ..Metrics::from_registry(&self.registry)
+        }
+    }
+
+    /// Setter swap policy
+    ///
+    /// The policy used by the medium-term scheduler. `None` disables swapping.
+    pub fn set_swap_policy(&mut self, policy: Option<SwapPolicy>) {
+        self.swap_policy = policy;
+    }
+
+    /// Getter swap policy
+    pub fn swap_policy(&self) -> Option<SwapPolicy> {
+        self.swap_policy
+    }
+
+    /// Medium-term scheduler
+    ///
+    /// Swap out jobs chosen by the swap policy while more jobs than allowed are resident, then swap in the suspended ready jobs of highest priority while there is room. The running job is never swapped out. Returns the decisions in order.
+    ///
+    /// # Example :
+    /// ```rust, ignore
+    ///# use filasse::scheduler::*;
+    ///# use filasse::swap::*;
+    ///# use filasse::job::*;
+    /// let mut sched = Scheduler::default();
+    /// sched.set_swap_policy(Some(SwapPolicy::new(1, Victim::LowestPriority)));
+    /// sched.add_to_scheduler(&mut Job::default());
+    /// sched.add_to_scheduler(&mut Job::default());
+    /// assert!(sched.medium_term() == vec![Swap::Out(2)]);
+    ///```
+    pub fn medium_term(&mut self) -> Vec<Swap> {
+        let policy = match self.swap_policy {
+            Some(policy) => policy,
+            None => return Vec::new(),
+        };
+        let mut swaps = Vec::new();
+        while self.resident() > policy.max_resident {
+            let mut candidates: Vec<Candidate> = self
+                .queue
+                .iter()
+                .chain(self.held.iter())
+                .map(|job| Candidate {
+                    pid: job.pid,
+                    priority: job.state.priority,
+                    remaining: job.state.duration,
+                    blocked: false,
+                })
+                .collect();
+            candidates.extend(self.blocked.iter().map(|job| Candidate {
+                pid: job.pid,
+                priority: job.state.priority,
+                remaining: job.state.duration,
+                blocked: true,
+            }));
+            match policy.choose(&candidates) {
+                Some(pid) => {
+                    let _ = self.suspend(pid);
+                    swaps.push(Swap::Out(pid));
+                }
+                None => break,
+            }
+        }
+        while self.resident() < policy.max_resident {
+            let index = match self
+                .suspended_ready
+                .iter()
+                .enumerate()
+                .max_by_key(|(i, job)| (job.state.priority, std::cmp::Reverse(*i)))
+            {
+                Some((index, _)) => index,
+                None => break,
+            };
+            let job: Job<Ready> = Job::from(self.suspended_ready.remove(index).unwrap());
+            self.registry.set_state(job.pid, JobState::Ready);
+            self.make_ready(job);
+            self.notify(Event::Other, AnyJob::from(job), JobState::SuspendedReady);
+            swaps.push(Swap::In(job.pid));
+        }
+        swaps
+    }
+
+    fn resident(&self) -> usize {
+        self.queue.len()
+            + self.held.len()
+            + self.blocked.len()
+            + usize::from(self.running.is_some())
+    }
+
+    // Error for an operation not allowed on the job `pid`.
+    fn illegal(&self, pid: u64) -> SchedulerError {
+        match self.registry.state(pid) {
+            Some(state) => SchedulerError::IllegalState { pid, state },
+            None => SchedulerError::UnknownPid(pid),
+        }
+    }
+
+    /// Parent
+    ///
+    /// The method allows you to get the parent pid of a job which is not terminated.
+    pub fn parent(&self, pid: u64) -> Option<u64> {
+        self.registry
+            .get(pid)
+            .filter(|entry| entry.state != JobState::Terminated)
+            .map(|entry| entry.parent)
+    }
+
+    /// Children
+    ///
+    /// The method allows you to get the pids of the children of a job, zombies included, in increasing order.
+    pub fn children(&self, pid: u64) -> Vec<u64> {
+        self.registry
+            .filter(&Filter::new().parent(pid))
+            .into_iter()
+            .filter(|entry| entry.state != JobState::Terminated)
+            .map(|entry| entry.pid)
+            .collect()
+    }
+
+    /// Descendants
+    ///
+    /// The method allows you to get the pids of the children of a job, of their children and so on, in increasing order.
+    pub fn descendants(&self, pid: u64) -> Vec<u64> {
+        let mut descendants = Vec::new();
+        let mut stack = vec![pid];
+        while let Some(current) = stack.pop() {
+            for child in self.children(current) {
+                descendants.push(child);
+                stack.push(child);
+            }
+        }
+        descendants.sort_unstable();
+        descendants
+    }
+
+    fn contains(&self, pid: u64) -> bool {
+        pid == INIT_PID || self.registry.is_alive(pid)
+    }
+
+    fn register(&mut self, pid: u64, parent: u64, state: JobState, priority: u32) {
+        self.registry
+            .insert(Entry::new(pid, parent, state, priority));
+    }
+
+    fn reap(&mut self, index: usize) -> Job<Terminated> {
+        let job: Job<Terminated> = Job::from(self.zombie.remove(index).unwrap());
+        self.registry.set_state(job.pid, JobState::Terminated);
+        self.terminated.push_back(job);
+        self.notify(Event::Reap, AnyJob::from(job), JobState::Zombie);
+        job
+    }
+
+    // The job becomes a zombie, its children are reparented to init, its parent receives CHLD and its dependents are resolved.
+    fn exit(&mut self, job: Job<Zombie>, reason: ExitReason) {
+        let (pid, parent) = (job.pid, job.parent);
+        let old = self.registry.state(pid).unwrap_or(JobState::Running);
+        self.zombie.push_back(job);
+        self.registry.set_state(pid, JobState::Zombie);
+        self.registry.set_exit(pid, reason);
+        self.notify(Event::Exit, AnyJob::from(job), old);
+        self.signals.remove(&pid);
+        self.limits.remove(&pid);
+        self.holds.remove(&pid);
+        reparent(&mut self.waiting, pid);
+        reparent(&mut self.queue, pid);
+        reparent(&mut self.held, pid);
+        reparent(&mut self.blocked, pid);
+        reparent(&mut self.stopped, pid);
+        reparent(&mut self.suspended_ready, pid);
+        reparent(&mut self.suspended_blocked, pid);
+        reparent(&mut self.zombie, pid);
+        reparent(self.running.as_mut(), pid);
+        for child in self.children(pid) {
+            self.registry.set_parent(child, INIT_PID);
+        }
+        self.notify_parent(parent);
+        self.resolve_dependencies();
+    }
+
+    /// Add an observer
+    ///
+    /// The observer is told of every transition of the jobs, after the one registered before it. Returns its id.
+    ///
+    /// # Example :
+    /// ```rust, ignore
+    ///# use filasse::observer::*;
+    ///# use filasse::scheduler::*;
+    ///# use std::sync::{Arc, Mutex};
+    /// let mut sched = Scheduler::default();
+    /// let log = Arc::new(Mutex::new(MyObserver::default()));
+    /// let id = sched.add_observer(log.clone());
+    ///```
+    pub fn add_observer(&mut self, observer: SharedObserver) -> usize {
+        self.observers.add(observer)
+    }
+
+    /// Remove an observer
+    ///
+    /// Returns the observer, `None` if no observer has this id.
+    pub fn remove_observer(&mut self, id: usize) -> Option<SharedObserver> {
+        self.observers.remove(id)
+    }
+
+    /// Getter queue lengths
+    pub fn queue_lengths(&self) -> Queues {
+        Queues {
+            waiting: self.waiting.len(),
+            ready: self.queue.len(),
+            held: self.held.len(),
+            blocked: self.blocked.len(),
+            stopped: self.stopped.len(),
+            suspended: self.suspended_ready.len() + self.suspended_blocked.len(),
+            zombie: self.zombie.len(),
+        }
+    }
+
+    // Tell the `log` facade and the observers that `job` went from `old` to its current state.
+    fn notify(&self, event: Event, job: AnyJob, old: JobState) {
+        let queues = self.queue_lengths();
+        log::debug!(
+            target: "filasse::scheduler",
+            "time={} event={:?} pid={} old={} state={} ready={} blocked={} zombie={}",
+            self.clock,
+            event,
+            job.pid(),
+            old,
+            job.state(),
+            queues.ready,
+            queues.blocked,
+            queues.zombie
+        );
+        if self.observers.is_empty() {
+            return;
+        }
+        let transition = Transition {
+            job,
+            old,
+            new: job.state(),
+            time: self.clock,
+            exit: self.registry.get(job.pid()).and_then(|entry| entry.exit),
+            queues,
+        };
+        self.observers.notify(event, &transition);
+    }
+
+    pub fn lock(&mut self) {
+        self.block_running().unwrap();
+    }
+
+    pub fn unlock(&mut self) {
+        let job: Job<Ready> = Job::from(self.blocked.pop_front().unwrap());
+        self.registry.set_state(job.pid, JobState::Ready);
+        self.queue.push_back(job);
+        self.notify(Event::Unblock, AnyJob::from(job), JobState::Blocked);
+    }
+
+    /// Add a device
+    ///
+    /// The method takes in argument a device and returns its identifier in the scheduler.
+    ///
+    /// # Example :
+    /// ```rust, ignore
+    ///# use filasse::scheduler::*;
+    ///# use filasse::device::*;
+    /// let mut sched = Scheduler::default();
+    /// let disk = sched.add_device(Device::new(DiskPolicy::Sstf, 200));
+    ///```
+    pub fn add_device(&mut self, device: Device) -> usize {
+        self.devices.push(device);
+        self.devices.len() - 1
+    }
+
+    /// Getter device
+    ///
+    /// The method allows you to get a device from its identifier.
+    pub fn device(&self, id: usize) -> Option<&Device> {
+        self.devices.get(id)
+    }
+
+    /// Request an I/O
+    ///
+    /// The running job, or the next ready job if none is running, blocks on the device until its request on `cylinder` is serviced. Returns the pid of the blocked job, or `None` if no job can run or the device does not exist.
+    ///
+    /// # Example :
+    /// ```rust, ignore
+    ///# use filasse::scheduler::*;
+    ///# use filasse::device::*;
+    ///# use filasse::job::*;
+    /// let mut sched = Scheduler::default();
+    /// let disk = sched.add_device(Device::default());
+    /// sched.add_to_scheduler(&mut Job::default());
+    /// sched.request_io(disk, 98);
+    ///```
+    pub fn request_io(&mut self, device: usize, cylinder: u64) -> Option<u64> {
+        if device >= self.devices.len() {
+            return None;
+        }
+        let pid = self.block_running()?.pid;
+        self.devices[device].submit(IoRequest {
+            pid,
+            cylinder,
+            time: self.clock,
+        });
+        Some(pid)
+    }
+
+    // Block the running job, dispatching the next ready job first if none is running.
+    fn block_running(&mut self) -> Option<Job<Blocked>> {
+        self.dispatch()?;
+        let job: Job<Blocked> = Job::from(self.running.take().unwrap());
+        self.registry.set_state(job.pid, JobState::Blocked);
+        self.blocked.push_back(job);
+        self.notify(Event::Block, AnyJob::from(job), JobState::Running);
+        Some(job)
+    }
+
+    /// Complete an I/O
+    ///
+    /// The device services its next request according to its policy. The clock moves to the end of the request if it is later, the processor having been idle meanwhile, so the jobs run during the service overlap it. The matching blocked job goes back to the end of the queue, a suspended blocked job becomes suspended ready.
+    ///
+    /// # Example :
+    /// ```rust, ignore
+    ///# use filasse::scheduler::*;
+    ///# use filasse::device::*;
+    ///# use filasse::job::*;
+    /// let mut sched = Scheduler::default();
+    /// let disk = sched.add_device(Device::default());
+    /// sched.add_to_scheduler(&mut Job::default());
+    /// sched.request_io(disk, 98);
+    /// let done = sched.complete_io(disk).unwrap();
+    ///```
+    pub fn complete_io(&mut self, device: usize) -> Option<Completion> {
+        let done = self.devices.get_mut(device)?.service()?;
+        if done.end > self.clock {
+            self.clock = done.end;
+            if let Some(tree) = self.fairshare.as_mut() {
+                tree.decay(self.clock);
+            }
+            self.enforce_limits();
+        }
+        if let Some(index) = self
+            .blocked
+            .iter()
+            .position(|job| job.pid == done.request.pid)
+        {
+            let job: Job<Ready> = Job::from(self.blocked.remove(index).unwrap());
+            self.registry.set_state(job.pid, JobState::Ready);
+            self.queue.push_back(job);
+            self.notify(Event::Unblock, AnyJob::from(job), JobState::Blocked);
+        } else if let Some(job) = take(&mut self.suspended_blocked, done.request.pid) {
+            let job: Job<SuspendedReady> = Job::from(job);
+            self.registry.set_state(job.pid, JobState::SuspendedReady);
+            self.suspended_ready.push_back(job);
+            self.notify(
+                Event::Unblock,
+                AnyJob::from(job),
+                JobState::SuspendedBlocked,
+            );
+        }
+        Some(done)
+    }
+
+    /// Process
+    ///
+    /// TBD
+    ///
+    /// # Example :
+    /// ```rust, ignore
+    ///# use filasse::scheduler::*;
+    ///# use filasse::job::*;
+    /// let mut sched = Scheduler::new(1);
+    ///    let mut job = Job::new(1, 2, 2, 2);
+    /// sched.add_to_scheduler(&mut job);
+    /// sched.process();
+    ///```
+    pub fn process(&mut self) {
+        self.dispatch();
+        let mut job: Job<Running> = self.running.take().unwrap();
+        let slice = job.state.duration.min(self.q);
+        self.clock += slice;
+        if let Some(tree) = self.fairshare.as_mut() {
+            tree.decay(self.clock);
+            if let Some(user) = self.owners.get(&job.pid) {
+                tree.charge(user, slice as f64);
+            }
+        }
+        if job.state.duration > self.q {
+            job.state.duration -= self.q;
+            let job: Job<Ready> = Job::from(job);
+            self.registry.set_state(job.pid, JobState::Ready);
+            self.queue.push_back(job);
+            self.notify(Event::QuantumExpiry, AnyJob::from(job), JobState::Running);
+        } else {
+            job.state.duration = 0;
+            self.exit(Job::from(job), ExitReason::Completed);
+        }
+        self.enforce_limits();
+    }
+
+    // Send TERM to the jobs over their time limit, then KILL once the grace period is over.
+    fn enforce_limits(&mut self) {
+        let mut expired: Vec<(u64, Signal)> = Vec::new();
+        for (&pid, limit) in self.limits.iter_mut() {
+            match (limit.start, limit.term) {
+                (Some(start), None) if self.clock >= start + limit.limit => {
+                    limit.term = Some(self.clock);
+                    expired.push((pid, Signal::Term));
+                }
+                (_, Some(term)) if self.clock >= term + self.grace => {
+                    expired.push((pid, Signal::Kill));
+                }
+                _ => {}
+            }
+        }
+        expired.sort_unstable_by_key(|&(pid, _)| pid);
+        for (pid, signal) in expired {
+            let _ = self.signal(pid, signal, ExitReason::Timeout);
+        }
+    }
+
+    /// Algorithm Round robin
+    ///
+    /// Every job runs for one quantum in turn until the queue is empty. The init job reaps its zombie children after each quantum. Nothing is printed : the transitions go to the observers and to the `log` facade.
+    ///
+    /// # Example :
+    /// ```rust, ignore
+    ///# use filasse::scheduler::*;
+    ///# use filasse::job::*;
+    /// let mut sched = Scheduler::new(1);
+    /// let mut job = Job::new(1, 2, 2, 2);
+    /// sched.add_to_scheduler(&mut job);
+    /// sched.process();
+    /// sched.round_robin();
+    ///```
+    pub fn round_robin(&mut self) {
+        while self.dispatch().is_some() {
+            self.process();
+            self.reap_init();
+        }
+        log::debug!(target: "filasse::scheduler", "time={} no job to run", self.clock);
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Scheduler {
+    /// Snapshot of the scheduler
+    ///
+    /// The whole state in JSON : every queue, the running job, the counters, the clock, the registry, the limits, the dependencies and the accounts. Restoring it gives a scheduler which continues exactly like this one.
+    ///
+    /// # Example :
+    /// ```rust, ignore
+    ///# use filasse::scheduler::*;
+    /// let snapshot = sched.snapshot();
+    /// std::fs::write("checkpoint.json", &snapshot).unwrap();
+    /// let mut sched = Scheduler::restore(&snapshot).unwrap();
+    ///```
+    pub fn snapshot(&self) -> String {
+        serde_json::to_string(&Snapshot {
+            version: SNAPSHOT_VERSION,
+            scheduler: self,
+        })
+        .expect("a scheduler is serializable")
+    }
+
+    /// Restore a snapshot
+    ///
+    /// Fails if the snapshot is invalid or of another version.
+    pub fn restore(snapshot: &str) -> Result<Scheduler, SnapshotError> {
+        let parse = |e: serde_json::Error| SnapshotError::Parse(e.to_string());
+        let value: serde_json::Value = serde_json::from_str(snapshot).map_err(parse)?;
+        match value.get("version").and_then(|v| v.as_u64()) {
+            Some(version) if version == SNAPSHOT_VERSION as u64 => {}
+            Some(version) => return Err(SnapshotError::Version(version as u32)),
+            None => return Err(SnapshotError::Parse("missing version".to_string())),
+        }
+        let snapshot: Snapshot<Scheduler> = serde_json::from_value(value).map_err(parse)?;
+        Ok(snapshot.scheduler)
+    }
+}
+
+// Event of a job leaving `old` for a state off the processor.
+fn off_processor(old: JobState) -> Event {
+    match old {
+        JobState::Running => Event::Preempt,
+        _ => Event::Other,
+    }
+}
+
+// Remove the job `pid` from a queue.
+fn take<S: State>(queue: &mut VecDeque<Job<S>>, pid: u64) -> Option<Job<S>> {
+    let index = queue.iter().position(|job| job.pid == pid)?;
+    queue.remove(index)
+}
+
+// Reparent the children of `pid` to init.
+fn reparent<'a, S: State + 'a>(jobs: impl IntoIterator<Item = &'a mut Job<S>>, pid: u64) {
+    for job in jobs {
+        if job.parent == pid {
+            job.parent = INIT_PID;
+        }
+    }
+}
+
+// Copy of the job `pid` in a queue.
+fn find_job<S: State + Copy>(queue: &VecDeque<Job<S>>, pid: u64) -> Option<Job<S>> {
+    queue.iter().find(|job| job.pid == pid).copied()
+}
+
+// A pid on a cycle of the graph of dependencies, `graph[i]` being the pids job `first + i` depends on.
+fn find_cycle(first: u64, graph: &[Vec<u64>]) -> Option<u64> {
+    // 0 : unvisited, 1 : on the current path, 2 : done.
+    let mut color = vec![0u8; graph.len()];
+    for start in 0..graph.len() {
+        if color[start] != 0 {
+            continue;
+        }
+        let mut stack = vec![(start, 0)];
+        color[start] = 1;
+        while let Some((node, next)) = stack.pop() {
+            if next < graph[node].len() {
+                stack.push((node, next + 1));
+                let child = (graph[node][next] - first) as usize;
+                match color[child] {
+                    0 => {
+                        color[child] = 1;
+                        stack.push((child, 0));
+                    }
+                    1 => return Some(first + child as u64),
+                    _ => {}
+                }
+            } else {
+                color[node] = 2;
+            }
+        }
+    }
+    None
+}
+
+// Maximum array size of a snapshot written before it was a setting.
+#[cfg(feature = "serde")]
+fn default_max_array_size() -> usize {
+    DEFAULT_MAX_ARRAY_SIZE
 }
@@ -0,0 +1,207 @@
+use std::collections::HashMap;
+
+/// Signals
+///
+/// The signals which can be sent between jobs. The user defined signals are numbered from 0 to 31, `Signal::user` rejects the others and the scheduler refuses them.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Signal {
+    Kill,
+    Term,
+    Stop,
+    Cont,
+    Chld,
+    User(u8),
+}
+
+/// Actions
+///
+/// The effect of a signal on the automaton.
+///
+/// * `Terminate` : the job becomes a zombie.
+/// * `Stop` : the job becomes stopped.
+/// * `Continue` : a stopped job becomes ready.
+/// * `Ignore` : nothing happens.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Action {
+    Terminate,
+    Stop,
+    Continue,
+    Ignore,
+}
+
+/// Dispositions
+///
+/// What a job does when it receives a signal.
+///
+/// * `Default` : the default action of the signal is applied.
+/// * `Ignore` : the signal is discarded.
+/// * `Catch` : the signal is recorded in the caught signals of the job, its state does not change.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Disposition {
+    #[default]
+    Default,
+    Ignore,
+    Catch,
+}
+
+/// Number of user defined signals
+pub const USER_SIGNALS: u8 = 32;
+
+impl Signal {
+    /// User defined signal
+    ///
+    /// `None` if `n` is not below `USER_SIGNALS`.
+    ///
+    /// # Example :
+    /// ```rust, ignore
+    ///# use filasse::signal::*;
+    /// assert!(Signal::user(1) == Some(Signal::User(1)));
+    /// assert!(Signal::user(32).is_none());
+    ///```
+    pub fn user(n: u8) -> Option<Signal> {
+        (n < USER_SIGNALS).then_some(Signal::User(n))
+    }
+
+    /// Valid signal
+    ///
+    /// False for a user defined signal out of range.
+    pub fn is_valid(&self) -> bool {
+        !matches!(self, Signal::User(n) if *n >= USER_SIGNALS)
+    }
+
+    /// Signal number
+    ///
+    /// The number of the signal, following Linux for the standard signals. User defined signals start at 32.
+    pub fn number(&self) -> u32 {
+        match self {
+            Signal::Kill => 9,
+            Signal::Term => 15,
+            Signal::Chld => 17,
+            Signal::Cont => 18,
+            Signal::Stop => 19,
+            Signal::User(n) => 32 + u32::from(*n),
+        }
+    }
+
+    /// Default action
+    ///
+    /// # Example :
+    /// ```rust, ignore
+    ///# use filasse::signal::*;
+    /// assert!(Signal::Chld.default_action() == Action::Ignore);
+    ///```
+    pub fn default_action(&self) -> Action {
+        match self {
+            Signal::Kill | Signal::Term | Signal::User(_) => Action::Terminate,
+            Signal::Stop => Action::Stop,
+            Signal::Cont => Action::Continue,
+            Signal::Chld => Action::Ignore,
+        }
+    }
+
+    /// Catchable
+    ///
+    /// KILL and STOP can be neither masked, ignored nor caught.
+    pub fn is_catchable(&self) -> bool {
+        !matches!(self, Signal::Kill | Signal::Stop)
+    }
+}
+
+/// Set of signals
+///
+/// A bit set indexed by the signal numbers, used as a signal mask. An invalid signal is never in a set.
+///
+/// # Example :
+/// ```rust, ignore
+///# use filasse::signal::*;
+/// let mask = SignalSet::empty().with(Signal::Term);
+/// assert!(mask.contains(Signal::Term));
+///```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SignalSet(u64);
+
+impl SignalSet {
+    pub fn empty() -> Self {
+        SignalSet(0)
+    }
+
+    pub fn with(self, signal: Signal) -> Self {
+        SignalSet(self.0 | bit(signal))
+    }
+
+    pub fn without(self, signal: Signal) -> Self {
+        SignalSet(self.0 & !bit(signal))
+    }
+
+    pub fn contains(&self, signal: Signal) -> bool {
+        self.0 & bit(signal) != 0
+    }
+}
+
+// Bit of a signal in a set, none for an invalid signal.
+fn bit(signal: Signal) -> u64 {
+    if signal.is_valid() {
+        1 << signal.number()
+    } else {
+        0
+    }
+}
+
+/// Signal state of a job
+///
+/// The mask, the dispositions, the signals pending because they are masked and the signals caught by the job.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SignalState {
+    pub mask: SignalSet,
+    #[cfg_attr(feature = "serde", serde(with = "handlers"))]
+    pub handlers: HashMap<Signal, Disposition>,
+    pub pending: Vec<Signal>,
+    pub caught: Vec<Signal>,
+}
+
+impl SignalState {
+    /// Disposition of a signal
+    ///
+    /// KILL and STOP always have the default disposition.
+    pub fn disposition(&self, signal: Signal) -> Disposition {
+        if !signal.is_catchable() {
+            return Disposition::Default;
+        }
+        self.handlers.get(&signal).copied().unwrap_or_default()
+    }
+
+    /// Masked
+    ///
+    /// KILL and STOP are never masked.
+    pub fn is_masked(&self, signal: Signal) -> bool {
+        signal.is_catchable() && self.mask.contains(signal)
+    }
+}
+
+// The handlers as a list of pairs, a user signal can not be a key of a JSON map.
+#[cfg(feature = "serde")]
+mod handlers {
+    use super::*;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        handlers: &HashMap<Signal, Disposition>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let mut pairs: Vec<(Signal, Disposition)> =
+            handlers.iter().map(|(&s, &d)| (s, d)).collect();
+        pairs.sort_by_key(|(signal, _)| signal.number());
+        pairs.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<HashMap<Signal, Disposition>, D::Error> {
+        let pairs = Vec::<(Signal, Disposition)>::deserialize(deserializer)?;
+        Ok(pairs.into_iter().collect())
+    }
+}
@@ -0,0 +1,76 @@
+/// Victim selection
+///
+/// How the medium-term scheduler chooses the job to swap out.
+///
+/// * `BlockedFirst` : blocked jobs are swapped out before ready jobs, the lowest priority first.
+/// * `LowestPriority` : the job with the lowest priority is swapped out.
+/// * `LongestRemaining` : the job with the longest remaining duration is swapped out.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Victim {
+    BlockedFirst,
+    LowestPriority,
+    LongestRemaining,
+}
+
+/// Swap decision
+///
+/// A job swapped out (suspended) or swapped in (resumed) by the medium-term scheduler.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Swap {
+    Out(u64),
+    In(u64),
+}
+
+/// Swap candidate
+///
+/// A resident job which can be swapped out.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Candidate {
+    pub pid: u64,
+    pub priority: u32,
+    pub remaining: u64,
+    pub blocked: bool,
+}
+
+/// Medium-term scheduler policy
+///
+/// At most `max_resident` jobs (ready, running and blocked) are kept in memory. The others are suspended. A higher `priority` value means a more important job.
+///
+/// # Example :
+/// ```rust, ignore
+///# use filasse::swap::*;
+/// let policy = SwapPolicy::new(4, Victim::BlockedFirst);
+///```
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SwapPolicy {
+    pub max_resident: usize,
+    pub victim: Victim,
+}
+
+impl SwapPolicy {
+    pub fn new(max_resident: usize, victim: Victim) -> Self {
+        SwapPolicy {
+            max_resident,
+            victim,
+        }
+    }
+
+    /// Choose a victim
+    ///
+    /// Returns the pid of the job to swap out among the candidates. On a tie, the last candidate is chosen, which is the last one to run in queue order.
+    pub fn choose(&self, candidates: &[Candidate]) -> Option<u64> {
+        let chosen = match self.victim {
+            Victim::BlockedFirst => candidates
+                .iter()
+                .rev()
+                .min_by_key(|c| (!c.blocked, c.priority)),
+            Victim::LowestPriority => candidates.iter().rev().min_by_key(|c| c.priority),
+            Victim::LongestRemaining => candidates
+                .iter()
+                .max_by_key(|c| (c.remaining, std::cmp::Reverse(c.priority))),
+        };
+        chosen.map(|c| c.pid)
+    }
+}
@@ -0,0 +1,151 @@
+use crate::batch::*;
+use crate::job::*;
+use std::fmt;
+
+/// Number of fields of a line of the Standard Workload Format
+pub const SWF_FIELDS: usize = 18;
+
+/// Errors of a SWF workload
+///
+/// * `Fields` : the line does not have the 18 fields of the format.
+/// * `Field` : the field of the line is not an integer, or an invalid value for this field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SwfError {
+    Fields { line: usize, count: usize },
+    Field { line: usize, field: &'static str },
+}
+
+impl fmt::Display for SwfError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SwfError::Fields { line, count } => {
+                write!(
+                    f,
+                    "line {}: {} fields instead of {}",
+                    line, count, SWF_FIELDS
+                )
+            }
+            SwfError::Field { line, field } => write!(f, "line {}: invalid {}", line, field),
+        }
+    }
+}
+
+impl std::error::Error for SwfError {}
+
+// Names of the fields, in order.
+const NAMES: [&str; SWF_FIELDS] = [
+    "job number",
+    "submit time",
+    "wait time",
+    "run time",
+    "allocated processors",
+    "average cpu time",
+    "used memory",
+    "requested processors",
+    "requested time",
+    "requested memory",
+    "status",
+    "user id",
+    "group id",
+    "executable",
+    "queue",
+    "partition",
+    "preceding job",
+    "think time",
+];
+
+/// Parse a SWF workload
+///
+/// Each line of the Standard Workload Format of the Parallel Workloads Archive is a job, lines starting with `;` are comments. A processor is a node of one core : a job asks for its requested processors, or its allocated ones when unknown, with its requested memory per processor. Its walltime is the requested time, or its run time when unknown. Its user is `u` followed by the user id. The jobs keep their order, their submit times being relative to the first one.
+///
+/// # Example :
+/// ```rust, ignore
+///# use filasse::swf::*;
+/// let jobs = parse("1 0 -1 100 4 -1 -1 4 200 -1 1 7 -1 -1 -1 -1 -1 -1").unwrap();
+/// assert!(jobs[0].walltime == 200);
+///```
+pub fn parse(text: &str) -> Result<Vec<BatchJob>, SwfError> {
+    let mut jobs = Vec::new();
+    for (number, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with(';') {
+            continue;
+        }
+        let number = number + 1;
+        let values: Vec<&str> = line.split_whitespace().collect();
+        if values.len() != SWF_FIELDS {
+            return Err(SwfError::Fields {
+                line: number,
+                count: values.len(),
+            });
+        }
+        let mut fields = [0i64; SWF_FIELDS];
+        for (i, value) in values.iter().enumerate() {
+            fields[i] = value
+                .parse::<f64>()
+                .map_err(|_| SwfError::Field {
+                    line: number,
+                    field: NAMES[i],
+                })?
+                .round() as i64;
+        }
+        let known = |i: usize| (fields[i] >= 0).then_some(fields[i] as u64);
+        let invalid = |i: usize| SwfError::Field {
+            line: number,
+            field: NAMES[i],
+        };
+        let processors = known(7)
+            .filter(|&p| p > 0)
+            .or(known(4).filter(|&p| p > 0))
+            .ok_or_else(|| invalid(7))?;
+        let runtime = known(3).ok_or_else(|| invalid(3))?;
+        let walltime = known(8).filter(|&t| t > 0).unwrap_or(runtime);
+        let submit = known(1).ok_or_else(|| invalid(1))?;
+        let mut job = BatchJob::new(
+            u32::try_from(processors).map_err(|_| invalid(7))?,
+            1,
+            known(9).unwrap_or(0),
+            walltime,
+        )
+        .runtime(runtime)
+        .submit(submit);
+        if let Some(user) = known(11) {
+            job = job.user(&format!("u{}", user));
+        }
+        jobs.push(job);
+    }
+    if let Some(first) = jobs.iter().map(|job| job.submit).min() {
+        for job in jobs.iter_mut() {
+            job.submit -= first;
+        }
+    }
+    Ok(jobs)
+}
+
+/// Write records in SWF
+///
+/// One line per record by increasing id, the unknown fields being -1. The status is 1 for a completed job, 0 for a failed one and 5 for a cancelled one.
+pub fn write(records: &[Record]) -> String {
+    let mut records: Vec<&Record> = records.iter().collect();
+    records.sort_by_key(|r| r.id);
+    let mut text = String::from("; Standard Workload Format written by filasse\n");
+    for r in records {
+        let processors = r.resources.total_cores();
+        let status = match r.exit {
+            ExitReason::Completed => 1,
+            ExitReason::Cancelled => 5,
+            _ => 0,
+        };
+        text.push_str(&format!(
+            "{} {} {} {} {} -1 -1 {} -1 -1 {} -1 -1 -1 -1 -1 -1 -1\n",
+            r.id,
+            r.submit,
+            r.wait(),
+            r.run_time(),
+            processors,
+            processors,
+            status
+        ));
+    }
+    text
+}
@@ -0,0 +1,413 @@
+use crate::dependency::*;
+use crate::job::*;
+use std::cmp::Ordering;
+use std::fmt;
+
+/// Errors of a workflow
+///
+/// * `UnknownTask` : an edge refers to a task which does not exist.
+/// * `Cycle` : the edges form a cycle through this task.
+/// * `NoProcessor` : the tasks have no cost on any processor.
+/// * `Empty` : the workflow has no task.
+/// * `CostShape` : this task has not a cost on every processor of the first task.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum WorkflowError {
+    UnknownTask(usize),
+    Cycle(usize),
+    NoProcessor,
+    Empty,
+    CostShape(usize),
+}
+
+impl fmt::Display for WorkflowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WorkflowError::UnknownTask(task) => write!(f, "unknown task {}", task),
+            WorkflowError::Cycle(task) => write!(f, "cycle through task {}", task),
+            WorkflowError::NoProcessor => write!(f, "no processor"),
+            WorkflowError::Empty => write!(f, "no task"),
+            WorkflowError::CostShape(task) => {
+                write!(f, "task {} has not a cost on every processor", task)
+            }
+        }
+    }
+}
+
+impl std::error::Error for WorkflowError {}
+
+/// Edge of a workflow
+///
+/// `to` needs the output of `from`. Sending it costs `comm` when both tasks are not on the same processor.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Edge {
+    pub from: usize,
+    pub to: usize,
+    pub comm: u64,
+}
+
+/// DAG of tasks on heterogeneous processors
+///
+/// `costs[task][processor]` is the execution time of a task on a processor.
+///
+/// # Example :
+/// ```rust, ignore
+///# use filasse::workflow::*;
+/// let mut dag = Dag::new(vec![vec![14, 16, 9], vec![13, 19, 18]]).unwrap();
+/// dag.add_edge(0, 1, 18).unwrap();
+/// let schedule = dag.heft().unwrap();
+///```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dag {
+    costs: Vec<Vec<u64>>,
+    edges: Vec<Edge>,
+    processors: usize,
+}
+
+/// Placement of a task
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Assignment {
+    pub task: usize,
+    pub processor: usize,
+    pub start: u64,
+    pub finish: u64,
+}
+
+/// Schedule of a workflow
+///
+/// The placement of every task, indexed by task, the makespan and the critical-path lower bound, which is the longest path using the fastest processor of each task and no communication.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Schedule {
+    pub assignments: Vec<Assignment>,
+    pub makespan: u64,
+    pub lower_bound: u64,
+}
+
+impl Schedule {
+    /// Schedule length ratio
+    ///
+    /// The makespan divided by the lower bound, 1 being optimal.
+    pub fn slr(&self) -> f64 {
+        if self.lower_bound == 0 {
+            return 1.0;
+        }
+        self.makespan as f64 / self.lower_bound as f64
+    }
+}
+
+// How a task chooses its processor.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Placement {
+    InsertionEft,
+    CriticalPath(usize),
+    EarliestStart,
+}
+
+impl Dag {
+    /// Initialisation method
+    ///
+    /// Every task must have a cost on the same number of processors.
+    pub fn new(costs: Vec<Vec<u64>>) -> Result<Self, WorkflowError> {
+        let processors = costs.first().ok_or(WorkflowError::Empty)?.len();
+        if processors == 0 {
+            return Err(WorkflowError::NoProcessor);
+        }
+        if let Some(task) = costs.iter().position(|c| c.len() != processors) {
+            return Err(WorkflowError::CostShape(task));
+        }
+        Ok(Dag {
+            costs,
+            edges: Vec::new(),
+            processors,
+        })
+    }
+
+    /// Workflow of jobs
+    ///
+    /// Build the DAG of a workflow submitted to the scheduler, the first job having the pid `first`. A job runs `duration / speed` on a processor, and each dependency between two jobs of the workflow costs `comm`. Dependencies on other jobs are ignored.
+    ///
+    /// # Example :
+    /// ```rust, ignore
+    ///# use filasse::workflow::*;
+    ///# use filasse::dependency::*;
+    ///# use filasse::job::*;
+    /// let jobs = vec![
+    ///     (Job::new(0, 0, 10, 0), vec![]),
+    ///     (Job::new(0, 0, 20, 0), vec![Dependency::AfterOk(1)]),
+    /// ];
+    /// let dag = Dag::from_workflow(&jobs, 1, &[1.0, 2.0], 5).unwrap();
+    ///```
+    pub fn from_workflow(
+        jobs: &[(Job<New>, Vec<Dependency>)],
+        first: u64,
+        speeds: &[f64],
+        comm: u64,
+    ) -> Result<Self, WorkflowError> {
+        let costs = jobs
+            .iter()
+            .map(|(job, _)| {
+                speeds
+                    .iter()
+                    .map(|speed| (job.state.duration as f64 / speed).ceil() as u64)
+                    .collect()
+            })
+            .collect();
+        let mut dag = Dag::new(costs)?;
+        for (to, (_, dependencies)) in jobs.iter().enumerate() {
+            for pid in dependencies.iter().filter_map(|d| d.pid()) {
+                if (first..first + jobs.len() as u64).contains(&pid) {
+                    dag.add_edge((pid - first) as usize, to, comm)?;
+                }
+            }
+        }
+        Ok(dag)
+    }
+
+    /// Add an edge
+    ///
+    /// The edge is rejected if it closes a cycle.
+    pub fn add_edge(&mut self, from: usize, to: usize, comm: u64) -> Result<(), WorkflowError> {
+        for task in [from, to] {
+            if task >= self.costs.len() {
+                return Err(WorkflowError::UnknownTask(task));
+            }
+        }
+        self.edges.push(Edge { from, to, comm });
+        if let Err(error) = self.topological_order() {
+            self.edges.pop();
+            return Err(error);
+        }
+        Ok(())
+    }
+
+    /// Number of tasks
+    pub fn tasks(&self) -> usize {
+        self.costs.len()
+    }
+
+    /// Number of processors
+    pub fn processors(&self) -> usize {
+        self.processors
+    }
+
+    /// Getter edges
+    pub fn edges(&self) -> &[Edge] {
+        &self.edges
+    }
+
+    /// Upward ranks
+    ///
+    /// The length of the longest path from each task to an exit task, using mean execution and communication costs.
+    pub fn upward_ranks(&self) -> Vec<f64> {
+        let order = self.topological_order().unwrap_or_default();
+        let mut rank = vec![0.0; self.tasks()];
+        for &task in order.iter().rev() {
+            let tail = self
+                .successors(task)
+                .map(|e| e.comm as f64 * self.comm_ratio() + rank[e.to])
+                .fold(0.0, f64::max);
+            rank[task] = self.mean_cost(task) + tail;
+        }
+        rank
+    }
+
+    /// Downward ranks
+    ///
+    /// The length of the longest path from an entry task to each task, the task excluded, using mean costs.
+    pub fn downward_ranks(&self) -> Vec<f64> {
+        let order = self.topological_order().unwrap_or_default();
+        let mut rank = vec![0.0; self.tasks()];
+        for &task in order.iter() {
+            rank[task] = self
+                .predecessors(task)
+                .map(|e| rank[e.from] + self.mean_cost(e.from) + e.comm as f64 * self.comm_ratio())
+                .fold(0.0, f64::max);
+        }
+        rank
+    }
+
+    /// Critical-path lower bound
+    ///
+    /// The longest path using the fastest processor of each task and no communication. No schedule can be shorter.
+    pub fn lower_bound(&self) -> u64 {
+        let order = self.topological_order().unwrap_or_default();
+        let mut finish = vec![0; self.tasks()];
+        for &task in order.iter() {
+            let start = self.predecessors(task).map(|e| finish[e.from]).max();
+            finish[task] = start.unwrap_or(0) + self.costs[task].iter().min().unwrap();
+        }
+        finish.into_iter().max().unwrap_or(0)
+    }
+
+    /// HEFT
+    ///
+    /// Heterogeneous Earliest Finish Time : tasks are taken by decreasing upward rank and placed on the processor where they finish first, possibly in an idle slot between two tasks.
+    pub fn heft(&self) -> Result<Schedule, WorkflowError> {
+        self.list_schedule(&self.upward_ranks(), Placement::InsertionEft)
+    }
+
+    /// CPOP
+    ///
+    /// Critical Path On a Processor : tasks are taken by decreasing sum of upward and downward ranks. The tasks of the critical path all go on the processor which runs them the fastest, the others where they finish first.
+    pub fn cpop(&self) -> Result<Schedule, WorkflowError> {
+        let up = self.upward_ranks();
+        let down = self.downward_ranks();
+        let priority: Vec<f64> = up.iter().zip(down.iter()).map(|(u, d)| u + d).collect();
+        let critical = priority.iter().cloned().fold(0.0, f64::max);
+        let on_path: Vec<usize> = (0..self.tasks())
+            .filter(|&t| (priority[t] - critical).abs() < 1e-9)
+            .collect();
+        let processor = (0..self.processors)
+            .min_by_key(|&p| on_path.iter().map(|&t| self.costs[t][p]).sum::<u64>())
+            .ok_or(WorkflowError::NoProcessor)?;
+        self.list_schedule(&priority, Placement::CriticalPath(processor))
+    }
+
+    /// List scheduling
+    ///
+    /// Tasks are taken by decreasing upward rank and placed, without insertion, on the processor where they start first.
+    pub fn list_scheduling(&self) -> Result<Schedule, WorkflowError> {
+        self.list_schedule(&self.upward_ranks(), Placement::EarliestStart)
+    }
+
+    fn list_schedule(
+        &self,
+        priority: &[f64],
+        placement: Placement,
+    ) -> Result<Schedule, WorkflowError> {
+        self.topological_order()?;
+        let critical: Vec<bool> = match placement {
+            Placement::CriticalPath(_) => {
+                let max = priority.iter().cloned().fold(0.0, f64::max);
+                priority.iter().map(|p| (p - max).abs() < 1e-9).collect()
+            }
+            _ => vec![false; self.tasks()],
+        };
+        let mut slots: Vec<Vec<(u64, u64)>> = vec![Vec::new(); self.processors];
+        let mut done: Vec<Option<Assignment>> = vec![None; self.tasks()];
+        let mut missing: Vec<usize> = (0..self.tasks())
+            .map(|t| self.predecessors(t).count())
+            .collect();
+        let mut ready: Vec<usize> = (0..self.tasks()).filter(|&t| missing[t] == 0).collect();
+        while !ready.is_empty() {
+            let index = (0..ready.len())
+                .max_by(|&a, &b| {
+                    // Equal ranks are computed along different paths, so compare them with a tolerance.
+                    let (pa, pb) = (priority[ready[a]], priority[ready[b]]);
+                    let order = if (pa - pb).abs() < 1e-9 {
+                        Ordering::Equal
+                    } else {
+                        pa.partial_cmp(&pb).unwrap()
+                    };
+                    order.then(ready[b].cmp(&ready[a]))
+                })
+                .unwrap();
+            let task = ready.swap_remove(index);
+            let candidates: Vec<usize> = match placement {
+                Placement::CriticalPath(p) if critical[task] => vec![p],
+                _ => (0..self.processors).collect(),
+            };
+            let insertion = placement != Placement::EarliestStart;
+            let best = candidates
+                .into_iter()
+                .map(|p| {
+                    let ready_at = self
+                        .predecessors(task)
+                        .map(|e| {
+                            let pred = done[e.from].unwrap();
+                            pred.finish + if pred.processor == p { 0 } else { e.comm }
+                        })
+                        .max()
+                        .unwrap_or(0);
+                    let cost = self.costs[task][p];
+                    let start = earliest_slot(&slots[p], ready_at, cost, insertion);
+                    Assignment {
+                        task,
+                        processor: p,
+                        start,
+                        finish: start + cost,
+                    }
+                })
+                .min_by_key(|a| match placement {
+                    Placement::EarliestStart => (a.start, a.finish, a.processor),
+                    _ => (a.finish, a.start, a.processor),
+                })
+                .ok_or(WorkflowError::NoProcessor)?;
+            let busy = &mut slots[best.processor];
+            let at = busy.partition_point(|&(s, _)| s <= best.start);
+            busy.insert(at, (best.start, best.finish));
+            done[task] = Some(best);
+            for e in self.successors(task) {
+                missing[e.to] -= 1;
+                if missing[e.to] == 0 {
+                    ready.push(e.to);
+                }
+            }
+        }
+        let assignments: Vec<Assignment> = done.into_iter().map(|a| a.unwrap()).collect();
+        Ok(Schedule {
+            makespan: assignments.iter().map(|a| a.finish).max().unwrap_or(0),
+            lower_bound: self.lower_bound(),
+            assignments,
+        })
+    }
+
+    fn successors(&self, task: usize) -> impl Iterator<Item = &Edge> {
+        self.edges.iter().filter(move |e| e.from == task)
+    }
+
+    fn predecessors(&self, task: usize) -> impl Iterator<Item = &Edge> {
+        self.edges.iter().filter(move |e| e.to == task)
+    }
+
+    fn mean_cost(&self, task: usize) -> f64 {
+        self.costs[task].iter().sum::<u64>() as f64 / self.processors as f64
+    }
+
+    // Mean communication cost factor : an edge costs nothing when both tasks share a processor.
+    fn comm_ratio(&self) -> f64 {
+        if self.processors <= 1 {
+            0.0
+        } else {
+            1.0
+        }
+    }
+
+    fn topological_order(&self) -> Result<Vec<usize>, WorkflowError> {
+        let mut missing: Vec<usize> = (0..self.tasks())
+            .map(|t| self.predecessors(t).count())
+            .collect();
+        let mut stack: Vec<usize> = (0..self.tasks())
+            .rev()
+            .filter(|&t| missing[t] == 0)
+            .collect();
+        let mut order = Vec::with_capacity(self.tasks());
+        while let Some(task) = stack.pop() {
+            order.push(task);
+            for e in self.successors(task) {
+                missing[e.to] -= 1;
+                if missing[e.to] == 0 {
+                    stack.push(e.to);
+                }
+            }
+        }
+        match (0..self.tasks()).find(|&t| missing[t] > 0) {
+            Some(task) => Err(WorkflowError::Cycle(task)),
+            None => Ok(order),
+        }
+    }
+}
+
+// Earliest start at or after `ready_at` for `cost` on a processor busy during `slots`, sorted by start.
+fn earliest_slot(slots: &[(u64, u64)], ready_at: u64, cost: u64, insertion: bool) -> u64 {
+    if !insertion {
+        return slots.last().map_or(ready_at, |&(_, end)| end.max(ready_at));
+    }
+    let mut start = ready_at;
+    for &(s, e) in slots {
+        if start + cost <= s {
+            return start;
+        }
+        start = start.max(e);
+    }
+    start
+}
@@ -0,0 +1,372 @@
+use crate::batch::*;
+use crate::dependency::*;
+use crate::job::*;
+use crate::policy::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+
+/// Errors of a workload file
+///
+/// * `Parse` : the file does not match the schema, the message giving the position.
+/// * `Job` : the field of the job is invalid.
+/// * `Scheduler` : the field of the scheduler configuration is invalid.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WorkloadError {
+    Parse(String),
+    Job {
+        job: String,
+        field: &'static str,
+        message: String,
+    },
+    Scheduler {
+        field: &'static str,
+        message: String,
+    },
+}
+
+impl fmt::Display for WorkloadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WorkloadError::Parse(message) => write!(f, "{}", message),
+            WorkloadError::Job {
+                job,
+                field,
+                message,
+            } => write!(f, "job {}: {}: {}", job, field, message),
+            WorkloadError::Scheduler { field, message } => {
+                write!(f, "scheduler: {}: {}", field, message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for WorkloadError {}
+
+/// Formats of a workload file
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Format {
+    Toml,
+    Json,
+    Yaml,
+}
+
+impl Format {
+    /// Format of a path
+    ///
+    /// Guessed from the extension, `None` if it is not a workload format.
+    pub fn of(path: &str) -> Option<Format> {
+        let extension = std::path::Path::new(path).extension()?.to_str()?;
+        match extension.to_lowercase().as_str() {
+            "toml" => Some(Format::Toml),
+            "json" => Some(Format::Json),
+            "yaml" | "yml" => Some(Format::Yaml),
+            _ => None,
+        }
+    }
+}
+
+/// Scheduler configuration of a workload
+///
+/// The policy is named like on the command line, `quantum` overriding the quantum of round robin.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    #[serde(default = "default_policy")]
+    pub policy: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quantum: Option<u64>,
+    #[serde(default = "one")]
+    pub cpus: u32,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            policy: default_policy(),
+            quantum: None,
+            cpus: 1,
+        }
+    }
+}
+
+/// Job of a workload
+///
+/// `bursts` alternate CPU and I/O bursts, starting with a CPU burst. The job asks for `processors` nodes of one core with `memory` memory on each. `dependencies` are the names of the jobs which must complete successfully before it starts. Without walltime, the limit is the sum of the bursts.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct JobSpec {
+    pub name: String,
+    #[serde(default)]
+    pub arrival: u64,
+    pub bursts: Vec<u64>,
+    #[serde(default)]
+    pub priority: u32,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dependencies: Vec<String>,
+    #[serde(default = "one")]
+    pub processors: u32,
+    #[serde(default)]
+    pub memory: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub walltime: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+}
+
+impl JobSpec {
+    /// CPU time
+    ///
+    /// The sum of the CPU bursts.
+    pub fn cpu(&self) -> u64 {
+        self.bursts.iter().step_by(2).sum()
+    }
+
+    /// Elapsed time
+    ///
+    /// The sum of all the bursts.
+    pub fn elapsed(&self) -> u64 {
+        self.bursts.iter().sum()
+    }
+}
+
+/// Workload
+///
+/// The jobs of an experiment and the configuration of the scheduler, written in TOML, JSON or YAML.
+///
+/// # Example :
+/// ```rust, ignore
+///# use filasse::workload::*;
+/// let workload = Workload::parse(r#"
+/// [scheduler]
+/// policy = "easy"
+/// cpus = 4
+///
+/// [[jobs]]
+/// name = "fetch"
+/// bursts = [10]
+///
+/// [[jobs]]
+/// name = "build"
+/// arrival = 5
+/// bursts = [30, 5, 10]
+/// processors = 2
+/// dependencies = ["fetch"]
+/// command = "make -j2"
+/// "#, Format::Toml).unwrap();
+///```
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Workload {
+    #[serde(default)]
+    pub scheduler: Config,
+    pub jobs: Vec<JobSpec>,
+}
+
+impl Workload {
+    /// Parse and validate a workload
+    pub fn parse(text: &str, format: Format) -> Result<Self, WorkloadError> {
+        let workload: Workload = match format {
+            Format::Toml => toml::from_str(text).map_err(|e| e.to_string()),
+            Format::Json => serde_json::from_str(text).map_err(|e| e.to_string()),
+            Format::Yaml => serde_yaml::from_str(text).map_err(|e| e.to_string()),
+        }
+        .map_err(WorkloadError::Parse)?;
+        workload.validate()?;
+        Ok(workload)
+    }
+
+    /// Write a workload
+    pub fn write(&self, format: Format) -> Result<String, WorkloadError> {
+        match format {
+            Format::Toml => toml::to_string(self).map_err(|e| e.to_string()),
+            Format::Json => serde_json::to_string_pretty(self).map_err(|e| e.to_string()),
+            Format::Yaml => serde_yaml::to_string(self).map_err(|e| e.to_string()),
+        }
+        .map_err(WorkloadError::Parse)
+    }
+
+    /// Validate a workload
+    ///
+    /// The names are unique, each job has bursts, processors within the CPUs and a positive walltime, the dependencies name other jobs without cycle, and the policy is known and runs on the CPUs.
+    pub fn validate(&self) -> Result<(), WorkloadError> {
+        let policy = self.policy()?;
+        let cpus = self.scheduler.cpus;
+        if cpus == 0 {
+            return Err(scheduler("cpus", "must be positive"));
+        }
+        if matches!(policy, Policy::RoundRobin { .. }) && cpus != 1 {
+            return Err(scheduler("cpus", "round robin runs on one cpu"));
+        }
+        let mut index = HashMap::new();
+        for (i, job) in self.jobs.iter().enumerate() {
+            if job.name.is_empty() {
+                return Err(invalid(&format!("#{}", i + 1), "name", "is empty"));
+            }
+            if index.insert(job.name.as_str(), i).is_some() {
+                return Err(invalid(&job.name, "name", "is not unique"));
+            }
+        }
+        for job in self.jobs.iter() {
+            if job.bursts.is_empty() {
+                return Err(invalid(&job.name, "bursts", "is empty"));
+            }
+            if job.processors == 0 {
+                return Err(invalid(&job.name, "processors", "must be positive"));
+            }
+            if job.processors > cpus {
+                let message = format!("{} processors on {} cpus", job.processors, cpus);
+                return Err(invalid(&job.name, "processors", &message));
+            }
+            if job.walltime == Some(0) {
+                return Err(invalid(&job.name, "walltime", "must be positive"));
+            }
+            for dependency in job.dependencies.iter() {
+                if dependency == &job.name {
+                    return Err(invalid(&job.name, "dependencies", "depends on itself"));
+                }
+                if !index.contains_key(dependency.as_str()) {
+                    let message = format!("unknown job {}", dependency);
+                    return Err(invalid(&job.name, "dependencies", &message));
+                }
+            }
+        }
+        if let Some(i) = self.cycle(&index) {
+            let name = &self.jobs[i].name;
+            return Err(invalid(name, "dependencies", "cyclic dependency"));
+        }
+        Ok(())
+    }
+
+    /// Policy of the scheduler configuration
+    pub fn policy(&self) -> Result<Policy, WorkloadError> {
+        let policy: Policy = self
+            .scheduler
+            .policy
+            .parse()
+            .map_err(|e: PolicyError| scheduler("policy", &e.to_string()))?;
+        match (policy, self.scheduler.quantum) {
+            (_, Some(0)) => Err(scheduler("quantum", "must be positive")),
+            (Policy::RoundRobin { .. }, Some(quantum)) => Ok(Policy::RoundRobin { quantum }),
+            _ => Ok(policy),
+        }
+    }
+
+    /// Batch jobs
+    ///
+    /// One job per entry, in order : it is submitted at its arrival and holds its processors through its I/O bursts, its runtime being its elapsed time. Its dependencies are the positions of the jobs they name, from 1. The simulated policies neither order the jobs by priority nor run their commands : a job with a priority or a command is rejected, they are only kept by `workflow`.
+    pub fn batch_jobs(&self) -> Result<Vec<BatchJob>, WorkloadError> {
+        let ids: HashMap<&str, u64> = self
+            .jobs
+            .iter()
+            .enumerate()
+            .map(|(i, job)| (job.name.as_str(), i as u64 + 1))
+            .collect();
+        self.jobs
+            .iter()
+            .map(|job| {
+                if job.priority != 0 {
+                    return Err(invalid(&job.name, "priority", "is not used by simulations"));
+                }
+                if job.command.is_some() {
+                    return Err(invalid(&job.name, "command", "is not run by simulations"));
+                }
+                let runtime = job.elapsed();
+                let batch = BatchJob::new(
+                    job.processors,
+                    1,
+                    job.memory,
+                    job.walltime.unwrap_or(runtime),
+                )
+                .runtime(runtime)
+                .submit(job.arrival);
+                Ok(job
+                    .dependencies
+                    .iter()
+                    .filter_map(|name| ids.get(name.as_str()))
+                    .fold(batch, |batch, &id| batch.after(id)))
+            })
+            .collect()
+    }
+
+    /// Workflow
+    ///
+    /// The jobs for `Scheduler::add_workflow`, their durations being their CPU times, their dependencies `AfterOk` on the pids they get when the scheduler gives pids from `first`.
+    pub fn workflow(&self, first: u64) -> Vec<(Job<New>, Vec<Dependency>)> {
+        let pids: HashMap<&str, u64> = self
+            .jobs
+            .iter()
+            .enumerate()
+            .map(|(i, job)| (job.name.as_str(), first + i as u64))
+            .collect();
+        self.jobs
+            .iter()
+            .map(|job| {
+                let dependencies = job
+                    .dependencies
+                    .iter()
+                    .filter_map(|name| pids.get(name.as_str()))
+                    .map(|&pid| Dependency::AfterOk(pid))
+                    .collect();
+                (Job::new(0, 0, job.cpu(), job.priority), dependencies)
+            })
+            .collect()
+    }
+
+    // Index of a job on a cycle of dependencies.
+    fn cycle(&self, index: &HashMap<&str, usize>) -> Option<usize> {
+        // 0 unvisited, 1 on the path, 2 done.
+        let mut marks = vec![0u8; self.jobs.len()];
+        for root in 0..self.jobs.len() {
+            if marks[root] != 0 {
+                continue;
+            }
+            let mut stack = vec![(root, 0)];
+            marks[root] = 1;
+            while let Some(&mut (node, ref mut next)) = stack.last_mut() {
+                match self.jobs[node].dependencies.get(*next) {
+                    Some(name) => {
+                        *next += 1;
+                        let child = index[name.as_str()];
+                        match marks[child] {
+                            0 => {
+                                marks[child] = 1;
+                                stack.push((child, 0));
+                            }
+                            1 => return Some(child),
+                            _ => {}
+                        }
+                    }
+                    None => {
+                        marks[node] = 2;
+                        stack.pop();
+                    }
+                }
+            }
+        }
+        None
+    }
+}
+
+fn default_policy() -> String {
+    Policy::Fcfs.to_string()
+}
+
+fn one() -> u32 {
+    1
+}
+
+fn invalid(job: &str, field: &'static str, message: &str) -> WorkloadError {
+    WorkloadError::Job {
+        job: job.to_string(),
+        field,
+        message: message.to_string(),
+    }
+}
+
+fn scheduler(field: &'static str, message: &str) -> WorkloadError {
+    WorkloadError::Scheduler {
+        field,
+        message: message.to_string(),
+    }
+}
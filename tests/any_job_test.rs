@@ -0,0 +1,65 @@
+#[cfg(test)]
+mod tests {
+
+    use filasse::any_job::*;
+    use filasse::job::*;
+    use filasse::scheduler::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn try_into_running() {
+        let foo: Job<Ready> = Job::from(Job::new(5, 2, 2, 1));
+        let foo = AnyJob::from(foo);
+        assert!(foo.state() == JobState::Ready);
+        let bar = foo.try_into_running().unwrap();
+        assert!(
+            bar.state
+                == Running {
+                    duration: 2,
+                    priority: 1
+                }
+        );
+    }
+
+    #[test]
+    fn illegal_edge() {
+        let foo = AnyJob::from(Job::new(5, 2, 2, 1));
+        let err = foo.try_into_running().unwrap_err();
+        assert!(err.job == foo);
+        assert!(err.to == JobState::Running);
+        assert!(err.to_string() == "illegal transition of job 5 from new to running");
+    }
+
+    #[test]
+    fn transition() {
+        let mut foo = AnyJob::from(Job::new(5, 2, 2, 1));
+        for state in [
+            JobState::Ready,
+            JobState::Running,
+            JobState::Blocked,
+            JobState::SuspendedBlocked,
+            JobState::SuspendedReady,
+            JobState::Zombie,
+            JobState::Terminated,
+        ] {
+            foo = foo.transition(state).unwrap();
+            assert!(foo.state() == state);
+        }
+        assert!(!foo.can_become(JobState::Ready));
+        assert!(foo.pid() == 5 && foo.parent() == 2);
+    }
+
+    #[test]
+    fn heterogeneous_map() {
+        let mut sched = Scheduler::new(1);
+        sched.add_to_scheduler(&mut Job::new(0, 0, 1, 0));
+        sched.add_to_scheduler(&mut Job::new(0, 0, 5, 0));
+        sched.process();
+        sched.dispatch();
+        let jobs: HashMap<u64, AnyJob> =
+            (1..=2).map(|pid| (pid, sched.job(pid).unwrap())).collect();
+        assert!(jobs[&1].state() == JobState::Zombie);
+        assert!(jobs[&2].state() == JobState::Running);
+        assert!(sched.job(3).is_none());
+    }
+}
@@ -0,0 +1,124 @@
+#[cfg(test)]
+mod tests {
+
+    use filasse::array::*;
+    use filasse::job::*;
+    use filasse::scheduler::*;
+
+    #[test]
+    fn parse() {
+        let spec = ArraySpec::parse("0-15:4,3,20%2").unwrap();
+        assert!(spec.indices == vec![0, 3, 4, 8, 12, 20]);
+        assert!(spec.throttle == Some(2));
+        assert!(ArraySpec::parse("1-3").unwrap().throttle.is_none());
+        assert!(ArraySpec::parse("5-1") == Err(ArrayError::InvalidSpec("5-1".to_string())));
+        assert!(ArraySpec::parse("1-4:0").is_err());
+        assert!(ArraySpec::parse("a").is_err());
+    }
+
+    #[test]
+    fn parse_limits() {
+        assert!(ArraySpec::parse("0-3%0") == Err(ArrayError::InvalidSpec("0-3%0".to_string())));
+        assert!(ArraySpec::parse("0-4000000000") == Err(ArrayError::TooLarge(MAX_ARRAY_INDICES)));
+        assert!(
+            ArraySpec::parse("0-4000000,4000001-4000002")
+                == Err(ArrayError::TooLarge(MAX_ARRAY_INDICES))
+        );
+        assert!(ArraySpec::parse("0-5,3-8").unwrap().indices.len() == 9);
+        assert!(
+            ArraySpec::parse("0-4000000000:4000000")
+                .unwrap()
+                .indices
+                .len()
+                == 1001
+        );
+    }
+
+    #[test]
+    fn max_array_size() {
+        let mut sched = Scheduler::new(10);
+        assert!(sched.max_array_size() == DEFAULT_MAX_ARRAY_SIZE);
+        let large = ArraySpec::parse("0-4999").unwrap();
+        assert!(
+            sched.add_array(&Job::default(), large.clone())
+                == Err(SchedulerError::ArrayTooLarge {
+                    size: 5000,
+                    max: DEFAULT_MAX_ARRAY_SIZE
+                })
+        );
+        let indices = (0..2000).map(|i| i % 1000).collect();
+        let id = sched
+            .add_array(&Job::default(), ArraySpec::new(indices))
+            .unwrap();
+        assert!(sched.array(id).unwrap().len() == 1000);
+        sched.set_max_array_size(5000);
+        let id = sched.add_array(&Job::default(), large).unwrap();
+        assert!(sched.array(id).unwrap().len() == 5000);
+    }
+
+    #[test]
+    fn substitution() {
+        assert!(substitute("out_%A_%a.log", 7, 3) == "out_7_3.log");
+        assert!(substitute("100%% %x%", 1, 2) == "100% %x%");
+    }
+
+    #[test]
+    fn submit() {
+        let mut sched = Scheduler::new(10);
+        sched.add_to_scheduler(&mut Job::new(0, 0, 10, 0));
+        let spec = ArraySpec::parse("1-3")
+            .unwrap()
+            .command("./sweep --seed %a -o %A");
+        let id = sched.add_array(&Job::new(0, 0, 10, 0), spec).unwrap();
+        assert!(id == 2);
+        let array = sched.array(id).unwrap();
+        assert!(array.len() == 3);
+        assert!(array.pid(3) == Some(4));
+        assert!(sched.array_of(3).unwrap().index(3) == Some(2));
+        assert!(sched.array_of(1).is_none());
+        assert!(sched.array_of(5).is_none());
+        assert!(sched.command_line(4) == Some("./sweep --seed 3 -o 2".to_string()));
+        assert!(sched.array_counts(id).unwrap()[&JobState::Ready] == 3);
+        assert!(
+            sched.add_array(&Job::default(), ArraySpec::new(vec![]))
+                == Err(SchedulerError::EmptyArray)
+        );
+        assert!(
+            sched.add_array(&Job::default(), ArraySpec::new(vec![1]).throttle(0))
+                == Err(SchedulerError::ZeroThrottle)
+        );
+    }
+
+    #[test]
+    fn throttle() {
+        let mut sched = Scheduler::new(10);
+        let spec = ArraySpec::parse("0-4%2").unwrap();
+        let id = sched.add_array(&Job::new(0, 0, 10, 0), spec).unwrap();
+        let counts = sched.array_counts(id).unwrap();
+        assert!(counts[&JobState::Ready] == 2);
+        assert!(counts[&JobState::New] == 3);
+        sched.process();
+        let counts = sched.array_counts(id).unwrap();
+        assert!(counts[&JobState::Zombie] == 1);
+        assert!(counts[&JobState::Ready] == 2);
+        assert!(counts[&JobState::New] == 2);
+        assert!(sched.set_array_throttle(id, Some(0)) == Err(SchedulerError::ZeroThrottle));
+        assert!(sched.array(id).unwrap().throttle == Some(2));
+        sched.set_array_throttle(id, None).unwrap();
+        assert!(sched.array_counts(id).unwrap()[&JobState::Ready] == 4);
+        assert!(sched.set_array_throttle(42, None) == Err(SchedulerError::UnknownArray(42)));
+    }
+
+    #[test]
+    fn cancel() {
+        let mut sched = Scheduler::new(10);
+        let spec = ArraySpec::parse("0-3%1").unwrap();
+        let id = sched.add_array(&Job::new(0, 0, 10, 0), spec).unwrap();
+        sched.process();
+        assert!(sched.cancel_array(id) == Ok(3));
+        let states = sched.array_states(id).unwrap();
+        assert!(states.iter().all(|(_, state)| *state == JobState::Zombie));
+        assert!(sched.registry().get(id + 3).unwrap().exit == Some(ExitReason::Cancelled));
+        assert!(sched.cancel_array(42) == Err(SchedulerError::UnknownArray(42)));
+    }
+}
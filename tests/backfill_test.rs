@@ -0,0 +1,140 @@
+#[cfg(test)]
+mod tests {
+
+    use filasse::backfill::*;
+    use filasse::batch::*;
+    use filasse::cluster::*;
+
+    fn start(batch: &BatchScheduler, id: u64) -> u64 {
+        batch.finished().iter().find(|r| r.id == id).unwrap().start
+    }
+
+    #[test]
+    fn easy() {
+        // The backfilled jobs end with their grace period before the head starts.
+        let jobs = vec![
+            BatchJob::new(2, 1, 1, 100),
+            BatchJob::new(4, 1, 1, 100),
+            BatchJob::new(2, 1, 1, 30),
+            BatchJob::new(2, 1, 1, 30),
+        ];
+        let report = compare(&Cluster::homogeneous(4, 1, 1), &jobs, Backfill::Easy).unwrap();
+        assert!(report.backfilled == 2);
+        assert!(report.makespan == 200);
+        assert!(report.fcfs_makespan == 230);
+        assert!((report.gain() - (0.9 - 720.0 / 920.0)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn head_not_delayed() {
+        let mut batch = BatchScheduler::new(Cluster::homogeneous(4, 1, 1));
+        batch.set_backfill(Backfill::Easy);
+        batch.submit(BatchJob::new(2, 1, 1, 10)).unwrap();
+        let head = batch.submit(BatchJob::new(4, 1, 1, 10)).unwrap();
+        let long = batch.submit(BatchJob::new(2, 1, 1, 20)).unwrap();
+        batch.run();
+        assert!(batch.backfilled() == 0);
+        assert!(start(&batch, head) == 10);
+        assert!(start(&batch, long) == 20);
+    }
+
+    #[test]
+    fn conservative() {
+        let jobs = vec![
+            BatchJob::new(3, 1, 1, 10),
+            BatchJob::new(2, 1, 1, 10),
+            BatchJob::new(4, 1, 1, 10),
+            BatchJob::new(1, 1, 1, 25),
+        ];
+        let mut easy = BatchScheduler::new(Cluster::homogeneous(4, 1, 1));
+        easy.set_backfill(Backfill::Easy);
+        let mut conservative = BatchScheduler::new(Cluster::homogeneous(4, 1, 1));
+        conservative.set_backfill(Backfill::Conservative);
+        for job in jobs {
+            easy.submit(job.clone()).unwrap();
+            conservative.submit(job).unwrap();
+        }
+        easy.run();
+        conservative.run();
+        // EASY only protects the head, so the third job is delayed by the backfilled one.
+        assert!(easy.backfilled() == 1);
+        assert!(start(&easy, 4) == 0);
+        assert!(start(&easy, 3) == 25);
+        assert!(conservative.backfilled() == 0);
+        assert!(start(&conservative, 3) == 20);
+        assert!(start(&conservative, 4) == 30);
+    }
+
+    #[test]
+    fn overrun_estimate() {
+        let mut batch = BatchScheduler::new(Cluster::homogeneous(2, 1, 1));
+        batch.set_backfill(Backfill::Easy);
+        batch
+            .submit(BatchJob::new(1, 1, 1, 10).runtime(100))
+            .unwrap();
+        let head = batch.submit(BatchJob::new(2, 1, 1, 10)).unwrap();
+        // Past its walltime, the first job holds its node until its KILL at 40, and the small job would run into the head with its grace period.
+        let small = batch.submit(BatchJob::new(1, 1, 1, 15).submit(20)).unwrap();
+        batch.run();
+        assert!(batch.backfilled() == 0);
+        assert!(start(&batch, head) == 40);
+        assert!(start(&batch, small) == 50);
+    }
+
+    #[test]
+    fn grace_before_head() {
+        let mut batch = BatchScheduler::new(Cluster::homogeneous(2, 1, 1));
+        batch.set_backfill(Backfill::Easy);
+        batch.set_grace(10);
+        batch.submit(BatchJob::new(1, 1, 1, 50)).unwrap();
+        let head = batch.submit(BatchJob::new(2, 1, 1, 10)).unwrap();
+        // Would end at 55 with its grace period, after the start of the head at 50.
+        let late = batch.submit(BatchJob::new(1, 1, 1, 45)).unwrap();
+        // Ends at 50 with its grace period.
+        let early = batch.submit(BatchJob::new(1, 1, 1, 30).submit(10)).unwrap();
+        batch.run();
+        assert!(start(&batch, early) == 10);
+        assert!(start(&batch, head) == 50);
+        assert!(start(&batch, late) == 60);
+    }
+
+    #[test]
+    fn walltime_estimate() {
+        let mut batch = BatchScheduler::new(Cluster::homogeneous(2, 1, 1));
+        batch.set_backfill(Backfill::Easy);
+        batch
+            .submit(BatchJob::new(1, 1, 1, 100).runtime(5))
+            .unwrap();
+        batch.submit(BatchJob::new(2, 1, 1, 10)).unwrap();
+        // Fits before the head only if the walltime of the first job is trusted.
+        let small = batch.submit(BatchJob::new(1, 1, 1, 50)).unwrap();
+        batch.run();
+        assert!(start(&batch, small) == 0);
+    }
+
+    #[test]
+    fn profile() {
+        let cluster = Cluster::homogeneous(2, 4, 8);
+        let nodes = cluster.partition_nodes(None);
+        let mut profile = Profile::new(&cluster);
+        profile.reserve(Slot {
+            start: 0,
+            end: 10,
+            allocation: Allocation {
+                nodes: vec![0],
+                cores: 4,
+                memory: 2,
+            },
+        });
+        assert!(profile.free(0, 0, 10) == (0, 6));
+        assert!(profile.free(0, 10, 20) == (4, 8));
+        let (start, allocation) = profile
+            .earliest(&Resources::new(2, 1, 1), &nodes, 0, 5)
+            .unwrap();
+        assert!(start == 10 && allocation.nodes == vec![0, 1]);
+        let (start, _) = profile
+            .earliest(&Resources::new(1, 1, 1), &nodes, 0, 5)
+            .unwrap();
+        assert!(start == 0);
+    }
+}
@@ -0,0 +1,102 @@
+#[cfg(test)]
+mod tests {
+
+    use filasse::batch::*;
+    use filasse::cluster::*;
+    use filasse::job::*;
+
+    #[test]
+    fn fcfs() {
+        let mut batch = BatchScheduler::new(Cluster::homogeneous(4, 8, 32));
+        let a = batch.submit(BatchJob::new(3, 8, 16, 10)).unwrap();
+        let b = batch.submit(BatchJob::new(2, 8, 16, 5)).unwrap();
+        let c = batch.submit(BatchJob::new(1, 8, 16, 5)).unwrap();
+        batch.schedule();
+        assert!(batch.state(a) == Some(JobState::Running));
+        assert!(batch.state(b) == Some(JobState::Ready));
+        // c would fit, but strict FCFS keeps it behind b.
+        assert!(batch.state(c) == Some(JobState::Ready));
+        assert!(batch.run() == 15);
+        let record = batch.finished().iter().find(|r| r.id == b).unwrap();
+        assert!(record.start == 10 && record.wait() == 10);
+        assert!(batch.state(c) == Some(JobState::Terminated));
+    }
+
+    #[test]
+    fn runtime() {
+        let mut batch = BatchScheduler::new(Cluster::homogeneous(1, 4, 8));
+        batch
+            .submit(BatchJob::new(1, 4, 8, 100).runtime(30))
+            .unwrap();
+        batch
+            .submit(BatchJob::new(1, 4, 8, 100).runtime(20))
+            .unwrap();
+        assert!(batch.run() == 50);
+        assert!((batch.utilization() - 1.0).abs() < 1e-9);
+        assert!((batch.mean_wait() - 15.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn arrivals() {
+        let mut batch = BatchScheduler::new(Cluster::homogeneous(2, 4, 8));
+        let late = batch.submit(BatchJob::new(1, 4, 8, 10).submit(50)).unwrap();
+        assert!(batch.state(late) == Some(JobState::New));
+        batch.submit(BatchJob::new(1, 4, 8, 10)).unwrap();
+        assert!(batch.run() == 60);
+        let record = batch.finished().iter().find(|r| r.id == late).unwrap();
+        assert!(record.start == 50 && record.wait() == 0);
+    }
+
+    #[test]
+    fn dependencies() {
+        let mut batch = BatchScheduler::new(Cluster::homogeneous(2, 4, 8));
+        let first = batch.submit(BatchJob::new(1, 4, 8, 50)).unwrap();
+        let second = batch
+            .submit(BatchJob::new(1, 4, 8, 10).after(first))
+            .unwrap();
+        let overrun = batch
+            .submit(BatchJob::new(1, 4, 8, 10).runtime(20).after(second))
+            .unwrap();
+        let cancelled = batch
+            .submit(BatchJob::new(1, 4, 8, 10).after(overrun))
+            .unwrap();
+        // Waits for the job 6, which is rejected for closing the cycle.
+        let cyclic = batch.submit(BatchJob::new(1, 4, 8, 10).after(6)).unwrap();
+        assert!(
+            batch.submit(BatchJob::new(1, 4, 8, 10).after(cyclic))
+                == Err(BatchError::CyclicDependency(6))
+        );
+        assert!(batch.state(second) == Some(JobState::New));
+        batch.run();
+        let record = |id: u64| batch.finished().iter().find(|r| r.id == id).unwrap();
+        assert!(record(second).start == 50 && record(second).wait() == 50);
+        assert!(record(overrun).start == 60 && record(overrun).exit == ExitReason::Timeout);
+        assert!(record(cancelled).exit == ExitReason::DependencyNeverSatisfied);
+        assert!(record(cancelled).run_time() == 0);
+        assert!(record(cyclic).exit == ExitReason::DependencyNeverSatisfied);
+        assert!(batch.finished().len() == 5);
+        assert!(
+            batch.submit(BatchJob::new(1, 4, 8, 10).after(6))
+                == Err(BatchError::InvalidDependency(6))
+        );
+        assert!(batch.submit(BatchJob::new(1, 4, 8, 10).after(0)).is_err());
+    }
+
+    #[test]
+    fn rejected() {
+        let mut cluster = Cluster::homogeneous(2, 4, 8);
+        cluster.add_partition("small", vec![0]).unwrap();
+        let mut batch = BatchScheduler::new(cluster);
+        assert!(
+            batch.submit(BatchJob::new(3, 1, 1, 1))
+                == Err(BatchError::Unsatisfiable(Resources::new(3, 1, 1)))
+        );
+        assert!(batch
+            .submit(BatchJob::new(2, 1, 1, 1).partition("small"))
+            .is_err());
+        assert!(
+            batch.submit(BatchJob::new(1, 1, 1, 1).partition("gpu"))
+                == Err(BatchError::UnknownPartition("gpu".to_string()))
+        );
+    }
+}
@@ -0,0 +1,211 @@
+#[cfg(test)]
+mod tests {
+
+    use filasse::cli::*;
+    use filasse::policy::*;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(|s| s.to_string()).collect()
+    }
+
+    fn workload(name: &str) -> String {
+        let path = std::env::temp_dir().join(format!("filasse_cli_{}.swf", name));
+        std::fs::write(
+            &path,
+            "1 0 -1 100 2 -1 -1 2 -1 -1 1 -1 -1 -1 -1 -1 -1 -1\n\
+             2 10 -1 50 4 -1 -1 4 -1 -1 1 -1 -1 -1 -1 -1 -1 -1\n\
+             3 20 -1 10 1 -1 -1 1 20 -1 1 -1 -1 -1 -1 -1 -1 -1\n",
+        )
+        .unwrap();
+        path.to_string_lossy().to_string()
+    }
+
+    #[test]
+    fn parse_commands() {
+        assert!(
+            parse(&args(
+                "simulate w.swf --policy rr --quantum 5 --trace t.csv"
+            )) == Ok(Command::Simulate {
+                workload: "w.swf".to_string(),
+                policy: Some(Policy::RoundRobin {
+                    quantum: DEFAULT_QUANTUM
+                }),
+                quantum: Some(5),
+                cpus: None,
+                gantt: None,
+                swf: None,
+                trace: Some("t.csv".to_string()),
+            })
+        );
+        assert!(
+            parse(&args("compare w.swf --policies fcfs,easy --cpus 8"))
+                == Ok(Command::Compare {
+                    workload: "w.swf".to_string(),
+                    policies: Some(vec![Policy::Fcfs, Policy::Easy]),
+                    quantum: None,
+                    cpus: Some(8),
+                })
+        );
+        let experiment = Experiment {
+            jobs: vec![],
+            policy: Some(Policy::RoundRobin { quantum: 50 }),
+            cpus: None,
+        };
+        assert!(experiment.settings(None, Some(5), None) == (Policy::RoundRobin { quantum: 5 }, 1));
+        assert!(experiment.settings(Some(Policy::Easy), Some(5), Some(4)) == (Policy::Easy, 4));
+        assert!(matches!(parse(&args("simulate")), Err(CliError::Usage(_))));
+        assert!(matches!(
+            parse(&args("simulate w.swf --cpus 0")),
+            Err(CliError::Usage(_))
+        ));
+        assert!(matches!(
+            parse(&args("validate w.swf --gantt g")),
+            Err(CliError::Usage(_))
+        ));
+        assert!(matches!(parse(&args("run w.swf")), Err(CliError::Usage(_))));
+        assert!(
+            parse(&args(
+                "sweep lublin:0.5 --quanta 10,50 --cpus 1,8 --seeds 3-5"
+            )) == Ok(Command::Sweep {
+                model: "lublin:0.5".to_string(),
+                policies: None,
+                quanta: vec![10, 50],
+                cpus: vec![1, 8],
+                seeds: vec![3, 4, 5],
+                jobs: 1000,
+                threads: None,
+                csv: None,
+                runs: None,
+            })
+        );
+        assert!(matches!(
+            parse(&args("sweep lublin --cpus 0")),
+            Err(CliError::Usage(_))
+        ));
+        assert!(matches!(
+            parse(&args("sweep gauss")),
+            Err(CliError::Harness(_))
+        ));
+        assert!(matches!(
+            parse(&args("simulate w.swf --policy sjf")),
+            Err(CliError::Policy(PolicyError::UnknownPolicy(_)))
+        ));
+        assert!(
+            parse(&args("replay events.jsonl --quantum 20"))
+                == Ok(Command::Replay {
+                    recording: "events.jsonl".to_string(),
+                    workload: None,
+                    policy: None,
+                    quantum: Some(20),
+                    cpus: None,
+                })
+        );
+        assert!(matches!(
+            parse(&args("replay trace.csv --policy easy")),
+            Err(CliError::Usage(_))
+        ));
+        assert!(matches!(
+            parse(&args(
+                "replay trace.csv --workload w.swf --policy fcfs,easy"
+            )),
+            Err(CliError::Usage(_))
+        ));
+    }
+
+    #[test]
+    fn simulate() {
+        let path = workload("simulate");
+        let gantt = std::env::temp_dir().join("filasse_cli_simulate.gantt");
+        let command = Command::Simulate {
+            workload: path,
+            policy: Some(Policy::Easy),
+            quantum: None,
+            cpus: Some(4),
+            gantt: Some(gantt.to_string_lossy().to_string()),
+            swf: None,
+            trace: None,
+        };
+        let output = run(&command).unwrap();
+        assert!(output.contains("policy          easy"));
+        assert!(output.contains("makespan        150"));
+        assert!(std::fs::read_to_string(gantt).unwrap().lines().count() == 4);
+    }
+
+    #[test]
+    fn compare_and_validate() {
+        let path = workload("compare");
+        let output = run(&parse(&args(&format!("compare {} --cpus 4", path))).unwrap()).unwrap();
+        assert!(output.lines().count() == 5);
+        assert!(output.lines().nth(2).unwrap().starts_with("easy"));
+        let valid = run(&parse(&args(&format!("validate {}", path))).unwrap()).unwrap();
+        assert!(valid.contains("3 jobs, valid"));
+        assert!(
+            run(&parse(&args(&format!("validate {} --cpus 2", path))).unwrap())
+                == Err(CliError::Unsatisfiable { job: 2, cpus: 2 })
+        );
+        assert!(matches!(
+            run(&parse(&args("validate /nonexistent/w.swf")).unwrap()),
+            Err(CliError::Io { .. })
+        ));
+    }
+    #[test]
+    fn dependencies() {
+        let path = std::env::temp_dir().join("filasse_cli_dependencies.toml");
+        std::fs::write(
+            &path,
+            "[scheduler]\npolicy = \"fcfs\"\ncpus = 4\n\n\
+             [[jobs]]\nname = \"a\"\nbursts = [50]\n\n\
+             [[jobs]]\nname = \"b\"\nbursts = [10]\ndependencies = [\"a\"]\n",
+        )
+        .unwrap();
+        let path = path.to_string_lossy().to_string();
+        let trace = std::env::temp_dir().join("filasse_cli_dependencies.csv");
+        let trace = trace.to_string_lossy().to_string();
+        let simulate = format!("simulate {} --trace {}", path, trace);
+        let output = run(&parse(&args(&simulate)).unwrap()).unwrap();
+        assert!(output.contains("makespan        60"));
+        assert!(output.contains("mean wait       25.00"));
+        assert!(std::fs::read_to_string(&trace)
+            .unwrap()
+            .contains("50,start,2,1,"));
+        let rr = format!("simulate {} --policy rr:5 --cpus 1", path);
+        assert!(run(&parse(&args(&rr)).unwrap())
+            .unwrap()
+            .contains("makespan        60"));
+    }
+
+    #[test]
+    fn sweep() {
+        let csv = std::env::temp_dir().join("filasse_cli_sweep.csv");
+        let command = parse(&args(&format!(
+            "sweep poisson:0.05:10 --policies fcfs,rr --quanta 5,10 --seeds 1,2 --jobs 50 --csv {}",
+            csv.to_string_lossy()
+        )))
+        .unwrap();
+        let output = run(&command).unwrap();
+        assert!(output.lines().count() == 4);
+        assert!(output.lines().nth(2).unwrap().starts_with("rr:5"));
+        assert!(std::fs::read_to_string(csv).unwrap().lines().count() == 13);
+    }
+
+    #[test]
+    fn replay() {
+        let path = workload("replay");
+        let trace = std::env::temp_dir().join("filasse_cli_replay.csv");
+        let trace = trace.to_string_lossy().to_string();
+        let simulate = format!("simulate {} --cpus 4 --trace {}", path, trace);
+        run(&parse(&args(&simulate)).unwrap()).unwrap();
+        let replay = format!("replay {} --workload {} --cpus 4", trace, path);
+        let output = run(&parse(&args(&replay)).unwrap()).unwrap();
+        assert!(output.ends_with("3 decisions replayed\n"));
+        let easy = format!("{} --policy easy", replay);
+        assert!(matches!(
+            run(&parse(&args(&easy)).unwrap()),
+            Err(CliError::Divergence(_))
+        ));
+        assert!(matches!(
+            run(&parse(&args("replay /nonexistent/events.jsonl")).unwrap()),
+            Err(CliError::EventLog { .. })
+        ));
+    }
+}
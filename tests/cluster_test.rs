@@ -0,0 +1,43 @@
+#[cfg(test)]
+mod tests {
+
+    use filasse::batch::*;
+    use filasse::cluster::*;
+
+    #[test]
+    fn allocate() {
+        let mut cluster = Cluster::homogeneous(3, 8, 32);
+        let allocation = cluster.allocate(&Resources::new(2, 6, 16), None).unwrap();
+        assert!(allocation.nodes == vec![0, 1]);
+        assert!(cluster.free_cores() == 12);
+        assert!(cluster.node(0).unwrap().free_memory == 16);
+        assert!(!cluster.fits(&Resources::new(2, 4, 8), None));
+        assert!(cluster.fits(&Resources::new(3, 2, 8), None));
+        cluster.release(&allocation);
+        assert!(cluster.free_cores() == cluster.total_cores());
+    }
+
+    #[test]
+    fn memory() {
+        let mut cluster = Cluster::homogeneous(2, 8, 32);
+        assert!(cluster.allocate(&Resources::new(1, 1, 40), None).is_none());
+        assert!(!cluster.can_ever_fit(&Resources::new(1, 1, 40), None));
+        cluster.allocate(&Resources::new(2, 1, 30), None).unwrap();
+        assert!(!cluster.fits(&Resources::new(1, 1, 4), None));
+    }
+
+    #[test]
+    fn partition() {
+        let mut cluster = Cluster::new();
+        cluster.add_node(4, 16);
+        let big = cluster.add_node(64, 256);
+        cluster.add_partition("fat", vec![big]).unwrap();
+        assert!(cluster.add_partition("bad", vec![7]) == Err(BatchError::UnknownNode(7)));
+        let allocation = cluster
+            .allocate(&Resources::new(1, 4, 16), Some("fat"))
+            .unwrap();
+        assert!(allocation.nodes == vec![big]);
+        assert!(!cluster.can_ever_fit(&Resources::new(2, 1, 1), Some("fat")));
+        assert!(!cluster.fits(&Resources::new(1, 1, 1), Some("none")));
+    }
+}
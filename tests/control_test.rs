@@ -0,0 +1,159 @@
+#[cfg(test)]
+mod tests {
+
+    use filasse::array::*;
+    use filasse::dependency::*;
+    use filasse::job::*;
+    use filasse::observer::*;
+    use filasse::scheduler::*;
+    use filasse::signal::*;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn cancel() {
+        let mut sched = Scheduler::default();
+        sched.add_to_scheduler(&mut Job::new(0, 0, 5, 0));
+        sched.add_to_scheduler(&mut Job::new(0, 0, 5, 0));
+        sched.dispatch();
+        sched.suspend(2).unwrap();
+        sched.cancel(1).unwrap();
+        sched.cancel(2).unwrap();
+        assert!(sched.running().is_none());
+        assert!(sched.suspended_ready().is_empty());
+        assert!(sched.zombie().len() == 2);
+        assert!(
+            sched.cancel(1)
+                == Err(SchedulerError::IllegalState {
+                    pid: 1,
+                    state: JobState::Zombie
+                })
+        );
+        assert!(sched.cancel(9) == Err(SchedulerError::UnknownPid(9)));
+    }
+
+    #[test]
+    fn hold_and_release() {
+        let mut sched = Scheduler::default();
+        sched.add_to_scheduler(&mut Job::new(0, 0, 5, 0));
+        sched.add_to_scheduler(&mut Job::new(0, 0, 5, 0));
+        sched.hold(1).unwrap();
+        sched.hold(1).unwrap();
+        assert!(sched.dispatch() == Some(2));
+        assert!(sched.held().front().unwrap().pid == 1);
+        assert!(
+            sched.hold(2)
+                == Err(SchedulerError::IllegalState {
+                    pid: 2,
+                    state: JobState::Running
+                })
+        );
+        sched.release(1).unwrap();
+        assert!(sched.queue().front().unwrap().pid == 1);
+        assert!(
+            sched.release(1)
+                == Err(SchedulerError::IllegalState {
+                    pid: 1,
+                    state: JobState::Ready
+                })
+        );
+    }
+
+    #[derive(Default)]
+    struct Transitions(Vec<(u64, JobState, JobState)>);
+
+    impl SchedulerObserver for Transitions {
+        fn on_transition(&mut self, transition: &Transition) {
+            self.0
+                .push((transition.job.pid(), transition.old, transition.new));
+        }
+    }
+
+    #[test]
+    fn hold_kept_through_suspend_and_stop() {
+        let mut sched = Scheduler::default();
+        sched.add_to_scheduler(&mut Job::new(0, 0, 5, 0));
+        sched.add_to_scheduler(&mut Job::new(0, 0, 5, 0));
+        sched.hold(1).unwrap();
+        sched.suspend(1).unwrap();
+        sched.resume(1).unwrap();
+        assert!(sched.held().front().unwrap().pid == 1);
+        assert!(sched.queue().len() == 1);
+        sched.send_signal(1, Signal::Stop).unwrap();
+        sched.send_signal(1, Signal::Cont).unwrap();
+        assert!(sched.held().front().unwrap().pid == 1);
+        sched.suspend(1).unwrap();
+        sched.release(1).unwrap();
+        assert!(sched.held().is_empty());
+        sched.resume(1).unwrap();
+        assert!(sched.queue().back().unwrap().pid == 1);
+    }
+
+    #[test]
+    fn hold_and_release_events() {
+        let mut sched = Scheduler::default();
+        let transitions = Arc::new(Mutex::new(Transitions::default()));
+        sched.add_observer(transitions.clone());
+        sched.add_to_scheduler(&mut Job::new(0, 0, 5, 0));
+        sched.hold(1).unwrap();
+        sched.release(1).unwrap();
+        let ready = (1, JobState::Ready, JobState::Ready);
+        assert!(transitions.lock().unwrap().0 == vec![ready, ready]);
+    }
+
+    #[test]
+    fn hold_waiting() {
+        let mut sched = Scheduler::default();
+        sched.add_to_scheduler(&mut Job::new(0, 0, 5, 0));
+        sched
+            .add_with_dependencies(&mut Job::new(0, 0, 5, 0), vec![Dependency::AfterAny(1)])
+            .unwrap();
+        sched.hold(2).unwrap();
+        assert!(sched.waiting().front().unwrap().pid == 2);
+        sched.cancel(1).unwrap();
+        assert!(sched.held().front().unwrap().pid == 2);
+        assert!(sched.queue().is_empty());
+        sched.release(2).unwrap();
+        assert!(sched.queue().front().unwrap().pid == 2);
+
+        let mut sched = Scheduler::default();
+        let spec = ArraySpec::parse("0-2%1").unwrap();
+        sched.add_array(&Job::new(0, 0, 5, 0), spec).unwrap();
+        sched.hold(2).unwrap();
+        sched.hold(3).unwrap();
+        sched.release(3).unwrap();
+        sched.cancel(1).unwrap();
+        assert!(sched.held().front().unwrap().pid == 2);
+        assert!(sched.waiting().front().unwrap().pid == 3);
+        sched.cancel(2).unwrap();
+        assert!(sched.queue().front().unwrap().pid == 3);
+    }
+
+    #[test]
+    fn requeue() {
+        let mut sched = Scheduler::default();
+        sched.add_to_scheduler(&mut Job::new(0, 0, 5, 0));
+        sched.add_to_scheduler(&mut Job::new(0, 0, 5, 0));
+        sched.dispatch();
+        sched.requeue(1).unwrap();
+        assert!(sched.running().is_none());
+        assert!(sched.queue().back().unwrap().pid == 1);
+        assert!(
+            sched.requeue(2)
+                == Err(SchedulerError::IllegalState {
+                    pid: 2,
+                    state: JobState::Ready
+                })
+        );
+    }
+
+    #[test]
+    fn renice() {
+        let mut sched = Scheduler::default();
+        sched.add_to_scheduler(&mut Job::new(0, 0, 5, 0));
+        sched.renice(1, 7).unwrap();
+        assert!(sched.queue().front().unwrap().state.priority == 7);
+        sched.cancel(1).unwrap();
+        assert!(sched.renice(1, 3).is_err());
+        assert!(sched.renice(9, 3) == Err(SchedulerError::UnknownPid(9)));
+    }
+}
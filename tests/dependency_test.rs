@@ -0,0 +1,110 @@
+#[cfg(test)]
+mod tests {
+
+    use filasse::dependency::*;
+    use filasse::job::*;
+    use filasse::scheduler::*;
+
+    #[test]
+    fn after_ok() {
+        let mut sched = Scheduler::new(1);
+        sched.add_to_scheduler(&mut Job::new(0, 0, 1, 0));
+        let pid = sched
+            .add_with_dependencies(&mut Job::new(0, 0, 1, 0), vec![Dependency::AfterOk(1)])
+            .unwrap();
+        assert!(sched.state(pid) == Some(JobState::New));
+        assert!(sched.queue().len() == 1);
+        sched.process();
+        assert!(sched.state(pid) == Some(JobState::Ready));
+        assert!(sched.queue().front().unwrap().pid == pid);
+    }
+
+    #[test]
+    fn after_start() {
+        let mut sched = Scheduler::new(1);
+        sched.add_to_scheduler(&mut Job::new(0, 0, 5, 0));
+        let pid = sched
+            .add_with_dependencies(&mut Job::default(), vec![Dependency::After(1)])
+            .unwrap();
+        assert!(sched.waiting().len() == 1);
+        sched.dispatch();
+        assert!(sched.waiting().is_empty());
+        assert!(sched.state(pid) == Some(JobState::Ready));
+    }
+
+    #[test]
+    fn never_satisfied() {
+        let mut sched = Scheduler::new(1);
+        sched.add_to_scheduler(&mut Job::new(0, 0, 5, 0));
+        let ok = sched
+            .add_with_dependencies(&mut Job::default(), vec![Dependency::AfterOk(1)])
+            .unwrap();
+        let not_ok = sched
+            .add_with_dependencies(&mut Job::default(), vec![Dependency::AfterNotOk(1)])
+            .unwrap();
+        let any = sched
+            .add_with_dependencies(&mut Job::default(), vec![Dependency::AfterAny(ok)])
+            .unwrap();
+        sched.cancel(1).unwrap();
+        assert!(sched.state(ok) == Some(JobState::Zombie));
+        assert!(
+            sched.registry().get(ok).unwrap().exit == Some(ExitReason::DependencyNeverSatisfied)
+        );
+        assert!(sched.state(not_ok) == Some(JobState::Ready));
+        assert!(sched.state(any) == Some(JobState::Ready));
+    }
+
+    #[test]
+    fn singleton() {
+        let mut sched = Scheduler::new(1);
+        let group = || vec![Dependency::Singleton(String::from("nightly"))];
+        let first = sched
+            .add_with_dependencies(&mut Job::new(0, 0, 1, 0), group())
+            .unwrap();
+        let second = sched
+            .add_with_dependencies(&mut Job::new(0, 0, 1, 0), group())
+            .unwrap();
+        assert!(sched.state(first) == Some(JobState::Ready));
+        assert!(sched.state(second) == Some(JobState::New));
+        sched.process();
+        assert!(sched.state(second) == Some(JobState::Ready));
+    }
+
+    #[test]
+    fn workflow() {
+        let mut sched = Scheduler::new(1);
+        let a = sched.pid_count();
+        let pids = sched
+            .add_workflow(vec![
+                (Job::new(0, 0, 1, 0), vec![]),
+                (Job::new(0, 0, 1, 0), vec![Dependency::AfterOk(a)]),
+                (Job::new(0, 0, 1, 0), vec![Dependency::AfterOk(a)]),
+                (
+                    Job::new(0, 0, 1, 0),
+                    vec![Dependency::AfterOk(a + 1), Dependency::AfterOk(a + 2)],
+                ),
+            ])
+            .unwrap();
+        assert!(pids == vec![a, a + 1, a + 2, a + 3]);
+        sched.round_robin();
+        assert!(sched.terminated().len() == 4);
+        assert!(sched.terminated().back().unwrap().pid == a + 3);
+    }
+
+    #[test]
+    fn reject_cycle() {
+        let mut sched = Scheduler::default();
+        let a = sched.pid_count();
+        let result = sched.add_workflow(vec![
+            (Job::default(), vec![Dependency::AfterOk(a + 2)]),
+            (Job::default(), vec![Dependency::AfterOk(a)]),
+            (Job::default(), vec![Dependency::AfterAny(a + 1)]),
+        ]);
+        assert!(matches!(result, Err(SchedulerError::CyclicDependency(_))));
+        assert!(sched.pid_count() == a);
+        assert!(
+            sched.add_with_dependencies(&mut Job::default(), vec![Dependency::After(42)])
+                == Err(SchedulerError::UnknownPid(42))
+        );
+    }
+}
@@ -0,0 +1,139 @@
+#[cfg(test)]
+mod tests {
+
+    use filasse::device::*;
+    use filasse::job::*;
+    use filasse::scheduler::*;
+
+    const REQUESTS: [u64; 8] = [98, 183, 37, 122, 14, 124, 65, 67];
+
+    fn disk(policy: DiskPolicy, direction: Direction) -> Device {
+        let mut disk = Device::new(policy, 200);
+        disk.set_head(53, direction);
+        for (pid, cylinder) in REQUESTS.iter().enumerate() {
+            disk.submit(IoRequest {
+                pid: pid as u64,
+                cylinder: *cylinder,
+                time: 0,
+            });
+        }
+        disk
+    }
+
+    fn order(disk: &mut Device) -> Vec<u64> {
+        let mut order = Vec::new();
+        while let Some(done) = disk.service() {
+            order.push(done.request.cylinder);
+        }
+        order
+    }
+
+    #[test]
+    fn fcfs() {
+        let mut disk = disk(DiskPolicy::Fcfs, Direction::Up);
+        assert!(order(&mut disk) == REQUESTS.to_vec());
+        assert!(disk.total_seek() == 640);
+    }
+
+    #[test]
+    fn sstf() {
+        let mut disk = disk(DiskPolicy::Sstf, Direction::Up);
+        assert!(order(&mut disk) == vec![65, 67, 37, 14, 98, 122, 124, 183]);
+        assert!(disk.total_seek() == 236);
+    }
+
+    #[test]
+    fn scan() {
+        let mut disk = disk(DiskPolicy::Scan, Direction::Down);
+        assert!(order(&mut disk) == vec![37, 14, 65, 67, 98, 122, 124, 183]);
+        assert!(disk.total_seek() == 236);
+    }
+
+    #[test]
+    fn c_scan() {
+        let mut disk = disk(DiskPolicy::CScan, Direction::Up);
+        assert!(order(&mut disk) == vec![65, 67, 98, 122, 124, 183, 14, 37]);
+        assert!(disk.total_seek() == 382);
+    }
+
+    #[test]
+    fn look() {
+        let mut disk = disk(DiskPolicy::Look, Direction::Up);
+        assert!(order(&mut disk) == vec![65, 67, 98, 122, 124, 183, 37, 14]);
+        assert!(disk.total_seek() == 299);
+    }
+
+    #[test]
+    fn service_time() {
+        let mut disk = Device::new(DiskPolicy::Fcfs, 200);
+        disk.set_timings(2, 5);
+        disk.submit(IoRequest {
+            pid: 1,
+            cylinder: 10,
+            time: 0,
+        });
+        disk.submit(IoRequest {
+            pid: 2,
+            cylinder: 10,
+            time: 100,
+        });
+        let done = disk.service().unwrap();
+        assert!(done.seek == 10);
+        assert!(done.service_time == 25);
+        assert!(done.end == 25);
+        assert!(disk.service().unwrap().end == 105);
+        assert!(disk.busy_time() == 30);
+        assert!(disk.time() == 105);
+    }
+
+    #[test]
+    fn complete_io() {
+        let mut sched = Scheduler::default();
+        let disk = sched.add_device(Device::new(DiskPolicy::Sstf, 200));
+        sched.add_to_scheduler(&mut Job::new(1, 0, 5, 0));
+        sched.add_to_scheduler(&mut Job::new(1, 0, 5, 0));
+        assert!(sched.request_io(disk, 150) == Some(1));
+        assert!(sched.request_io(disk, 20) == Some(2));
+        assert!(sched.queue().is_empty());
+        let done = sched.complete_io(disk).unwrap();
+        assert!(done.request.pid == 2);
+        assert!(sched.queue().front().unwrap().pid == 2);
+        assert!(sched.device(disk).unwrap().pending().len() == 1);
+    }
+
+    #[test]
+    fn io_overlap() {
+        let mut sched = Scheduler::new(10);
+        let disk = sched.add_device(Device::new(DiskPolicy::Fcfs, 200));
+        sched.add_to_scheduler(&mut Job::new(1, 0, 5, 0));
+        sched.add_to_scheduler(&mut Job::new(1, 0, 100, 0));
+        assert!(sched.request_io(disk, 150) == Some(1));
+        // The second job runs while the disk services the first one until 150.
+        while sched.clock() < 100 {
+            sched.process();
+        }
+        assert!(sched.complete_io(disk).unwrap().end == 150);
+        assert!(sched.clock() == 150);
+        sched.process();
+        assert!(sched.registry().get(1).unwrap().exit == Some(ExitReason::Completed));
+        // Its turnaround includes the 150 of service time.
+        assert!(sched.clock() == 155);
+        assert!(sched.metrics().makespan == 155);
+    }
+
+    #[test]
+    fn request_io_blocks_running_job() {
+        let mut sched = Scheduler::default();
+        let disk = sched.add_device(Device::default());
+        sched.add_to_scheduler(&mut Job::new(1, 0, 5, 0));
+        sched.add_to_scheduler(&mut Job::new(1, 0, 5, 0));
+        assert!(sched.dispatch() == Some(1));
+        assert!(sched.request_io(disk, 98) == Some(1));
+        assert!(sched.running().is_none());
+        assert!(sched.blocked().front().unwrap().pid == 1);
+        assert!(sched.queue().front().unwrap().pid == 2);
+        assert!(sched.request_io(disk, 10) == Some(2));
+        assert!(sched.request_io(disk, 10).is_none());
+        assert!(sched.device(disk).unwrap().pending().len() == 2);
+    }
+}
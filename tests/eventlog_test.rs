@@ -0,0 +1,48 @@
+#[cfg(test)]
+mod tests {
+
+    use filasse::eventlog::*;
+    use filasse::job::*;
+    use filasse::observer::*;
+    use filasse::scheduler::*;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn write_and_read() {
+        let path = std::env::temp_dir().join("filasse_events.jsonl");
+        let path = path.to_string_lossy().to_string();
+        let log = Arc::new(Mutex::new(EventLog::create(&path).unwrap()));
+        let mut sched = Scheduler::new(10);
+        sched.add_observer(log.clone());
+        sched.add_to_scheduler(&mut Job::new(0, 0, 15, 0));
+        sched.add_to_scheduler(&mut Job::new(0, 0, 5, 0));
+        sched.round_robin();
+        log.lock().unwrap().flush().unwrap();
+        let records = read(&path).unwrap();
+        assert!(records.len() == 10);
+        assert!(records[2].event == Event::Dispatch);
+        assert!(records[2].cpu == Some(0));
+        assert!(records[2].queues.ready == 1);
+        assert!(records[3].event == Event::QuantumExpiry);
+        assert!(records[3].time == 10 && records[3].state == JobState::Ready);
+        assert!(records[3].cpu.is_none());
+        assert!(records[5].exit == Some(ExitReason::Completed));
+        assert!(records[5].queues.zombie == 1);
+        assert!(records.last().unwrap().state == JobState::Terminated);
+        let text = std::fs::read_to_string(&path).unwrap();
+        assert!(text.lines().all(|line| line.starts_with("{\"time\":")));
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(parse("\n").unwrap().is_empty());
+        assert!(matches!(
+            parse("{\"time\":0}\n"),
+            Err(EventLogError::Parse { line: 1, .. })
+        ));
+        assert!(matches!(
+            read("/nonexistent/events.jsonl"),
+            Err(EventLogError::Io(_))
+        ));
+    }
+}
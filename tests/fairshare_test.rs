@@ -0,0 +1,125 @@
+#[cfg(test)]
+mod tests {
+
+    use filasse::batch::*;
+    use filasse::cluster::*;
+    use filasse::fairshare::*;
+    use filasse::job::*;
+    use filasse::priority::*;
+    use filasse::scheduler::*;
+
+    fn tree(half_life: u64) -> FairShare {
+        let mut tree = FairShare::new(half_life);
+        tree.add_account("physics", ROOT_ACCOUNT, 2).unwrap();
+        tree.add_account("biology", ROOT_ACCOUNT, 1).unwrap();
+        tree.add_user("alice", "physics", 1).unwrap();
+        tree.add_user("bob", "physics", 1).unwrap();
+        tree.add_user("carol", "biology", 1).unwrap();
+        tree
+    }
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    #[test]
+    fn shares() {
+        let tree = tree(0);
+        assert!(close(tree.normalized_share("physics").unwrap(), 2.0 / 3.0));
+        assert!(close(tree.normalized_share("alice").unwrap(), 1.0 / 3.0));
+        assert!(close(tree.normalized_share("carol").unwrap(), 1.0 / 3.0));
+        assert!(tree.factor("alice") == Some(1.0));
+        assert!(tree.account("bob") == Some("physics"));
+        assert!(tree.factor("dave").is_none());
+    }
+
+    #[test]
+    fn factors() {
+        let mut tree = tree(0);
+        assert!(tree.charge("alice", 100.0));
+        assert!(!tree.charge("physics", 100.0));
+        assert!(tree.get("physics").unwrap().usage == 100.0);
+        assert!(close(tree.factor("alice").unwrap(), 0.125));
+        assert!(close(tree.effective_usage("bob").unwrap(), 0.5));
+        assert!(close(tree.factor("bob").unwrap(), 2f64.powf(-1.5)));
+        assert!(close(tree.factor("carol").unwrap(), 0.5));
+        assert!(tree.factors().len() == 3);
+    }
+
+    #[test]
+    fn decay() {
+        let mut tree = tree(10);
+        tree.charge("alice", 100.0);
+        tree.decay(10);
+        assert!(close(tree.get("alice").unwrap().usage, 50.0));
+        tree.decay(30);
+        assert!(close(tree.get(ROOT_ACCOUNT).unwrap().usage, 12.5));
+    }
+
+    #[test]
+    fn errors() {
+        let mut tree = tree(0);
+        assert!(
+            tree.add_user("alice", "biology", 1)
+                == Err(FairShareError::Duplicate("alice".to_string()))
+        );
+        assert!(
+            tree.add_account("chemistry", "nowhere", 1)
+                == Err(FairShareError::UnknownAccount("nowhere".to_string()))
+        );
+        assert!(tree.add_user("eve", "alice", 1).is_err());
+    }
+
+    #[test]
+    fn dispatch_order() {
+        let mut sched = Scheduler::new(10);
+        for _ in 0..3 {
+            sched.add_to_scheduler(&mut Job::new(0, 0, 30, 0));
+        }
+        sched.set_owner(1, "alice").unwrap();
+        sched.set_owner(2, "alice").unwrap();
+        sched.set_owner(3, "carol").unwrap();
+        sched.set_fairshare(Some(tree(0)));
+        sched.process();
+        assert!(sched.fairshare().unwrap().get("alice").unwrap().usage == 10.0);
+        assert!(sched.dispatch() == Some(3));
+    }
+
+    #[test]
+    fn fork_owner() {
+        let mut sched = Scheduler::default();
+        sched.add_to_scheduler(&mut Job::default());
+        sched.set_owner(1, "bob").unwrap();
+        let parent = sched.dispatch().unwrap();
+        let child = sched.fork(parent, &mut Job::default()).unwrap();
+        assert!(sched.owner(child) == Some("bob"));
+        assert!(sched.set_owner(42, "bob") == Err(SchedulerError::UnknownPid(42)));
+    }
+
+    #[test]
+    fn batch_usage() {
+        let mut batch = BatchScheduler::new(Cluster::homogeneous(1, 2, 1));
+        batch.set_fairshare(Some(tree(0)));
+        batch.set_priority(Some(Multifactor::new(Weights {
+            age: 0,
+            size: 0,
+            fairshare: 1000,
+            qos: 0,
+            partition: 0,
+        })));
+        batch
+            .submit(BatchJob::new(1, 2, 1, 100).user("alice"))
+            .unwrap();
+        let later = batch
+            .submit(BatchJob::new(1, 2, 1, 10).user("alice"))
+            .unwrap();
+        let other = batch
+            .submit(BatchJob::new(1, 2, 1, 10).user("carol"))
+            .unwrap();
+        batch.run();
+        let start = |id| batch.finished().iter().find(|r| r.id == id).unwrap().start;
+        assert!(start(other) == 100);
+        assert!(start(later) == 110);
+        assert!(batch.fairshare().unwrap().get("alice").unwrap().usage == 220.0);
+    }
+}
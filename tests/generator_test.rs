@@ -0,0 +1,104 @@
+#[cfg(test)]
+mod tests {
+
+    use filasse::generator::*;
+
+    fn generator() -> Generator {
+        Generator::new(
+            Arrival::Poisson { rate: 0.5 },
+            Runtime::Exponential { mean: 20.0 },
+        )
+    }
+
+    #[test]
+    fn reproducible() {
+        let a: Vec<_> = generator().stream(1).unwrap().take(100).collect();
+        let b: Vec<_> = generator().stream(1).unwrap().take(100).collect();
+        let c: Vec<_> = generator().stream(2).unwrap().take(100).collect();
+        assert!(a == b);
+        assert!(a != c);
+        assert!(a.windows(2).all(|w| w[0].0 <= w[1].0));
+        assert!(a.iter().all(|(_, job)| job.state.duration >= 1));
+        assert!(Lublin::new(64).batch_jobs(3, 50) == Lublin::new(64).batch_jobs(3, 50));
+    }
+
+    #[test]
+    fn means() {
+        let jobs: Vec<_> = generator().stream(7).unwrap().take(10_000).collect();
+        let last = jobs.last().unwrap().0 as f64;
+        assert!((last / 10_000.0 - 2.0).abs() < 0.1);
+        let mean = jobs.iter().map(|(_, job)| job.state.duration).sum::<u64>() as f64 / 10_000.0;
+        assert!((mean - 20.0).abs() < 1.0);
+        let bimodal = Generator::new(
+            Arrival::Weibull {
+                shape: 0.5,
+                scale: 1.0,
+            },
+            Runtime::Bimodal {
+                p: 0.5,
+                short: 10.0,
+                long: 1000.0,
+                sigma: 0.0,
+            },
+        );
+        assert!(bimodal
+            .stream(7)
+            .unwrap()
+            .take(100)
+            .all(|(_, job)| job.state.duration == 10 || job.state.duration == 1000));
+    }
+
+    #[test]
+    fn on_off() {
+        let generator = Generator::new(
+            Arrival::OnOff {
+                rate: 1.0,
+                on: 10.0,
+                off: 1000.0,
+            },
+            Runtime::LogNormal {
+                mu: 2.0,
+                sigma: 1.0,
+            },
+        )
+        .priorities(3);
+        let jobs = generator.batch_jobs(5, 1000).unwrap();
+        let gaps: Vec<u64> = jobs.windows(2).map(|w| w[1].submit - w[0].submit).collect();
+        assert!(gaps.iter().filter(|&&gap| gap > 100).count() > 10);
+        assert!(gaps.iter().filter(|&&gap| gap <= 2).count() > 500);
+        assert!(generator
+            .stream(5)
+            .unwrap()
+            .take(100)
+            .all(|(_, job)| job.state.priority <= 3));
+    }
+
+    #[test]
+    fn lublin() {
+        let jobs = Lublin::new(128).batch_jobs(11, 2000).unwrap();
+        assert!(jobs.len() == 2000);
+        assert!(jobs
+            .iter()
+            .all(|job| (1..=128).contains(&job.resources.nodes)));
+        let serial = jobs.iter().filter(|job| job.resources.nodes == 1).count();
+        assert!(serial > 400 && serial < 700);
+        assert!(jobs.iter().all(|job| job.walltime == job.runtime));
+        let busy = Lublin::new(128).load(0.5).batch_jobs(11, 2000).unwrap();
+        assert!(busy.last().unwrap().submit < jobs.last().unwrap().submit);
+    }
+
+    #[test]
+    fn invalid() {
+        let mut generator = generator();
+        generator.arrival = Arrival::Poisson { rate: 0.0 };
+        assert!(generator.stream(1).is_err());
+        generator.arrival = Arrival::Poisson { rate: 1.0 };
+        generator.runtime = Runtime::HyperExponential {
+            p: 1.5,
+            short: 1.0,
+            long: 2.0,
+        };
+        assert!(generator.validate() == Err(GeneratorError::InvalidParameter("p")));
+        assert!(Lublin::new(0).batch_jobs(1, 1) == Err(GeneratorError::InvalidParameter("nodes")));
+    }
+}
@@ -0,0 +1,74 @@
+#[cfg(test)]
+mod tests {
+
+    use filasse::harness::*;
+    use filasse::policy::*;
+
+    fn matrix() -> Matrix {
+        Matrix::new("poisson:0.05:15".parse().unwrap(), 100)
+            .policies(vec![Policy::Fcfs, Policy::RoundRobin { quantum: 100 }])
+            .quanta(vec![5, 20])
+            .cpus(vec![1, 2])
+            .seeds(vec![1, 2, 3])
+    }
+
+    #[test]
+    fn sources() {
+        assert!("lublin".parse::<Source>() == Ok(Source::Lublin { load: 1.0 }));
+        assert!("lublin:0.5".parse::<Source>() == Ok(Source::Lublin { load: 0.5 }));
+        assert!(matches!(
+            "poisson:0.1:20".parse::<Source>(),
+            Ok(Source::Generator(_))
+        ));
+        assert!(
+            "weibull".parse::<Source>() == Err(HarnessError::InvalidSource("weibull".to_string()))
+        );
+        assert!(matches!(
+            "lublin:-1".parse::<Source>(),
+            Err(HarnessError::Generator(_))
+        ));
+        let jobs = Source::Lublin { load: 1.0 }.jobs(4, 50, 16).unwrap();
+        assert!(jobs.iter().all(|job| job.resources.nodes <= 16));
+    }
+
+    #[test]
+    fn cells() {
+        assert!(
+            matrix().cells()
+                == vec![
+                    (Policy::Fcfs, 1),
+                    (Policy::Fcfs, 2),
+                    (Policy::RoundRobin { quantum: 5 }, 1),
+                    (Policy::RoundRobin { quantum: 20 }, 1),
+                ]
+        );
+    }
+
+    #[test]
+    fn run() {
+        let comparison = matrix().threads(3).run().unwrap();
+        assert!(comparison.runs.len() == 12);
+        assert!(comparison.runs[4].policy == Policy::Fcfs && comparison.runs[4].cpus == 2);
+        assert!(comparison.runs[4].seed == 2);
+        assert!(comparison == matrix().threads(1).run().unwrap());
+        let summaries = comparison.summaries();
+        assert!(summaries.len() == 4 * METRICS.len());
+        let wait = summaries
+            .iter()
+            .find(|s| s.policy == Policy::Fcfs && s.cpus == 1 && s.metric == "mean_wait")
+            .unwrap();
+        assert!(wait.runs == 3);
+        assert!(wait.low() <= wait.mean && wait.mean <= wait.high());
+        let values: Vec<f64> = comparison.runs[..3]
+            .iter()
+            .map(|run| run.metrics.mean_wait)
+            .collect();
+        assert!((wait.mean - values.iter().sum::<f64>() / 3.0).abs() < 1e-9);
+        assert!(comparison.csv().lines().count() == 1 + 12 * METRICS.len());
+        assert!(comparison.csv().contains("\nrr,5,1,1,makespan,"));
+        assert!(comparison.summary_csv().lines().count() == 1 + 4 * METRICS.len());
+        assert!(comparison
+            .summary_csv()
+            .starts_with("policy,quantum,cpus,metric,runs,mean,sd,low,high\nfcfs,,1,makespan,3,"));
+    }
+}
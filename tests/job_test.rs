@@ -127,4 +127,78 @@ mod tests {
         let bar: Job<Terminated> = bar.into();
         assert!(bar.state == Terminated {});
     }
+
+    #[test]
+    fn from_running_to_stopped() {
+        let bar = Job {
+            pid: 1,
+            parent: 0,
+            state: Running {
+                duration: 2,
+                priority: 2,
+            },
+        };
+        let bar: Job<Stopped> = bar.into();
+        assert!(
+            bar.state
+                == Stopped {
+                    duration: 2,
+                    priority: 2
+                }
+        );
+    }
+
+    #[test]
+    fn from_stopped_to_ready() {
+        let bar = Job {
+            pid: 1,
+            parent: 0,
+            state: Stopped {
+                duration: 2,
+                priority: 2,
+            },
+        };
+        let bar: Job<Ready> = bar.into();
+        assert!(
+            bar.state
+                == Ready {
+                    duration: 2,
+                    priority: 2
+                }
+        );
+    }
+
+    #[test]
+    fn from_ready_to_zombie() {
+        let bar = Job {
+            pid: 1,
+            parent: 0,
+            state: Ready {
+                duration: 2,
+                priority: 2,
+            },
+        };
+        let bar: Job<Zombie> = bar.into();
+        assert!(bar.state == Zombie {});
+    }
+
+    #[test]
+    fn from_suspended_blocked_to_suspended_ready() {
+        let bar = Job {
+            pid: 1,
+            parent: 0,
+            state: SuspendedBlocked {
+                duration: 2,
+                priority: 2,
+            },
+        };
+        let bar: Job<SuspendedReady> = bar.into();
+        assert!(
+            bar.state
+                == SuspendedReady {
+                    duration: 2,
+                    priority: 2
+                }
+        );
+    }
 }
@@ -0,0 +1,97 @@
+#[cfg(test)]
+mod tests {
+
+    use filasse::batch::*;
+    use filasse::cluster::*;
+    use filasse::job::*;
+    use filasse::scheduler::*;
+    use filasse::signal::*;
+
+    #[test]
+    fn timeout() {
+        let mut sched = Scheduler::new(10);
+        sched.add_to_scheduler(&mut Job::new(0, 0, 100, 0));
+        sched.set_time_limit(1, 30).unwrap();
+        sched.set_grace(0);
+        sched.process();
+        sched.process();
+        assert!(sched.state(1) == Some(JobState::Ready));
+        sched.process();
+        assert!(sched.clock() == 30);
+        assert!(sched.state(1) == Some(JobState::Zombie));
+        assert!(sched.registry().get(1).unwrap().exit == Some(ExitReason::Timeout));
+        assert!(sched.metrics().timeouts() == 1);
+        assert!(sched.time_limit(1).is_none());
+    }
+
+    #[test]
+    fn grace() {
+        let mut sched = Scheduler::new(10);
+        sched.add_to_scheduler(&mut Job::new(0, 0, 100, 0));
+        sched.set_time_limit(1, 20).unwrap();
+        sched.set_grace(20);
+        sched
+            .set_signal_handler(1, Signal::Term, Disposition::Catch)
+            .unwrap();
+        sched.process();
+        sched.process();
+        assert!(sched.signal_state(1).unwrap().caught == vec![Signal::Term]);
+        assert!(sched.time_limit(1).unwrap().term == Some(20));
+        sched.process();
+        assert!(sched.state(1) == Some(JobState::Ready));
+        sched.process();
+        assert!(sched.state(1) == Some(JobState::Zombie));
+        assert!(sched.metrics().timeouts() == 1);
+    }
+
+    #[test]
+    fn within_limit() {
+        let mut sched = Scheduler::new(10);
+        sched.add_to_scheduler(&mut Job::new(0, 0, 15, 0));
+        assert!(sched.set_time_limit(7, 20) == Err(SchedulerError::UnknownPid(7)));
+        sched.set_time_limit(1, 20).unwrap();
+        sched.process();
+        sched.process();
+        assert!(sched.registry().get(1).unwrap().exit == Some(ExitReason::Completed));
+        assert!(sched.metrics().timeouts() == 0);
+    }
+
+    #[test]
+    fn batch_timeout() {
+        let mut batch = BatchScheduler::new(Cluster::homogeneous(1, 4, 8));
+        batch.set_grace(5);
+        let late = batch
+            .submit(BatchJob::new(1, 4, 8, 10).runtime(50))
+            .unwrap();
+        let quick = batch
+            .submit(BatchJob::new(1, 4, 8, 10).runtime(12))
+            .unwrap();
+        assert!(batch.run() == 27);
+        let record = |id| batch.finished().iter().find(|r| r.id == id).unwrap();
+        assert!(record(late).end == 15);
+        assert!(record(late).exit == ExitReason::Timeout);
+        // Ends during the grace period, still past its walltime.
+        assert!(record(quick).end == 27);
+        assert!(record(quick).exit == ExitReason::Timeout);
+        let metrics = batch.metrics();
+        assert!(metrics.timeouts() == 2 && batch.timeouts() == 2);
+        assert!(metrics.count(ExitReason::Completed) == 0);
+    }
+
+    #[test]
+    fn batch_metrics() {
+        let mut batch = BatchScheduler::new(Cluster::homogeneous(1, 4, 8));
+        batch.submit(BatchJob::new(1, 4, 8, 10)).unwrap();
+        batch
+            .submit(BatchJob::new(1, 2, 8, 10).runtime(30))
+            .unwrap();
+        batch.run();
+        let metrics = batch.metrics();
+        assert!(metrics.jobs == 2);
+        assert!(metrics.count(ExitReason::Completed) == 1);
+        assert!(metrics.timeouts() == 1);
+        assert!(metrics.makespan == 40);
+        assert!((metrics.mean_wait - 5.0).abs() < 1e-9);
+        assert!((metrics.mean_turnaround - 25.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,94 @@
+#[cfg(test)]
+mod tests {
+
+    use filasse::job::*;
+    use filasse::observer::*;
+    use filasse::scheduler::*;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Default)]
+    struct Recorder(Vec<(Event, u64, JobState, JobState, u64)>);
+
+    impl SchedulerObserver for Recorder {
+        fn on_event(&mut self, event: Event, t: &Transition) {
+            self.0.push((event, t.job.pid(), t.old, t.new, t.time));
+        }
+    }
+
+    #[derive(Default)]
+    struct Exits(Vec<(u64, Option<ExitReason>)>);
+
+    impl SchedulerObserver for Exits {
+        fn on_exit(&mut self, t: &Transition) {
+            self.0.push((t.job.pid(), t.exit));
+        }
+    }
+
+    #[test]
+    fn transitions() {
+        let recorder = Arc::new(Mutex::new(Recorder::default()));
+        let exits = Arc::new(Mutex::new(Exits::default()));
+        let mut sched = Scheduler::new(10);
+        sched.add_observer(recorder.clone());
+        sched.add_observer(exits.clone());
+        sched.add_to_scheduler(&mut Job::new(0, 0, 15, 0));
+        sched.add_to_scheduler(&mut Job::new(0, 0, 5, 0));
+        while sched.dispatch().is_some() {
+            sched.process();
+            sched.reap_init();
+        }
+        use Event::*;
+        use JobState::*;
+        assert!(
+            recorder.lock().unwrap().0
+                == vec![
+                    (Submit, 1, New, Ready, 0),
+                    (Submit, 2, New, Ready, 0),
+                    (Dispatch, 1, Ready, Running, 0),
+                    (QuantumExpiry, 1, Running, Ready, 10),
+                    (Dispatch, 2, Ready, Running, 10),
+                    (Exit, 2, Running, Zombie, 15),
+                    (Reap, 2, Zombie, Terminated, 15),
+                    (Dispatch, 1, Ready, Running, 15),
+                    (Exit, 1, Running, Zombie, 20),
+                    (Reap, 1, Zombie, Terminated, 20),
+                ]
+        );
+        assert!(
+            exits.lock().unwrap().0
+                == vec![
+                    (2, Some(ExitReason::Completed)),
+                    (1, Some(ExitReason::Completed))
+                ]
+        );
+    }
+
+    #[test]
+    fn preempt_and_remove() {
+        let recorder = Arc::new(Mutex::new(Recorder::default()));
+        let mut sched = Scheduler::new(10);
+        let id = sched.add_observer(recorder.clone());
+        sched.add_to_scheduler(&mut Job::new(0, 0, 15, 0));
+        sched.dispatch();
+        sched.requeue(1).unwrap();
+        sched.suspend(1).unwrap();
+        sched.resume(1).unwrap();
+        sched.cancel(1).unwrap();
+        let events: Vec<Event> = recorder.lock().unwrap().0.iter().map(|e| e.0).collect();
+        assert!(
+            events
+                == vec![
+                    Event::Submit,
+                    Event::Dispatch,
+                    Event::Preempt,
+                    Event::Other,
+                    Event::Other,
+                    Event::Exit
+                ]
+        );
+        assert!(sched.remove_observer(id).is_some());
+        assert!(sched.remove_observer(id).is_none());
+        sched.reap_init();
+        assert!(recorder.lock().unwrap().0.len() == 6);
+    }
+}
@@ -0,0 +1,117 @@
+#[cfg(test)]
+mod tests {
+
+    use filasse::batch::*;
+    use filasse::job::*;
+    use filasse::policy::*;
+    use filasse::report::*;
+
+    fn workload() -> Vec<BatchJob> {
+        vec![
+            BatchJob::new(2, 1, 0, 100),
+            BatchJob::new(4, 1, 0, 50).submit(10),
+            BatchJob::new(1, 1, 0, 20).runtime(10).submit(20),
+        ]
+    }
+
+    #[test]
+    fn names() {
+        assert!("easy".parse::<Policy>() == Ok(Policy::Easy));
+        assert!(
+            "RR".parse::<Policy>()
+                == Ok(Policy::RoundRobin {
+                    quantum: DEFAULT_QUANTUM
+                })
+        );
+        assert!("rr:7".parse::<Policy>() == Ok(Policy::RoundRobin { quantum: 7 }));
+        assert!(Policy::RoundRobin { quantum: 7 }.to_string() == "rr:7");
+        assert!("rr:0".parse::<Policy>() == Err(PolicyError::InvalidParameter("0".to_string())));
+        assert!("fcfs:3".parse::<Policy>().is_err());
+        assert!("sjf".parse::<Policy>() == Err(PolicyError::UnknownPolicy("sjf".to_string())));
+    }
+
+    #[test]
+    fn batch() {
+        let fcfs = Policy::Fcfs.simulate(&workload(), 4).unwrap();
+        let easy = Policy::Easy.simulate(&workload(), 4).unwrap();
+        assert!(fcfs.metrics.makespan == 160);
+        assert!(easy.metrics.makespan == 150);
+        let record = easy.records.iter().find(|r| r.id == 3).unwrap();
+        assert!(record.start == 20 && record.end == 30);
+        assert!(matches!(
+            Policy::Fcfs.simulate(&workload(), 2),
+            Err(PolicyError::Batch(BatchError::Unsatisfiable(_)))
+        ));
+    }
+
+    #[test]
+    fn round_robin() {
+        let jobs = vec![
+            BatchJob::new(1, 1, 0, 30),
+            BatchJob::new(1, 1, 0, 10).submit(5),
+            BatchJob::new(1, 1, 0, 10).submit(100),
+        ];
+        let simulation = Policy::RoundRobin { quantum: 10 }
+            .simulate(&jobs, 1)
+            .unwrap();
+        let end = |id: u64| simulation.records.iter().find(|r| r.id == id).unwrap().end;
+        assert!(end(1) == 40 && end(2) == 30 && end(3) == 110);
+        assert!(simulation.metrics.count(ExitReason::Completed) == 3);
+        assert!(
+            Policy::RoundRobin { quantum: 10 }.simulate(&jobs, 2)
+                == Err(PolicyError::Cpus {
+                    policy: Policy::RoundRobin { quantum: 10 },
+                    cpus: 2
+                })
+        );
+    }
+
+    #[test]
+    fn dependencies() {
+        let jobs = vec![
+            BatchJob::new(1, 1, 0, 10).after(2),
+            BatchJob::new(1, 1, 0, 20).submit(5),
+        ];
+        let cyclic = vec![
+            BatchJob::new(1, 1, 0, 10).after(2),
+            BatchJob::new(1, 1, 0, 10).after(1),
+        ];
+        let outside = vec![BatchJob::new(1, 1, 0, 10).after(2)];
+        for policy in [Policy::Fcfs, Policy::RoundRobin { quantum: 10 }] {
+            let simulation = policy.simulate(&jobs, 1).unwrap();
+            let record = |id: u64| simulation.records.iter().find(|r| r.id == id).unwrap();
+            assert!(record(1).start == 25 && record(1).end == 35);
+            assert!(simulation.metrics.count(ExitReason::Completed) == 2);
+            assert!(
+                policy.simulate(&cyclic, 1)
+                    == Err(PolicyError::Batch(BatchError::CyclicDependency(1)))
+            );
+            assert!(
+                policy.simulate(&outside, 1)
+                    == Err(PolicyError::Batch(BatchError::InvalidDependency(2)))
+            );
+        }
+        let failed = vec![
+            BatchJob::new(1, 1, 0, 10).runtime(20),
+            BatchJob::new(1, 1, 0, 10).after(1),
+        ];
+        let simulation = Policy::Fcfs.simulate(&failed, 1).unwrap();
+        assert!(simulation.records[1].exit == ExitReason::DependencyNeverSatisfied);
+    }
+
+    #[test]
+    fn reports() {
+        let simulation = Policy::Fcfs.simulate(&workload(), 4).unwrap();
+        let chart = gantt(&simulation.records, 16);
+        let lines: Vec<&str> = chart.lines().collect();
+        assert!(lines[0] == "1 |##########......| 0-100");
+        assert!(lines[1] == "2 |..........#####.| 100-150");
+        let events = trace(&simulation.records);
+        assert!(events.lines().count() == 7);
+        assert!(events.lines().nth(1).unwrap() == "0,start,1,2,");
+        assert!(simulation
+            .metrics
+            .to_string()
+            .contains("makespan        160"));
+    }
+}
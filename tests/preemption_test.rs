@@ -0,0 +1,204 @@
+#[cfg(test)]
+mod tests {
+
+    use filasse::batch::*;
+    use filasse::cluster::*;
+    use filasse::job::*;
+    use filasse::preemption::*;
+    use filasse::swf;
+
+    fn scheduler(mode: PreemptMode) -> BatchScheduler {
+        let mut batch = BatchScheduler::new(Cluster::homogeneous(1, 4, 8));
+        let mut policy = Preemption::new();
+        policy.set_qos("high", 1, PreemptMode::Off);
+        policy.set_qos("low", 0, mode);
+        batch.set_preemption(Some(policy));
+        batch
+    }
+
+    fn record(batch: &BatchScheduler, id: u64) -> Record {
+        batch
+            .finished()
+            .iter()
+            .find(|r| r.id == id)
+            .unwrap()
+            .clone()
+    }
+
+    #[test]
+    fn suspend() {
+        let mut batch = scheduler(PreemptMode::Suspend);
+        batch
+            .submit(BatchJob::new(1, 4, 4, 100).qos("low"))
+            .unwrap();
+        batch
+            .submit(BatchJob::new(1, 4, 4, 10).submit(50).qos("high"))
+            .unwrap();
+        batch.schedule();
+        batch.step();
+        assert!(batch.time() == 50);
+        assert!(batch.state(1) == Some(JobState::Stopped));
+        assert!(batch.state(2) == Some(JobState::Running));
+        assert!(batch.cluster().node(0).unwrap().free_memory == 0);
+        batch.run();
+        assert!(record(&batch, 2).end == 60);
+        let low = record(&batch, 1);
+        assert!(low.start == 0 && low.end == 110);
+        assert!(low.suspended == 10 && low.run_time() == 100);
+        assert!(low.exit == ExitReason::Completed);
+        let metrics = batch.metrics();
+        assert!(metrics.preemptions == 1);
+        assert!(metrics.utilization <= 1.0);
+        assert!((metrics.utilization - 1.0).abs() < 1e-9);
+        let line = swf::write(batch.finished())
+            .lines()
+            .nth(1)
+            .unwrap()
+            .to_string();
+        assert!(line.split_whitespace().nth(3) == Some("100"));
+        assert!(
+            batch.preempted()
+                == [Preempted {
+                    id: 1,
+                    by: 2,
+                    time: 50,
+                    mode: PreemptMode::Suspend,
+                    lost: 0
+                }]
+        );
+    }
+
+    #[test]
+    fn suspend_needs_memory() {
+        let mut batch = scheduler(PreemptMode::Suspend);
+        batch
+            .submit(BatchJob::new(1, 4, 8, 100).qos("low"))
+            .unwrap();
+        batch
+            .submit(BatchJob::new(1, 4, 4, 10).submit(50).qos("high"))
+            .unwrap();
+        batch.run();
+        assert!(batch.preempted().is_empty());
+        assert!(batch.metrics().preemptions == 0);
+        assert!(record(&batch, 2).start == 100);
+        assert!(record(&batch, 1).suspended == 0);
+    }
+
+    #[test]
+    fn requeue() {
+        let mut batch = scheduler(PreemptMode::Requeue);
+        let mut policy = batch.preemption().unwrap().clone();
+        policy.checkpoint = 20;
+        batch.set_preemption(Some(policy));
+        batch
+            .submit(BatchJob::new(1, 4, 4, 100).qos("low"))
+            .unwrap();
+        batch
+            .submit(BatchJob::new(1, 4, 4, 10).submit(50).qos("high"))
+            .unwrap();
+        batch.schedule();
+        batch.step();
+        assert!(batch.state(1) == Some(JobState::Ready));
+        batch.run();
+        let low = record(&batch, 1);
+        assert!(low.start == 60 && low.end == 120);
+        let metrics = batch.metrics();
+        assert!(metrics.preemptions == 1);
+        assert!(metrics.lost_work == 40);
+    }
+
+    #[test]
+    fn cancel() {
+        let mut batch = scheduler(PreemptMode::Cancel);
+        batch
+            .submit(BatchJob::new(1, 4, 4, 100).qos("low"))
+            .unwrap();
+        batch
+            .submit(BatchJob::new(1, 4, 4, 10).submit(50).qos("high"))
+            .unwrap();
+        batch.run();
+        let low = record(&batch, 1);
+        assert!(low.end == 50 && low.exit == ExitReason::Cancelled);
+        assert!(batch.metrics().lost_work == 200);
+        assert!(batch.makespan() == 60);
+    }
+
+    #[test]
+    fn tiers() {
+        let mut batch = scheduler(PreemptMode::Off);
+        batch
+            .submit(BatchJob::new(1, 4, 4, 100).qos("low"))
+            .unwrap();
+        batch
+            .submit(BatchJob::new(1, 4, 4, 10).submit(50).qos("high"))
+            .unwrap();
+        batch.submit(BatchJob::new(1, 4, 4, 10).submit(60)).unwrap();
+        batch.run();
+        assert!(batch.preempted().is_empty());
+        assert!(record(&batch, 2).start == 100);
+        let mut batch = scheduler(PreemptMode::Cancel);
+        batch
+            .submit(BatchJob::new(1, 4, 4, 100).qos("low"))
+            .unwrap();
+        batch
+            .submit(BatchJob::new(1, 4, 4, 10).submit(50).qos("low"))
+            .unwrap();
+        batch.run();
+        assert!(batch.preempted().is_empty());
+    }
+
+    #[test]
+    fn head_not_delayed() {
+        let mut batch = scheduler(PreemptMode::Suspend);
+        batch
+            .submit(BatchJob::new(1, 2, 2, 100).qos("low"))
+            .unwrap();
+        let head = batch.submit(BatchJob::new(1, 4, 4, 10)).unwrap();
+        // Fits on the free cores without preempting, but would delay the head until 200.
+        let high = batch
+            .submit(BatchJob::new(1, 2, 2, 200).qos("high"))
+            .unwrap();
+        batch.run();
+        assert!(batch.preempted().is_empty());
+        assert!(record(&batch, head).start == 100);
+        assert!(record(&batch, high).start == 110);
+        // A job preempting a lower tier may delay the head.
+        let mut batch = scheduler(PreemptMode::Suspend);
+        batch
+            .submit(BatchJob::new(1, 4, 4, 100).qos("low"))
+            .unwrap();
+        let head = batch.submit(BatchJob::new(1, 4, 4, 10)).unwrap();
+        let high = batch
+            .submit(BatchJob::new(1, 4, 4, 50).submit(10).qos("high"))
+            .unwrap();
+        batch.run();
+        assert!(record(&batch, high).start == 10);
+        assert!(record(&batch, head).start == 150);
+    }
+
+    fn oldest(execution: &Execution, now: u64) -> f64 {
+        -(execution.run_time(now) as f64)
+    }
+
+    #[test]
+    fn cost() {
+        for (cost, victim) in [(Cost::Youngest, 2), (Cost::Custom(oldest), 1)] {
+            let mut batch = scheduler(PreemptMode::Requeue);
+            let mut policy = batch.preemption().unwrap().clone();
+            policy.cost = cost;
+            batch.set_preemption(Some(policy));
+            batch
+                .submit(BatchJob::new(1, 2, 2, 100).qos("low"))
+                .unwrap();
+            batch
+                .submit(BatchJob::new(1, 2, 2, 100).submit(20).qos("low"))
+                .unwrap();
+            batch
+                .submit(BatchJob::new(1, 2, 2, 10).submit(50).qos("high"))
+                .unwrap();
+            batch.run();
+            assert!(batch.preempted().len() == 1);
+            assert!(batch.preempted()[0].id == victim);
+        }
+    }
+}
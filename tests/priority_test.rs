@@ -0,0 +1,115 @@
+#[cfg(test)]
+mod tests {
+
+    use filasse::batch::*;
+    use filasse::cluster::*;
+    use filasse::priority::*;
+
+    fn start(batch: &BatchScheduler, id: u64) -> u64 {
+        batch.finished().iter().find(|r| r.id == id).unwrap().start
+    }
+
+    #[test]
+    fn factors() {
+        let mut policy = Multifactor::default();
+        policy.max_age = 100;
+        policy.set_qos("high", 10);
+        policy.set_qos("normal", 5);
+        policy.set_partition("batch", 2);
+        policy.set_fairshare("alice", 0.25);
+        let job = BatchJob::new(2, 4, 1, 10)
+            .user("alice")
+            .qos("normal")
+            .partition("batch");
+        let factors = policy.factors(&job, 50, 16);
+        assert!(factors.age == 0.5);
+        assert!(factors.size == 0.5);
+        assert!(factors.fairshare == 0.25);
+        assert!(factors.qos == 0.5);
+        assert!(factors.partition == 1.0);
+        let unknown = policy.factors(&BatchJob::new(4, 4, 1, 10), 500, 16);
+        assert!(unknown.age == 1.0 && unknown.size == 1.0);
+        assert!(unknown.fairshare == 1.0 && unknown.qos == 0.0);
+        policy.favor = SizeFavor::Small;
+        assert!(policy.factors(&job, 50, 8).size == 0.0);
+    }
+
+    #[test]
+    fn explain() {
+        let mut policy = Multifactor::new(Weights {
+            age: 0,
+            size: 100,
+            fairshare: 0,
+            qos: 1000,
+            partition: 0,
+        });
+        policy.set_qos("high", 4);
+        let mut batch = BatchScheduler::new(Cluster::homogeneous(2, 2, 1));
+        batch.set_priority(Some(policy));
+        let running = batch.submit(BatchJob::new(2, 2, 1, 10)).unwrap();
+        batch.schedule();
+        let waiting = batch
+            .submit(BatchJob::new(1, 2, 1, 10).qos("high"))
+            .unwrap();
+        batch.schedule();
+        assert!(batch.explain(running) == Err(BatchError::UnknownJob(running)));
+        let explanation = batch.explain(waiting).unwrap();
+        assert!(explanation.contributions.size == 50.0);
+        assert!(explanation.contributions.qos == 1000.0);
+        assert!(explanation.priority == 1050.0);
+        assert!(
+            explanation.to_string()
+                == "job 2 priority 1050 = age 0 + size 50 + fairshare 0 + qos 1000 + partition 0"
+        );
+    }
+
+    #[test]
+    fn qos_order() {
+        let mut policy = Multifactor::default();
+        policy.set_qos("high", 10);
+        policy.set_qos("normal", 1);
+        let mut batch = BatchScheduler::new(Cluster::homogeneous(1, 1, 1));
+        batch.set_priority(Some(policy));
+        batch.submit(BatchJob::new(1, 1, 1, 10)).unwrap();
+        batch.schedule();
+        let normal = batch
+            .submit(BatchJob::new(1, 1, 1, 10).qos("normal"))
+            .unwrap();
+        let high = batch
+            .submit(BatchJob::new(1, 1, 1, 10).qos("high"))
+            .unwrap();
+        batch.run();
+        assert!(start(&batch, high) == 10);
+        assert!(start(&batch, normal) == 20);
+    }
+
+    #[test]
+    fn fairshare_order() {
+        let mut policy = Multifactor::default();
+        policy.set_fairshare("heavy", 0.1);
+        policy.set_fairshare("light", 0.9);
+        let mut batch = BatchScheduler::new(Cluster::homogeneous(1, 1, 1));
+        batch.set_priority(Some(policy));
+        batch.submit(BatchJob::new(1, 1, 1, 10)).unwrap();
+        let heavy = batch
+            .submit(BatchJob::new(1, 1, 1, 10).user("heavy"))
+            .unwrap();
+        let light = batch
+            .submit(BatchJob::new(1, 1, 1, 10).user("light"))
+            .unwrap();
+        batch.run();
+        assert!(start(&batch, light) < start(&batch, heavy));
+    }
+
+    #[test]
+    fn fcfs_without_policy() {
+        let mut batch = BatchScheduler::new(Cluster::homogeneous(1, 1, 1));
+        batch.submit(BatchJob::new(1, 1, 1, 10)).unwrap();
+        let first = batch.submit(BatchJob::new(1, 1, 1, 10)).unwrap();
+        let second = batch
+            .submit(BatchJob::new(1, 1, 1, 10).qos("high"))
+            .unwrap();
+        batch.run();
+        assert!(start(&batch, first) < start(&batch, second));
+    }
+}
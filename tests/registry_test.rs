@@ -0,0 +1,75 @@
+#[cfg(test)]
+mod tests {
+
+    use filasse::device::*;
+    use filasse::job::*;
+    use filasse::registry::*;
+    use filasse::scheduler::*;
+
+    #[test]
+    fn states() {
+        let mut sched = Scheduler::new(1);
+        let disk = sched.add_device(Device::default());
+        sched.add_to_scheduler(&mut Job::new(0, 0, 1, 0));
+        sched.add_to_scheduler(&mut Job::new(0, 0, 5, 0));
+        sched.add_to_scheduler(&mut Job::new(0, 0, 5, 0));
+        sched.process();
+        sched.request_io(disk, 10);
+        sched.dispatch();
+        assert!(sched.state(1) == Some(JobState::Zombie));
+        assert!(sched.state(2) == Some(JobState::Blocked));
+        assert!(sched.state(3) == Some(JobState::Running));
+        assert!(sched.blocked().front().unwrap().pid == 2);
+        sched.reap_init();
+        assert!(sched.state(1) == Some(JobState::Terminated));
+        assert!(sched.state(9).is_none());
+    }
+
+    #[test]
+    fn counts() {
+        let mut sched = Scheduler::default();
+        sched.add_to_scheduler(&mut Job::default());
+        sched.add_to_scheduler(&mut Job::default());
+        sched.add_to_scheduler(&mut Job::default());
+        sched.suspend(3).unwrap();
+        let counts = sched.registry().counts();
+        assert!(counts[&JobState::Ready] == 2);
+        assert!(counts[&JobState::SuspendedReady] == 1);
+        assert!(sched.registry().count(JobState::Running) == 0);
+        assert!(sched.registry().len() == 3);
+    }
+
+    #[test]
+    fn filter() {
+        let mut sched = Scheduler::default();
+        sched.add_to_scheduler(&mut Job::new(0, 0, 5, 1));
+        sched.add_to_scheduler(&mut Job::new(0, 0, 5, 4));
+        sched.add_to_scheduler(&mut Job::new(0, 0, 5, 9));
+        let parent = sched.dispatch().unwrap();
+        let child = sched.fork(parent, &mut Job::new(0, 0, 5, 3)).unwrap();
+        let pids = |filter: Filter| -> Vec<u64> {
+            sched
+                .registry()
+                .filter(&filter)
+                .iter()
+                .map(|entry| entry.pid)
+                .collect()
+        };
+        assert!(pids(Filter::new().state(JobState::Ready)) == vec![2, 3, child]);
+        assert!(pids(Filter::new().parent(parent)) == vec![child]);
+        assert!(pids(Filter::new().priority(3..=5)) == vec![2, child]);
+        assert!(pids(Filter::new().state(JobState::Ready).priority(0..=3)) == vec![child]);
+    }
+
+    #[test]
+    fn renice_and_reparent() {
+        let mut sched = Scheduler::new(1);
+        sched.add_to_scheduler(&mut Job::new(0, 0, 1, 0));
+        let parent = sched.dispatch().unwrap();
+        let child = sched.fork(parent, &mut Job::new(0, 0, 5, 0)).unwrap();
+        sched.renice(child, 6).unwrap();
+        assert!(sched.registry().get(child).unwrap().priority == 6);
+        sched.process();
+        assert!(sched.registry().get(child).unwrap().parent == INIT_PID);
+    }
+}
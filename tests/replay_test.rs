@@ -0,0 +1,116 @@
+#[cfg(test)]
+mod tests {
+
+    use filasse::batch::*;
+    use filasse::eventlog::*;
+    use filasse::job::*;
+    use filasse::observer::*;
+    use filasse::policy::*;
+    use filasse::replay::*;
+    use filasse::report::*;
+    use filasse::scheduler::*;
+    use std::sync::{Arc, Mutex};
+
+    fn jobs() -> Vec<BatchJob> {
+        vec![
+            BatchJob::new(2, 1, 0, 100),
+            BatchJob::new(4, 1, 0, 50).submit(10),
+            BatchJob::new(1, 1, 0, 20).submit(20),
+        ]
+    }
+
+    #[test]
+    fn divergences() {
+        let a = vec![Decision { time: 0, job: 1 }, Decision { time: 0, job: 2 }];
+        let b = vec![Decision { time: 0, job: 2 }, Decision { time: 0, job: 1 }];
+        assert!(divergence(&a, &b).is_none());
+        let c = vec![Decision { time: 0, job: 1 }, Decision { time: 5, job: 2 }];
+        assert!(
+            divergence(&a, &c)
+                == Some(Divergence {
+                    index: 1,
+                    recorded: Some(Decision { time: 0, job: 2 }),
+                    replayed: Some(Decision { time: 5, job: 2 }),
+                })
+        );
+        let divergence = divergence(&a, &a[..1]).unwrap();
+        assert!(divergence.index == 1 && divergence.replayed.is_none());
+        assert!(
+            divergence.to_string() == "decision 1 differs : recorded job 2 at 0, replayed nothing"
+        );
+    }
+
+    #[test]
+    fn replay_trace_of_policy() {
+        let jobs = jobs();
+        let recorded = trace(&Policy::Fcfs.simulate(&jobs, 4).unwrap().records);
+        assert!(trace_decisions(&recorded).unwrap().len() == 3);
+        assert!(replay_trace(Policy::Fcfs, &jobs, 4, &recorded)
+            .unwrap()
+            .is_none());
+        let divergence = replay_trace(Policy::Easy, &jobs, 4, &recorded)
+            .unwrap()
+            .unwrap();
+        assert!(divergence.index == 1);
+        assert!(divergence.recorded == Some(Decision { time: 100, job: 2 }));
+        assert!(divergence.replayed == Some(Decision { time: 20, job: 3 }));
+    }
+
+    #[test]
+    fn invalid_trace() {
+        assert!(
+            trace_decisions("time,event,job,processors,exit\n0,start,x,1,\n")
+                == Err(ReplayError::Trace {
+                    line: 2,
+                    message: "invalid job".to_string(),
+                })
+        );
+        assert!(matches!(
+            trace_decisions("0,run,1,1,\n"),
+            Err(ReplayError::Trace { line: 1, .. })
+        ));
+        assert!(matches!(
+            replay_trace(Policy::RoundRobin { quantum: 10 }, &jobs(), 2, ""),
+            Err(ReplayError::Policy(PolicyError::Cpus { .. }))
+        ));
+    }
+
+    #[test]
+    fn replay_event_log() {
+        let records = Arc::new(Mutex::new(Records::default()));
+        let mut sched = Scheduler::new(10);
+        sched.add_observer(records.clone());
+        sched.add_to_scheduler(&mut Job::new(0, 0, 15, 0));
+        sched.add_to_scheduler(&mut Job::new(0, 0, 5, 0));
+        sched.round_robin();
+        let records = records.lock().unwrap().0.clone();
+        let jobs = log_jobs(&records);
+        assert!(jobs.len() == 2 && jobs[0].runtime == 15 && jobs[1].runtime == 5);
+        assert!(
+            log_decisions(&records)
+                == vec![
+                    Decision { time: 0, job: 1 },
+                    Decision { time: 10, job: 2 },
+                    Decision { time: 15, job: 1 },
+                ]
+        );
+        assert!(replay_log(Policy::RoundRobin { quantum: 10 }, 1, &records)
+            .unwrap()
+            .is_none());
+        let divergence = replay_log(Policy::RoundRobin { quantum: 20 }, 1, &records)
+            .unwrap()
+            .unwrap();
+        assert!(divergence.index == 1);
+        assert!(divergence.replayed == Some(Decision { time: 15, job: 2 }));
+        assert!(replay_log(Policy::Fcfs, 1, &records).unwrap().is_some());
+    }
+
+    #[derive(Default)]
+    struct Records(Vec<EventRecord>);
+
+    impl SchedulerObserver for Records {
+        fn on_event(&mut self, event: Event, transition: &Transition) {
+            self.0.push(EventRecord::new(event, transition));
+        }
+    }
+}
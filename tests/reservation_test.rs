@@ -0,0 +1,191 @@
+#[cfg(test)]
+mod tests {
+
+    use filasse::backfill::*;
+    use filasse::batch::*;
+    use filasse::cluster::*;
+    use filasse::reservation::*;
+
+    fn start(batch: &BatchScheduler, id: u64) -> u64 {
+        batch.finished().iter().find(|r| r.id == id).unwrap().start
+    }
+
+    #[test]
+    fn maintenance_drain() {
+        let mut batch = BatchScheduler::new(Cluster::homogeneous(2, 1, 1));
+        batch
+            .reserve_nodes("maintenance", 50, 100, vec![0, 1], Access::Maintenance)
+            .unwrap();
+        let long = batch.submit(BatchJob::new(1, 1, 1, 60)).unwrap();
+        batch.schedule();
+        assert!(batch.running().is_empty());
+        assert!(batch.next_event() == Some(100));
+        batch.run();
+        assert!(start(&batch, long) == 100);
+    }
+
+    #[test]
+    fn backfill_before_maintenance() {
+        let mut batch = BatchScheduler::new(Cluster::homogeneous(2, 1, 1));
+        batch.set_backfill(Backfill::Easy);
+        batch.set_grace(10);
+        batch
+            .reserve_nodes("maintenance", 50, 100, vec![0, 1], Access::Maintenance)
+            .unwrap();
+        let long = batch.submit(BatchJob::new(1, 1, 1, 60)).unwrap();
+        let short = batch.submit(BatchJob::new(2, 1, 1, 40)).unwrap();
+        batch.run();
+        assert!(batch.backfilled() == 1);
+        assert!(start(&batch, short) == 0);
+        assert!(start(&batch, long) == 100);
+    }
+
+    #[test]
+    fn grace_before_maintenance() {
+        let mut batch = BatchScheduler::new(Cluster::homogeneous(2, 1, 1));
+        batch
+            .reserve_nodes("maintenance", 50, 100, vec![0, 1], Access::Maintenance)
+            .unwrap();
+        // Its walltime ends before the window, but a timed-out job may run the grace period longer.
+        let overrun = batch
+            .submit(BatchJob::new(1, 1, 1, 40).runtime(100))
+            .unwrap();
+        batch.run();
+        assert!(start(&batch, overrun) == 100);
+        let record = batch.finished().iter().find(|r| r.id == overrun).unwrap();
+        assert!(record.end == 100 + 40 + batch.grace());
+    }
+
+    #[test]
+    fn heterogeneous_nodes() {
+        let mut cluster = Cluster::new();
+        let large = cluster.add_node(4, 8);
+        let small = cluster.add_node(2, 4);
+        let mut batch = BatchScheduler::new(cluster);
+        let id = batch
+            .reserve_nodes(
+                "maintenance",
+                0,
+                100,
+                vec![large, small],
+                Access::Maintenance,
+            )
+            .unwrap();
+        let reservation = batch.reservations().iter().find(|r| r.id == id).unwrap();
+        assert!(
+            reservation.allocations
+                == vec![
+                    Allocation {
+                        nodes: vec![large],
+                        cores: 4,
+                        memory: 8,
+                    },
+                    Allocation {
+                        nodes: vec![small],
+                        cores: 2,
+                        memory: 4,
+                    },
+                ]
+        );
+        let too_large = Allocation {
+            nodes: vec![large, small],
+            cores: 3,
+            memory: 1,
+        };
+        assert!(
+            batch.reserve_cores("late", 100, 200, too_large, Access::Maintenance)
+                == Err(BatchError::Unsatisfiable(Resources::new(1, 3, 1)))
+        );
+        let job = batch.submit(BatchJob::new(2, 2, 4, 10)).unwrap();
+        batch.run();
+        assert!(start(&batch, job) == 100);
+    }
+
+    #[test]
+    fn users() {
+        let mut batch = BatchScheduler::new(Cluster::homogeneous(2, 1, 1));
+        batch
+            .reserve_nodes(
+                "alice",
+                0,
+                100,
+                vec![0],
+                Access::Users(vec!["alice".to_string()]),
+            )
+            .unwrap();
+        let bob = batch
+            .submit(BatchJob::new(1, 1, 1, 10).user("bob"))
+            .unwrap();
+        batch.schedule();
+        assert!(batch.running()[0].allocation.nodes == vec![1]);
+        let other = batch
+            .submit(BatchJob::new(1, 1, 1, 10).user("bob"))
+            .unwrap();
+        let alice = batch
+            .submit(BatchJob::new(1, 1, 1, 10).user("alice"))
+            .unwrap();
+        batch.set_backfill(Backfill::Easy);
+        batch.run();
+        assert!(start(&batch, bob) == 0);
+        assert!(start(&batch, alice) == 0);
+        assert!(start(&batch, other) == 10);
+    }
+
+    #[test]
+    fn accounts() {
+        let mut batch = BatchScheduler::new(Cluster::homogeneous(1, 4, 4));
+        batch
+            .reserve_cores(
+                "physics",
+                0,
+                100,
+                Allocation {
+                    nodes: vec![0],
+                    cores: 2,
+                    memory: 2,
+                },
+                Access::Accounts(vec!["physics".to_string()]),
+            )
+            .unwrap();
+        let outside = batch.submit(BatchJob::new(1, 3, 1, 10)).unwrap();
+        let inside = batch
+            .submit(BatchJob::new(1, 3, 1, 10).account("physics"))
+            .unwrap();
+        batch.set_backfill(Backfill::Easy);
+        batch.run();
+        assert!(start(&batch, inside) == 0);
+        assert!(start(&batch, outside) == 100);
+    }
+
+    #[test]
+    fn calendar() {
+        let mut batch = BatchScheduler::new(Cluster::homogeneous(2, 4, 4));
+        let late = batch
+            .reserve_nodes("late", 200, 300, vec![0], Access::Maintenance)
+            .unwrap();
+        let early = batch
+            .reserve_nodes("early", 0, 100, vec![0, 1], Access::Maintenance)
+            .unwrap();
+        assert!(batch.reservations()[0].id == early);
+        assert!(batch.calendar(150, 250).len() == 1);
+        assert!(batch.calendar(0, 1000).len() == 2);
+        assert!(
+            batch.reserve_nodes("again", 50, 60, vec![1], Access::Maintenance)
+                == Err(BatchError::ReservationConflict(early))
+        );
+        assert!(
+            batch.reserve_nodes("empty", 10, 10, vec![1], Access::Maintenance)
+                == Err(BatchError::EmptyWindow { start: 10, end: 10 })
+        );
+        assert!(
+            batch.reserve_nodes("none", 10, 20, vec![5], Access::Maintenance)
+                == Err(BatchError::UnknownNode(5))
+        );
+        assert!(batch.cancel_reservation(late).unwrap().name == "late");
+        assert!(batch.cancel_reservation(late) == Err(BatchError::UnknownReservation(late)));
+        let next = batch
+            .reserve_nodes("next", 200, 300, vec![0], Access::Maintenance)
+            .unwrap();
+        assert!(next != late);
+    }
+}
@@ -37,7 +37,7 @@ mod tests {
         sched.add_to_scheduler(&mut Job::default());
         assert!(sched.pid_count() == 2);
         assert!(
-            sched.queue().get(0).unwrap().state
+            sched.queue().front().unwrap().state
                 == Ready {
                     duration: 0,
                     priority: 0
@@ -57,13 +57,86 @@ mod tests {
         let mut sched = Scheduler::new(1);
         sched.add_to_scheduler(&mut Job::new(1, 2, 1, 0));
         assert!(
-            sched.queue().get(0).unwrap().state
+            sched.queue().front().unwrap().state
                 == Ready {
                     duration: 1,
                     priority: 0
                 }
         );
         sched.process();
-        assert!(sched.zombie().get(0).unwrap().state == Zombie {});
+        assert!(sched.zombie().front().unwrap().state == Zombie {});
+    }
+
+    #[test]
+    fn fork() {
+        let mut sched = Scheduler::new(1);
+        sched.add_to_scheduler(&mut Job::new(7, 7, 5, 0));
+        sched.add_to_scheduler(&mut Job::new(7, 7, 5, 0));
+        assert!(sched.queue().front().unwrap().parent == INIT_PID);
+        assert!(sched.fork(1, &mut Job::default()) == Err(SchedulerError::NotRunning(1)));
+        assert!(sched.fork(9, &mut Job::default()) == Err(SchedulerError::UnknownPid(9)));
+        let parent = sched.dispatch().unwrap();
+        let child = sched.fork(parent, &mut Job::default()).unwrap();
+        assert!(child == 3);
+        assert!(sched.children(parent) == vec![child]);
+        assert!(sched.parent(child) == Some(parent));
+    }
+
+    #[test]
+    fn wait() {
+        let mut sched = Scheduler::new(1);
+        sched.add_to_scheduler(&mut Job::new(0, 0, 5, 0));
+        let parent = sched.dispatch().unwrap();
+        assert!(sched.wait(parent) == Err(SchedulerError::NoChild(parent)));
+        let child = sched.fork(parent, &mut Job::new(0, 0, 1, 0)).unwrap();
+        assert!(sched.wait(parent) == Ok(None));
+        sched.process();
+        sched.process();
+        assert!(sched.zombie().front().unwrap().pid == child);
+        let reaped = sched.waitpid(parent, child).unwrap().unwrap();
+        assert!(reaped.pid == child);
+        assert!(sched.zombie().is_empty());
+        assert!(sched.terminated().front().unwrap().pid == child);
+    }
+
+    #[test]
+    fn waitpid_not_child() {
+        let mut sched = Scheduler::new(1);
+        sched.add_to_scheduler(&mut Job::new(0, 0, 5, 0));
+        sched.add_to_scheduler(&mut Job::new(0, 0, 5, 0));
+        assert!(
+            sched.waitpid(1, 2)
+                == Err(SchedulerError::NotChild {
+                    parent: 1,
+                    child: 2
+                })
+        );
+        assert!(sched.waitpid(1, 9) == Err(SchedulerError::UnknownPid(9)));
+    }
+
+    #[test]
+    fn orphans() {
+        let mut sched = Scheduler::new(1);
+        sched.add_to_scheduler(&mut Job::new(0, 0, 1, 0));
+        let parent = sched.dispatch().unwrap();
+        let child = sched.fork(parent, &mut Job::new(0, 0, 3, 0)).unwrap();
+        sched.dispatch();
+        let sibling = sched.fork(parent, &mut Job::new(0, 0, 3, 0)).unwrap();
+        assert!(sched.descendants(parent) == vec![child, sibling]);
+        sched.process();
+        assert!(sched.parent(child) == Some(INIT_PID));
+        assert!(sched.children(INIT_PID) == vec![parent, child, sibling]);
+        assert!(sched.reap_init() == 1);
+        assert!(sched.zombie().is_empty());
+    }
+
+    #[test]
+    fn round_robin_reaps() {
+        let mut sched = Scheduler::new(1);
+        sched.add_to_scheduler(&mut Job::new(0, 0, 2, 0));
+        sched.add_to_scheduler(&mut Job::new(0, 0, 1, 0));
+        sched.round_robin();
+        assert!(sched.zombie().is_empty());
+        assert!(sched.terminated().len() == 2);
     }
 }
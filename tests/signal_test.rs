@@ -0,0 +1,109 @@
+#[cfg(test)]
+mod tests {
+
+    use filasse::job::*;
+    use filasse::scheduler::*;
+    use filasse::signal::*;
+
+    #[test]
+    fn default_actions() {
+        assert!(Signal::Kill.default_action() == Action::Terminate);
+        assert!(Signal::Term.default_action() == Action::Terminate);
+        assert!(Signal::Stop.default_action() == Action::Stop);
+        assert!(Signal::Cont.default_action() == Action::Continue);
+        assert!(Signal::Chld.default_action() == Action::Ignore);
+        assert!(Signal::User(1).default_action() == Action::Terminate);
+    }
+
+    #[test]
+    fn user_signals() {
+        assert!(Signal::user(31) == Some(Signal::User(31)));
+        assert!(Signal::user(32).is_none());
+        assert!(!Signal::User(32).is_valid());
+        let mask = SignalSet::empty()
+            .with(Signal::User(0))
+            .with(Signal::User(32));
+        assert!(mask.contains(Signal::User(0)));
+        assert!(!mask.contains(Signal::User(32)));
+        assert!(mask == SignalSet::empty().with(Signal::User(0)));
+        let mut sched = Scheduler::default();
+        sched.add_to_scheduler(&mut Job::new(0, 0, 5, 0));
+        assert!(
+            sched.send_signal(1, Signal::User(32))
+                == Err(SchedulerError::InvalidSignal(Signal::User(32)))
+        );
+        assert!(
+            sched.set_signal_handler(1, Signal::User(40), Disposition::Catch)
+                == Err(SchedulerError::InvalidSignal(Signal::User(40)))
+        );
+        assert!(sched.queue().front().unwrap().pid == 1);
+    }
+
+    #[test]
+    fn stop_and_cont() {
+        let mut sched = Scheduler::default();
+        sched.add_to_scheduler(&mut Job::new(0, 0, 5, 0));
+        sched.add_to_scheduler(&mut Job::new(0, 0, 5, 0));
+        sched.dispatch();
+        sched.send_signal(1, Signal::Stop).unwrap();
+        assert!(sched.running().is_none());
+        assert!(sched.stopped().front().unwrap().pid == 1);
+        sched.send_signal(1, Signal::Cont).unwrap();
+        assert!(sched.stopped().is_empty());
+        assert!(sched.queue().back().unwrap().pid == 1);
+    }
+
+    #[test]
+    fn kill() {
+        let mut sched = Scheduler::default();
+        sched.add_to_scheduler(&mut Job::new(0, 0, 5, 0));
+        sched.send_signal(1, Signal::Stop).unwrap();
+        sched.send_signal(1, Signal::Kill).unwrap();
+        assert!(sched.stopped().is_empty());
+        assert!(sched.zombie().front().unwrap().pid == 1);
+        assert!(sched.send_signal(9, Signal::Kill) == Err(SchedulerError::UnknownPid(9)));
+    }
+
+    #[test]
+    fn chld_to_parent() {
+        let mut sched = Scheduler::default();
+        sched.add_to_scheduler(&mut Job::new(0, 0, 500, 0));
+        let parent = sched.dispatch().unwrap();
+        let child = sched.fork(parent, &mut Job::default()).unwrap();
+        sched
+            .set_signal_handler(parent, Signal::Chld, Disposition::Catch)
+            .unwrap();
+        sched.send_signal(child, Signal::Term).unwrap();
+        assert!(sched.signal_state(parent).unwrap().caught == vec![Signal::Chld]);
+        assert!(sched.running().unwrap().pid == parent);
+    }
+
+    #[test]
+    fn mask() {
+        let mut sched = Scheduler::default();
+        sched.add_to_scheduler(&mut Job::new(0, 0, 5, 0));
+        let mask = SignalSet::empty().with(Signal::Term).with(Signal::Kill);
+        sched.set_signal_mask(1, mask).unwrap();
+        sched.send_signal(1, Signal::Term).unwrap();
+        assert!(sched.queue().len() == 1);
+        assert!(sched.signal_state(1).unwrap().pending == vec![Signal::Term]);
+        sched.set_signal_mask(1, SignalSet::empty()).unwrap();
+        assert!(sched.zombie().front().unwrap().pid == 1);
+    }
+
+    #[test]
+    fn kill_cannot_be_ignored() {
+        let mut sched = Scheduler::default();
+        sched.add_to_scheduler(&mut Job::new(0, 0, 5, 0));
+        sched
+            .set_signal_handler(1, Signal::Kill, Disposition::Ignore)
+            .unwrap();
+        sched
+            .set_signal_handler(1, Signal::Term, Disposition::Ignore)
+            .unwrap();
+        sched.send_signal(1, Signal::Term).unwrap();
+        assert!(sched.queue().len() == 1);
+        sched.send_signal(1, Signal::Kill).unwrap();
+        assert!(sched.queue().is_empty());
+    }
+}
@@ -0,0 +1,79 @@
+#[cfg(test)]
+mod tests {
+
+    use filasse::dependency::*;
+    use filasse::job::*;
+    use filasse::scheduler::*;
+    use filasse::signal::*;
+
+    fn workload() -> Scheduler {
+        let mut sched = Scheduler::new(10);
+        sched.add_to_scheduler(&mut Job::new(0, 0, 35, 2));
+        sched.add_to_scheduler(&mut Job::new(0, 0, 20, 1));
+        sched.add_to_scheduler(&mut Job::new(0, 0, 15, 0));
+        sched
+            .add_with_dependencies(&mut Job::new(0, 0, 10, 0), vec![Dependency::AfterOk(2)])
+            .unwrap();
+        sched.set_time_limit(1, 30).unwrap();
+        sched.set_owner(2, "alice").unwrap();
+        sched.hold(3).unwrap();
+        sched
+            .set_signal_handler(2, Signal::User(1), Disposition::Catch)
+            .unwrap();
+        sched
+    }
+
+    fn run(sched: &mut Scheduler, quanta: usize) {
+        for _ in 0..quanta {
+            if sched.dispatch().is_none() {
+                break;
+            }
+            sched.process();
+            sched.reap_init();
+        }
+    }
+
+    #[test]
+    fn resume() {
+        let mut reference = workload();
+        run(&mut reference, 3);
+        let snapshot = reference.snapshot();
+        let mut restored = Scheduler::restore(&snapshot).unwrap();
+        assert!(restored.clock() == reference.clock());
+        assert!(restored.pid_count() == reference.pid_count());
+        assert!(restored.queue() == reference.queue());
+        assert!(restored.held() == reference.held());
+        assert!(restored.waiting() == reference.waiting());
+        assert!(restored.dependencies(4) == reference.dependencies(4));
+        assert!(restored.time_limit(1) == reference.time_limit(1));
+        assert!(restored.owner(2) == Some("alice"));
+        assert!(restored.signal_state(2) == reference.signal_state(2));
+        run(&mut reference, 10);
+        run(&mut restored, 10);
+        assert!(restored.clock() == reference.clock());
+        assert!(restored.registry() == reference.registry());
+        assert!(restored.queue() == reference.queue());
+        assert!(restored.zombie() == reference.zombie());
+    }
+
+    #[test]
+    fn invalid() {
+        let snapshot = workload().snapshot();
+        let other = snapshot.replacen(
+            &format!("\"version\":{}", SNAPSHOT_VERSION),
+            &format!("\"version\":{}", SNAPSHOT_VERSION + 1),
+            1,
+        );
+        assert!(
+            Scheduler::restore(&other).unwrap_err() == SnapshotError::Version(SNAPSHOT_VERSION + 1)
+        );
+        assert!(matches!(
+            Scheduler::restore("{\"version\":1}"),
+            Err(SnapshotError::Parse(_))
+        ));
+        assert!(matches!(
+            Scheduler::restore("checkpoint"),
+            Err(SnapshotError::Parse(_))
+        ));
+    }
+}
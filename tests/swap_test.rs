@@ -0,0 +1,91 @@
+#[cfg(test)]
+mod tests {
+
+    use filasse::device::*;
+    use filasse::job::*;
+    use filasse::scheduler::*;
+    use filasse::swap::*;
+
+    #[test]
+    fn suspend_and_resume() {
+        let mut sched = Scheduler::default();
+        sched.add_to_scheduler(&mut Job::new(0, 0, 5, 0));
+        sched.add_to_scheduler(&mut Job::new(0, 0, 5, 0));
+        sched.suspend(1).unwrap();
+        assert!(sched.queue().len() == 1);
+        assert!(sched.suspended_ready().front().unwrap().pid == 1);
+        sched.resume(1).unwrap();
+        assert!(sched.queue().back().unwrap().pid == 1);
+        assert!(
+            sched.resume(1)
+                == Err(SchedulerError::IllegalState {
+                    pid: 1,
+                    state: JobState::Ready
+                })
+        );
+        assert!(sched.suspend(9) == Err(SchedulerError::UnknownPid(9)));
+    }
+
+    #[test]
+    fn suspended_blocked_io() {
+        let mut sched = Scheduler::default();
+        let disk = sched.add_device(Device::default());
+        sched.add_to_scheduler(&mut Job::new(0, 0, 5, 0));
+        sched.request_io(disk, 10);
+        sched.suspend(1).unwrap();
+        assert!(sched.suspended_blocked().front().unwrap().pid == 1);
+        sched.complete_io(disk);
+        assert!(sched.suspended_blocked().is_empty());
+        assert!(sched.suspended_ready().front().unwrap().pid == 1);
+    }
+
+    #[test]
+    fn choose() {
+        let candidates = [
+            Candidate {
+                pid: 1,
+                priority: 1,
+                remaining: 10,
+                blocked: false,
+            },
+            Candidate {
+                pid: 2,
+                priority: 5,
+                remaining: 30,
+                blocked: true,
+            },
+            Candidate {
+                pid: 3,
+                priority: 1,
+                remaining: 20,
+                blocked: false,
+            },
+        ];
+        let policy = |victim| SwapPolicy::new(1, victim);
+        assert!(policy(Victim::BlockedFirst).choose(&candidates) == Some(2));
+        assert!(policy(Victim::LowestPriority).choose(&candidates) == Some(3));
+        assert!(policy(Victim::LongestRemaining).choose(&candidates) == Some(2));
+    }
+
+    #[test]
+    fn medium_term() {
+        let mut sched = Scheduler::default();
+        sched.set_swap_policy(Some(SwapPolicy::new(2, Victim::LowestPriority)));
+        sched.add_to_scheduler(&mut Job::new(0, 0, 5, 3));
+        sched.add_to_scheduler(&mut Job::new(0, 0, 5, 1));
+        sched.add_to_scheduler(&mut Job::new(0, 0, 5, 2));
+        sched.dispatch();
+        assert!(sched.medium_term() == vec![Swap::Out(2)]);
+        sched.process();
+        assert!(sched.zombie().front().unwrap().pid == 1);
+        assert!(sched.medium_term() == vec![Swap::In(2)]);
+        assert!(sched.queue().len() == 2);
+    }
+
+    #[test]
+    fn no_policy() {
+        let mut sched = Scheduler::default();
+        sched.add_to_scheduler(&mut Job::default());
+        assert!(sched.medium_term().is_empty());
+    }
+}
@@ -0,0 +1,54 @@
+#[cfg(test)]
+mod tests {
+
+    use filasse::batch::*;
+    use filasse::cluster::*;
+    use filasse::swf::*;
+
+    const WORKLOAD: &str = "; comment
+10 100 -1 50 2 -1 -1 4 80 16 1 7 -1 -1 -1 -1 -1 -1
+
+11 130 -1 20 2 -1 -1 -1 -1 -1 1 -1 -1 -1 -1 -1 -1 -1
+";
+
+    #[test]
+    fn parse_jobs() {
+        let jobs = parse(WORKLOAD).unwrap();
+        assert!(jobs.len() == 2);
+        assert!(jobs[0].resources == Resources::new(4, 1, 16));
+        assert!(jobs[0].walltime == 80 && jobs[0].runtime == 50);
+        assert!(jobs[0].submit == 0 && jobs[1].submit == 30);
+        assert!(jobs[0].user == Some("u7".to_string()));
+        assert!(jobs[1].resources == Resources::new(2, 1, 0));
+        assert!(jobs[1].walltime == 20);
+        assert!(jobs[1].user.is_none());
+    }
+
+    #[test]
+    fn errors() {
+        assert!(parse("1 2 3") == Err(SwfError::Fields { line: 1, count: 3 }));
+        assert!(
+            parse("; c\n1 0 -1 x 1 -1 -1 1 -1 -1 1 -1 -1 -1 -1 -1 -1 -1")
+                == Err(SwfError::Field {
+                    line: 2,
+                    field: "run time"
+                })
+        );
+        assert!(parse("1 0 -1 5 -1 -1 -1 -1 -1 -1 1 -1 -1 -1 -1 -1 -1 -1").is_err());
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut batch = BatchScheduler::new(Cluster::homogeneous(4, 1, 16));
+        for job in parse(WORKLOAD).unwrap() {
+            batch.submit(job).unwrap();
+        }
+        batch.run();
+        let text = write(batch.finished());
+        let jobs = parse(&text).unwrap();
+        assert!(jobs.len() == 2);
+        assert!(jobs[0].runtime == 50 && jobs[1].runtime == 20);
+        assert!(jobs[1].submit == 30);
+        assert!(text.lines().nth(2).unwrap().starts_with("2 30 20 20 2"));
+    }
+}
@@ -0,0 +1,134 @@
+#[cfg(test)]
+mod tests {
+    use filasse::dependency::*;
+    use filasse::job::*;
+    use filasse::workflow::*;
+
+    // Example graph of Topcuoglu, Hariri and Wu, 10 tasks on 3 processors.
+    fn example() -> Dag {
+        let mut dag = Dag::new(vec![
+            vec![14, 16, 9],
+            vec![13, 19, 18],
+            vec![11, 13, 19],
+            vec![13, 8, 17],
+            vec![12, 13, 10],
+            vec![13, 16, 9],
+            vec![7, 15, 11],
+            vec![5, 11, 14],
+            vec![18, 12, 20],
+            vec![21, 7, 16],
+        ])
+        .unwrap();
+        let edges = [
+            (0, 1, 18),
+            (0, 2, 12),
+            (0, 3, 9),
+            (0, 4, 11),
+            (0, 5, 14),
+            (1, 7, 19),
+            (1, 8, 16),
+            (2, 6, 23),
+            (3, 7, 27),
+            (3, 8, 23),
+            (4, 8, 13),
+            (5, 7, 15),
+            (6, 9, 17),
+            (7, 9, 11),
+            (8, 9, 13),
+        ];
+        for (from, to, comm) in edges {
+            dag.add_edge(from, to, comm).unwrap();
+        }
+        dag
+    }
+
+    fn is_valid(dag: &Dag, schedule: &Schedule) -> bool {
+        let a = &schedule.assignments;
+        let precedence = dag.edges().iter().all(|e| {
+            let comm = if a[e.from].processor == a[e.to].processor {
+                0
+            } else {
+                e.comm
+            };
+            a[e.from].finish + comm <= a[e.to].start
+        });
+        let overlap = a.iter().any(|x| {
+            a.iter().any(|y| {
+                x.task != y.task
+                    && x.processor == y.processor
+                    && x.start < y.finish
+                    && y.start < x.finish
+            })
+        });
+        precedence && !overlap
+    }
+
+    #[test]
+    fn upward_ranks() {
+        let ranks = example().upward_ranks();
+        assert!((ranks[0] - 108.0).abs() < 1e-9);
+        assert!((ranks[9] - 44.0 / 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn heft() {
+        let dag = example();
+        let schedule = dag.heft().unwrap();
+        assert!(schedule.makespan == 80);
+        assert!(is_valid(&dag, &schedule));
+    }
+
+    #[test]
+    fn cpop() {
+        let dag = example();
+        let schedule = dag.cpop().unwrap();
+        assert!(schedule.makespan == 86);
+        assert!(is_valid(&dag, &schedule));
+    }
+
+    #[test]
+    fn list_scheduling() {
+        let dag = example();
+        let schedule = dag.list_scheduling().unwrap();
+        assert!(is_valid(&dag, &schedule));
+        assert!(schedule.makespan >= schedule.lower_bound);
+    }
+
+    #[test]
+    fn lower_bound() {
+        let dag = example();
+        assert!(dag.lower_bound() == 9 + 13 + 12 + 7);
+        let schedule = dag.heft().unwrap();
+        assert!(schedule.slr() >= 1.0);
+    }
+
+    #[test]
+    fn cycle() {
+        let mut dag = Dag::new(vec![vec![1], vec![1], vec![1]]).unwrap();
+        dag.add_edge(0, 1, 0).unwrap();
+        dag.add_edge(1, 2, 0).unwrap();
+        assert!(dag.add_edge(2, 0, 0).is_err());
+        assert!(dag.edges().len() == 2);
+        assert!(dag.add_edge(0, 3, 0) == Err(WorkflowError::UnknownTask(3)));
+    }
+
+    #[test]
+    fn invalid_costs() {
+        assert!(Dag::new(Vec::new()) == Err(WorkflowError::Empty));
+        assert!(Dag::new(vec![vec![]]) == Err(WorkflowError::NoProcessor));
+        assert!(Dag::new(vec![vec![1, 2], vec![3]]) == Err(WorkflowError::CostShape(1)));
+    }
+
+    #[test]
+    fn from_workflow() {
+        let jobs = vec![
+            (Job::new(0, 0, 10, 0), vec![]),
+            (Job::new(0, 0, 20, 0), vec![Dependency::AfterOk(1)]),
+            (Job::new(0, 0, 5, 0), vec![Dependency::AfterAny(42)]),
+        ];
+        let dag = Dag::from_workflow(&jobs, 1, &[1.0, 2.0], 5).unwrap();
+        assert!(dag.tasks() == 3);
+        assert!(dag.edges().len() == 1);
+        assert!(dag.heft().unwrap().makespan == 15);
+    }
+}
@@ -0,0 +1,148 @@
+#[cfg(test)]
+mod tests {
+
+    use filasse::dependency::*;
+    use filasse::policy::*;
+    use filasse::scheduler::*;
+    use filasse::workload::*;
+
+    const TOML: &str = r#"
+[scheduler]
+policy = "rr"
+quantum = 5
+
+[[jobs]]
+name = "a"
+bursts = [10, 4, 6]
+priority = 2
+
+[[jobs]]
+name = "b"
+arrival = 3
+bursts = [5]
+dependencies = ["a"]
+walltime = 8
+command = "./b --fast"
+"#;
+
+    fn error(text: &str) -> WorkloadError {
+        Workload::parse(text, Format::Toml).unwrap_err()
+    }
+
+    #[test]
+    fn formats() {
+        let workload = Workload::parse(TOML, Format::Toml).unwrap();
+        assert!(workload.policy() == Ok(Policy::RoundRobin { quantum: 5 }));
+        assert!(workload.scheduler.cpus == 1);
+        assert!(workload.jobs[0].cpu() == 16 && workload.jobs[0].elapsed() == 20);
+        assert!(workload.jobs[1].command == Some("./b --fast".to_string()));
+        for format in [Format::Toml, Format::Json, Format::Yaml] {
+            let text = workload.write(format).unwrap();
+            assert!(Workload::parse(&text, format) == Ok(workload.clone()));
+        }
+        let json = r#"{"jobs": [{"name": "x", "bursts": [1]}]}"#;
+        let workload = Workload::parse(json, Format::Json).unwrap();
+        assert!(workload.scheduler == Config::default());
+        assert!(workload.jobs[0].processors == 1);
+        let yaml = "scheduler:\n  cpus: 2\njobs:\n  - name: x\n    bursts: [3]\n";
+        assert!(Workload::parse(yaml, Format::Yaml).unwrap().scheduler.cpus == 2);
+        assert!(Format::of("w.YML") == Some(Format::Yaml));
+        assert!(Format::of("w.swf").is_none());
+    }
+
+    #[test]
+    fn conversions() {
+        let workload = Workload::parse(TOML, Format::Toml).unwrap();
+        assert!(
+            workload.batch_jobs()
+                == Err(WorkloadError::Job {
+                    job: "a".to_string(),
+                    field: "priority",
+                    message: "is not used by simulations".to_string(),
+                })
+        );
+        let mut simulated = workload.clone();
+        simulated.jobs[0].priority = 0;
+        assert!(matches!(
+            simulated.batch_jobs(),
+            Err(WorkloadError::Job {
+                field: "command",
+                ..
+            })
+        ));
+        simulated.jobs[1].command = None;
+        let jobs = simulated.batch_jobs().unwrap();
+        assert!(jobs[0].runtime == 20 && jobs[0].walltime == 20);
+        assert!(jobs[1].submit == 3 && jobs[1].walltime == 8);
+        assert!(jobs[0].dependencies.is_empty() && jobs[1].dependencies == vec![1]);
+        let mut sched = Scheduler::default();
+        let first = sched.pid_count();
+        let pids = sched.add_workflow(workload.workflow(first)).unwrap();
+        assert!(sched.dependencies(pids[1]) == [Dependency::AfterOk(pids[0])]);
+        assert!(sched.registry().get(pids[0]).unwrap().priority == 2);
+    }
+
+    #[test]
+    fn schema() {
+        assert!(matches!(
+            error("[[jobs]]\nname = \"a\"\nburst = [1]\n"),
+            WorkloadError::Parse(_)
+        ));
+        assert!(matches!(
+            error("[[jobs]]\nname = \"a\"\n"),
+            WorkloadError::Parse(_)
+        ));
+        assert!(matches!(
+            error("[[jobs]]\nname = \"a\"\nbursts = [-1]\n"),
+            WorkloadError::Parse(_)
+        ));
+    }
+
+    #[test]
+    fn validation() {
+        let job = |name: &str, extra: &str| {
+            format!("[[jobs]]\nname = \"{}\"\nbursts = [1]\n{}\n", name, extra)
+        };
+        assert!(
+            error(&format!("{}{}", job("a", ""), job("a", "")))
+                == WorkloadError::Job {
+                    job: "a".to_string(),
+                    field: "name",
+                    message: "is not unique".to_string()
+                }
+        );
+        let field = |e: WorkloadError| match e {
+            WorkloadError::Job { field, .. } => field,
+            WorkloadError::Scheduler { field, .. } => field,
+            WorkloadError::Parse(_) => "",
+        };
+        assert!(field(error("[[jobs]]\nname = \"a\"\nbursts = []\n")) == "bursts");
+        assert!(field(error(&job("a", "processors = 2"))) == "processors");
+        assert!(field(error(&job("a", "walltime = 0"))) == "walltime");
+        assert!(field(error(&job("a", "dependencies = [\"z\"]"))) == "dependencies");
+        assert!(field(error(&job("a", "dependencies = [\"a\"]"))) == "dependencies");
+        let cycle = format!(
+            "{}{}{}",
+            job("a", "dependencies = [\"c\"]"),
+            job("b", "dependencies = [\"a\"]"),
+            job("c", "dependencies = [\"b\"]")
+        );
+        assert!(field(error(&cycle)) == "dependencies");
+        let config = |text: &str| field(error(&format!("[scheduler]\n{}\n{}", text, job("a", ""))));
+        assert!(config("policy = \"sjf\"") == "policy");
+        assert!(config("policy = \"rr\"\ncpus = 2") == "cpus");
+        assert!(config("cpus = 0") == "cpus");
+        assert!(config("quantum = 0") == "quantum");
+        assert!(
+            error(&job("a", "processors = 0")).to_string() == "job a: processors: must be positive"
+        );
+    }
+
+    #[test]
+    fn example() {
+        let text = std::fs::read_to_string("workloads/example.toml").unwrap();
+        let workload = Workload::parse(&text, Format::Toml).unwrap();
+        assert!(workload.policy() == Ok(Policy::Easy));
+        assert!(workload.jobs.len() == 4);
+    }
+}